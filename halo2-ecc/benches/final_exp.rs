@@ -0,0 +1,167 @@
+use halo2_base::gates::circuit::{builder::RangeCircuitBuilder, CircuitBuilderStage};
+use halo2_base::gates::RangeChip;
+use halo2_base::halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::bn256::{Fq, Fq12, Fr, G1Affine, G2Affine},
+};
+use halo2_base::utils::modulus;
+use halo2_base::Context;
+use halo2_ecc::bn254::pairing::{prepared_g2::PreparedG2, PairingChip};
+use halo2_ecc::bn254::{final_exp::HardPart, Fp12Chip, FpChip};
+use halo2_ecc::fields::FieldChip;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use criterion::{criterion_group, criterion_main};
+use criterion::{BenchmarkId, Criterion};
+
+use pprof::criterion::{Output, PProfProfiler};
+// Thanks to the example provided by @jebbow in his article
+// https://www.jibbow.com/posts/criterion-flamegraphs/
+
+const K: u32 = 20;
+const LOOKUP_BITS: usize = 19;
+const LIMB_BITS: usize = 88;
+const NUM_LIMBS: usize = 3;
+
+/// Final exponentiation variants to compare: each hard part of [`Fp12Chip::final_exp_with`], and
+/// [`Fp12Chip::final_exp_torus`].
+#[derive(Clone, Copy, Debug)]
+enum Variant {
+    HardPart(HardPart),
+    Torus,
+}
+
+/// Variants to compare; add new ones here.
+const VARIANTS: [Variant; 3] = [
+    Variant::HardPart(HardPart::Scott),
+    Variant::HardPart(HardPart::FuentesCastaneda),
+    Variant::Torus,
+];
+
+/// Loads `a` and computes its final exponentiation; returns the number of advice cells used.
+fn final_exp_bench(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    a: Fq12,
+    variant: Variant,
+) -> usize {
+    let fp_chip = FpChip::<Fr>::new(range, LIMB_BITS, NUM_LIMBS);
+    let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+    let a = fp12_chip.load_private(ctx, a);
+    match variant {
+        Variant::HardPart(hard_part) => fp12_chip.final_exp_with(ctx, a, hard_part),
+        Variant::Torus => fp12_chip.final_exp_torus(ctx, a),
+    };
+    ctx.advice.len()
+}
+
+/// Returns the advice cells of a Miller loop on `(p, q)` with `q` loaded in circuit, and with `q` prepared out of
+/// circuit by [`PreparedG2`].
+fn miller_loop_cells(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    p: G1Affine,
+    q: G2Affine,
+) -> (usize, usize) {
+    let fp_chip = FpChip::<Fr>::new(range, LIMB_BITS, NUM_LIMBS);
+    let chip = PairingChip::new(&fp_chip);
+    let p = chip.load_private_g1(ctx, p);
+
+    let start = ctx.advice.len();
+    let q_assigned = chip.load_private_g2(ctx, q);
+    chip.miller_loop(ctx, &q_assigned, &p);
+    let cells = ctx.advice.len() - start;
+
+    let prepared = PreparedG2::new(q);
+    let start = ctx.advice.len();
+    chip.miller_loop_prepared(ctx, &[(&p, &prepared)]);
+    (cells, ctx.advice.len() - start)
+}
+
+/// Returns the advice cells of `cyclotomic_pow` on a 254-bit exponent with the NAF, and with the 4-NAF of
+/// `cyclotomic_pow_wnaf`.
+fn cyclotomic_pow_cells(ctx: &mut Context<Fr>, range: &RangeChip<Fr>, a: Fq12) -> (usize, usize) {
+    let fp_chip = FpChip::<Fr>::new(range, LIMB_BITS, NUM_LIMBS);
+    let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+    let a = fp12_chip.load_private(ctx, a);
+    let exp = modulus::<Fr>().to_u64_digits();
+
+    let start = ctx.advice.len();
+    fp12_chip.cyclotomic_pow(ctx, a.clone(), exp.clone());
+    let naf_cells = ctx.advice.len() - start;
+
+    let start = ctx.advice.len();
+    fp12_chip.cyclotomic_pow_wnaf(ctx, a, exp, 4);
+    (naf_cells, ctx.advice.len() - start)
+}
+
+/// Prints the advice cells of the operations that are compared, but not timed, next to the final exponentiation.
+fn print_cell_counts(rng: &mut StdRng) {
+    let mut builder = RangeCircuitBuilder::from_stage(CircuitBuilderStage::Mock)
+        .use_k(K as usize)
+        .use_lookup_bits(LOOKUP_BITS);
+    let range = builder.range_chip();
+    let ctx = builder.main(0);
+
+    let (cells, prepared_cells) =
+        miller_loop_cells(ctx, &range, G1Affine::random(&mut *rng), G2Affine::random(&mut *rng));
+    println!("miller_loop: {cells} advice cells, with PreparedG2: {prepared_cells}");
+
+    // map a random element into the cyclotomic subgroup by the easy part of the final exponentiation
+    let p = modulus::<Fq>();
+    let easy_exp = (p.pow(6) - 1u64) * (p.pow(2) + 1u64);
+    let a = Fq12::random(&mut *rng).pow_vartime(easy_exp.to_u64_digits());
+    let (naf_cells, wnaf_cells) = cyclotomic_pow_cells(ctx, &range, a);
+    println!("cyclotomic_pow: {naf_cells} advice cells with NAF, {wnaf_cells} with 4-NAF");
+}
+
+fn final_exp_circuit(
+    stage: CircuitBuilderStage,
+    a: Fq12,
+    variant: Variant,
+) -> (RangeCircuitBuilder<Fr>, usize) {
+    let mut builder =
+        RangeCircuitBuilder::from_stage(stage).use_k(K as usize).use_lookup_bits(LOOKUP_BITS);
+    let range = builder.range_chip();
+    let num_advice_cells = final_exp_bench(builder.main(0), &range, a, variant);
+    (builder, num_advice_cells)
+}
+
+fn bench(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = Fq12::random(&mut rng);
+    // Set `FINAL_EXP_MOCK=1` to also check every variant with `MockProver` before timing it.
+    let mock = std::env::var("FINAL_EXP_MOCK").is_ok_and(|v| v == "1");
+    print_cell_counts(&mut rng);
+
+    let mut group = c.benchmark_group("final-exp-witness-gen");
+    group.sample_size(10);
+    for variant in VARIANTS {
+        let (mut circuit, num_advice_cells) =
+            final_exp_circuit(CircuitBuilderStage::Mock, a, variant);
+        println!("{variant:?}: {num_advice_cells} advice cells");
+        if mock {
+            circuit.calculate_params(Some(20));
+            MockProver::run(K, &circuit, vec![]).unwrap().assert_satisfied();
+        }
+        drop(circuit);
+
+        group.bench_with_input(
+            BenchmarkId::new("final_exp", format!("{variant:?}")),
+            &variant,
+            |b, &variant| {
+                b.iter(|| final_exp_circuit(CircuitBuilderStage::Mock, a, variant));
+            },
+        );
+    }
+    group.finish()
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(10, Output::Flamegraph(None)));
+    targets = bench
+}
+criterion_main!(benches);
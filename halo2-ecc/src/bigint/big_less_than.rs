@@ -1,4 +1,4 @@
-use super::ProperUint;
+use super::{ProperCrtUint, ProperUint};
 use halo2_base::{gates::RangeInstructions, utils::ScalarField, AssignedValue, Context};
 
 // given OverflowInteger<F>'s `a` and `b` of the same shape,
@@ -15,3 +15,23 @@ pub fn assign<F: ScalarField>(
     let (_, underflow) = super::sub::assign(range, ctx, a, b, limb_bits, limb_base);
     underflow
 }
+
+/// Given [`ProperCrtUint`]s `a` and `b` with the same number of limbs, returns the bit `a < b`, comparing
+/// them as BigUints.
+///
+/// For example, with `n` loaded as a constant, `crt(range, ctx, &s, &n, ..)` constrains nothing by itself but
+/// returns whether `s` is in `[0, n)`.
+///
+/// # Assumptions
+/// * `a, b` have the same nonzero number of limbs, each with at most `limb_bits` bits
+/// * `limb_base = 2^limb_bits`
+pub fn crt<F: ScalarField>(
+    range: &impl RangeInstructions<F>,
+    ctx: &mut Context<F>,
+    a: &ProperCrtUint<F>,
+    b: &ProperCrtUint<F>,
+    limb_bits: usize,
+    limb_base: F,
+) -> AssignedValue<F> {
+    assign(range, ctx, a.clone(), b.clone(), limb_bits, limb_base)
+}
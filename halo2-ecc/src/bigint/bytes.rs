@@ -0,0 +1,119 @@
+use super::{ProperCrtUint, ProperUint};
+use halo2_base::{
+    gates::GateInstructions,
+    utils::{biguint_to_fe, BigPrimeField},
+    AssignedValue, Context,
+    QuantumCell::Constant,
+};
+use num_bigint::BigUint;
+use num_traits::One;
+
+/// Packs the little-endian `bytes` into a [`ProperCrtUint`] with `ceil(8 * bytes.len() / limb_bits)` limbs of
+/// `limb_bits` bits each.
+///
+/// Constrains each of `bytes` to be in `[0, 256)`.
+///
+/// # Assumptions
+/// * `bytes` is nonempty
+/// * `limb_bits < F::CAPACITY`
+pub fn from_bytes_le<F: BigPrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<F>,
+    bytes: &[AssignedValue<F>],
+    limb_bits: usize,
+) -> ProperCrtUint<F> {
+    assert!(!bytes.is_empty());
+    // num_to_bits constrains each byte to 8 bits
+    let bits = bytes.iter().flat_map(|byte| gate.num_to_bits(ctx, *byte, 8)).collect::<Vec<_>>();
+    let limbs = bits
+        .chunks(limb_bits)
+        .map(|chunk| {
+            let pow_of_two = gate.pow_of_two()[..chunk.len()].iter().map(|c| Constant(*c));
+            gate.inner_product(ctx, chunk.iter().copied(), pow_of_two)
+        })
+        .collect::<Vec<_>>();
+    let limb_base = biguint_to_fe::<F>(&(BigUint::one() << limb_bits));
+    let mut limb_bases = Vec::with_capacity(limbs.len());
+    limb_bases.push(F::ONE);
+    while limb_bases.len() != limbs.len() {
+        limb_bases.push(limb_base * limb_bases.last().unwrap());
+    }
+    let value = BigUint::from_bytes_le(
+        &bytes.iter().map(|byte| byte.value().get_lower_32() as u8).collect::<Vec<_>>(),
+    );
+    ProperUint(limbs).into_crt(ctx, gate, value, &limb_bases, limb_bits)
+}
+
+/// Same as [`from_bytes_le`], with `bytes` in big-endian order.
+pub fn from_bytes_be<F: BigPrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<F>,
+    bytes: &[AssignedValue<F>],
+    limb_bits: usize,
+) -> ProperCrtUint<F> {
+    let bytes_le = bytes.iter().rev().copied().collect::<Vec<_>>();
+    from_bytes_le(gate, ctx, &bytes_le, limb_bits)
+}
+
+/// Returns exactly `max_bits` little-endian bits of `a`, each as an `AssignedValue` constrained to be a bit.
+///
+/// Each limb is decomposed into `limb_bits` bits whose recomposition is constrained to equal the limb. Bits
+/// past `max_bits` are constrained to be zero, so the circuit is unsatisfiable if `a >= 2^max_bits`.
+///
+/// # Assumptions
+/// * `a` is a proper representation with limbs of `limb_bits` bits
+pub fn to_bits_le<F: BigPrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<F>,
+    a: &ProperCrtUint<F>,
+    limb_bits: usize,
+    max_bits: usize,
+) -> Vec<AssignedValue<F>> {
+    let mut bits = a
+        .limbs()
+        .iter()
+        .flat_map(|limb| gate.num_to_bits(ctx, *limb, limb_bits))
+        .collect::<Vec<_>>();
+    for bit in bits.iter().skip(max_bits) {
+        gate.assert_is_const(ctx, bit, &F::ZERO);
+    }
+    bits.truncate(max_bits);
+    let zero = ctx.load_zero();
+    bits.resize(max_bits, zero);
+    bits
+}
+
+/// Returns the `num_bytes` little-endian bytes of `a`, each as an `AssignedValue` in `[0, 256)`.
+///
+/// Constrains `a < 2^{8 * num_bytes}`, so the circuit is unsatisfiable if `a` does not fit.
+///
+/// # Assumptions
+/// * `a` is a proper representation with limbs of `limb_bits` bits
+pub fn to_bytes_le<F: BigPrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<F>,
+    a: &ProperCrtUint<F>,
+    limb_bits: usize,
+    num_bytes: usize,
+) -> Vec<AssignedValue<F>> {
+    let bits = to_bits_le(gate, ctx, a, limb_bits, 8 * num_bytes);
+    bits.chunks(8)
+        .map(|byte_bits| {
+            let pow_of_two = gate.pow_of_two()[..8].iter().map(|c| Constant(*c));
+            gate.inner_product(ctx, byte_bits.iter().copied(), pow_of_two)
+        })
+        .collect()
+}
+
+/// Same as [`to_bytes_le`], with the output in big-endian order.
+pub fn to_bytes_be<F: BigPrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<F>,
+    a: &ProperCrtUint<F>,
+    limb_bits: usize,
+    num_bytes: usize,
+) -> Vec<AssignedValue<F>> {
+    let mut bytes = to_bytes_le(gate, ctx, a, limb_bits, num_bytes);
+    bytes.reverse();
+    bytes
+}
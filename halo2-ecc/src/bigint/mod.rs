@@ -12,9 +12,11 @@ pub mod big_is_equal;
 pub mod big_is_even;
 pub mod big_is_zero;
 pub mod big_less_than;
+pub mod bytes;
 pub mod carry_mod;
 pub mod check_carry_mod_to_zero;
 pub mod check_carry_to_zero;
+pub mod mod_pow;
 pub mod mul_no_carry;
 pub mod negative;
 pub mod scalar_mul_and_add_no_carry;
@@ -24,6 +26,9 @@ pub mod select_by_indicator;
 pub mod sub;
 pub mod sub_no_carry;
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Clone, Debug, PartialEq, Default)]
 pub enum BigIntStrategy {
     // use existing gates
@@ -0,0 +1,123 @@
+use super::{
+    big_less_than, check_carry_to_zero, mul_no_carry, select, sub_no_carry, CRTInteger,
+    FixedOverflowInteger, OverflowInteger, ProperCrtUint, ProperUint,
+};
+use halo2_base::{
+    gates::{GateInstructions, RangeInstructions},
+    utils::{decompose_bigint, log2_ceil, BigPrimeField},
+    AssignedValue, Context,
+};
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+/// Pads `a` with `k` zero limbs, so that the product of two padded integers is computed without truncation.
+fn pad<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    a: &ProperCrtUint<F>,
+    limb_bits: usize,
+) -> CRTInteger<F> {
+    let k = a.limbs().len();
+    let mut limbs = a.limbs().to_vec();
+    limbs.extend((0..k).map(|_| ctx.load_zero()));
+    CRTInteger::new(OverflowInteger::new(limbs, limb_bits), *a.native(), a.0.value.clone())
+}
+
+/// Returns `a * b (mod modulus)` as a [`ProperCrtUint`] in `[0, modulus)`, where `modulus` is a witness
+/// rather than a fixed constant.
+///
+/// We witness `quot, out` with `k` limbs each and constrain `a * b = modulus * quot + out` as integers and
+/// `out < modulus`. The equality is checked on the `2k`-limb products, which cannot wrap around, so it is
+/// checked modulo `2^{2nk}` and in the native field `F`.
+///
+/// # Assumptions
+/// * `a, b, modulus` have the same number `k` of limbs, each with at most `limb_bits` bits
+/// * `modulus != 0`
+/// * `a * b < modulus * 2^{nk}` (e.g., `a, b < modulus`), otherwise `quot` does not fit in `k` limbs and the
+///   constraints are not satisfied
+/// * `limb_bases[i] = 2^{limb_bits * i}` for `i < k`
+pub fn mul_mod<F: BigPrimeField>(
+    range: &impl RangeInstructions<F>,
+    ctx: &mut Context<F>,
+    a: &ProperCrtUint<F>,
+    b: &ProperCrtUint<F>,
+    modulus: &ProperCrtUint<F>,
+    limb_bits: usize,
+    limb_bases: &[F],
+) -> ProperCrtUint<F> {
+    let k = modulus.limbs().len();
+    assert_eq!(a.limbs().len(), k);
+    assert_eq!(b.limbs().len(), k);
+    let gate = range.gate();
+
+    let (quot_val, out_val) = (&a.0.value * &b.0.value).div_mod_floor(&modulus.0.value);
+    let [quot, out] = [quot_val, out_val].map(|val| {
+        let limbs = ctx.assign_witnesses(decompose_bigint::<F>(&val, k, limb_bits));
+        for limb in &limbs {
+            range.range_check(ctx, *limb, limb_bits);
+        }
+        ProperUint(limbs).into_crt(ctx, gate, val.to_biguint().unwrap(), limb_bases, limb_bits)
+    });
+
+    // out < modulus
+    let out_is_reduced =
+        big_less_than::assign(range, ctx, out.clone(), modulus.clone(), limb_bits, limb_bases[1]);
+    gate.assert_is_const(ctx, &out_is_reduced, &F::ONE);
+
+    // a * b - modulus * quot - out = 0
+    let num_limbs_log2_ceil = log2_ceil(2 * k as u64);
+    let [a_pad, b_pad, mod_pad, quot_pad, out_pad] =
+        [a, b, modulus, &quot, &out].map(|x| pad(ctx, x, limb_bits));
+    let ab = mul_no_carry::crt(gate, ctx, a_pad, b_pad, num_limbs_log2_ceil);
+    let mod_quot = mul_no_carry::crt(gate, ctx, mod_pad, quot_pad, num_limbs_log2_ceil);
+    let diff = sub_no_carry::crt(gate, ctx, ab, mod_quot);
+    let diff = sub_no_carry::crt(gate, ctx, diff, out_pad);
+    debug_assert!(diff.value.is_zero());
+    let limb_base_big = BigInt::one() << limb_bits;
+    check_carry_to_zero::truncate(
+        range,
+        ctx,
+        diff.truncation,
+        limb_bits,
+        limb_bases[1],
+        &limb_base_big,
+    );
+    gate.assert_is_const(ctx, &diff.native, &F::ZERO);
+
+    out
+}
+
+/// Returns `base^e (mod modulus)` as a [`ProperCrtUint`] in `[0, modulus)`, where `e` is given by its
+/// little-endian bits `exp_bits` and `modulus` is a witness rather than a fixed constant.
+///
+/// Uses left-to-right square-and-multiply with [`mul_mod`], selecting the product only when the exponent bit is set.
+///
+/// # Assumptions
+/// * `base, modulus` have the same number `k` of limbs, each with at most `limb_bits` bits
+/// * `modulus != 0`
+/// * every element of `exp_bits` is constrained to be a bit
+/// * `limb_bases[i] = 2^{limb_bits * i}` for `i < k`
+pub fn crt<F: BigPrimeField>(
+    range: &impl RangeInstructions<F>,
+    ctx: &mut Context<F>,
+    base: &ProperCrtUint<F>,
+    exp_bits: &[AssignedValue<F>],
+    modulus: &ProperCrtUint<F>,
+    limb_bits: usize,
+    limb_bases: &[F],
+) -> ProperCrtUint<F> {
+    let k = modulus.limbs().len();
+    let one = FixedOverflowInteger::from_native(&BigUint::one(), k, limb_bits)
+        .assign(ctx)
+        .into_crt(ctx, range.gate(), BigUint::one(), limb_bases, limb_bits);
+    // reduce `base` first so that every product below is of two integers less than `modulus`
+    let base = mul_mod(range, ctx, base, &one, modulus, limb_bits, limb_bases);
+    // `1 (mod modulus)`, which is `0` when `modulus = 1`
+    let mut acc = mul_mod(range, ctx, &one, &one, modulus, limb_bits, limb_bases);
+    for bit in exp_bits.iter().rev() {
+        acc = mul_mod(range, ctx, &acc, &acc, modulus, limb_bits, limb_bases);
+        let prod = mul_mod(range, ctx, &acc, &base, modulus, limb_bits, limb_bases);
+        acc = ProperCrtUint(select::crt(range.gate(), ctx, prod.0, acc.0, *bit));
+    }
+    acc
+}
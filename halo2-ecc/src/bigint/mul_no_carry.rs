@@ -1,5 +1,11 @@
-use super::{CRTInteger, OverflowInteger};
-use halo2_base::{gates::GateInstructions, utils::ScalarField, Context, QuantumCell::Existing};
+use super::{CRTInteger, FixedCRTInteger, FixedOverflowInteger, OverflowInteger};
+use halo2_base::{
+    gates::GateInstructions,
+    utils::{biguint_to_fe, modulus, BigPrimeField, ScalarField},
+    Context,
+    QuantumCell::{Constant, Existing},
+};
+use num_bigint::BigInt;
 
 /// # Assumptions
 /// * `a` and `b` have the same number of limbs `k`
@@ -47,3 +53,68 @@ pub fn crt<F: ScalarField>(
 
     CRTInteger::new(out_trunc, out_native, out_val)
 }
+
+/// Same as [`truncate`] except `b` is a constant, so its limbs are never assigned in the circuit.
+///
+/// # Assumptions
+/// * `a` and `b` have the same number of limbs `k`
+/// * `k` is nonzero
+/// * `num_limbs_log2_ceil = log2_ceil(k)`
+/// * all limbs of `b` are less than `2^b_max_limb_bits`
+/// * `log2_ceil(k) + a.max_limb_bits + b_max_limb_bits <= F::NUM_BITS as usize - 2`
+pub fn truncate_constant<F: ScalarField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<F>,
+    a: OverflowInteger<F>,
+    b: &FixedOverflowInteger<F>,
+    b_max_limb_bits: usize,
+    num_limbs_log2_ceil: usize,
+) -> OverflowInteger<F> {
+    let k = a.limbs.len();
+    assert_eq!(k, b.limbs.len());
+    debug_assert!(k > 0);
+
+    debug_assert!(
+        num_limbs_log2_ceil + a.max_limb_bits + b_max_limb_bits <= F::NUM_BITS as usize - 2
+    );
+
+    let out_limbs = (0..k)
+        .map(|i| {
+            gate.inner_product(
+                ctx,
+                a.limbs[..=i].iter().copied(),
+                b.limbs[..=i].iter().rev().map(|c| Constant(*c)),
+            )
+        })
+        .collect();
+
+    OverflowInteger::new(out_limbs, num_limbs_log2_ceil + a.max_limb_bits + b_max_limb_bits)
+}
+
+/// Same as [`crt`] except `b` is a constant: the truncation is computed with [`truncate_constant`] and the
+/// native product multiplies by `b mod r` as a constant, where `r` is the modulus of `F`.
+///
+/// # Assumptions
+/// * same as [`truncate_constant`]
+pub fn crt_constant<F: BigPrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<F>,
+    a: CRTInteger<F>,
+    b: FixedCRTInteger<F>,
+    b_max_limb_bits: usize,
+    num_limbs_log2_ceil: usize,
+) -> CRTInteger<F> {
+    let b_native = biguint_to_fe::<F>(&(&b.value % modulus::<F>()));
+    let out_trunc = truncate_constant::<F>(
+        gate,
+        ctx,
+        a.truncation,
+        &b.truncation,
+        b_max_limb_bits,
+        num_limbs_log2_ceil,
+    );
+    let out_native = gate.mul(ctx, a.native, Constant(b_native));
+    let out_val = a.value * BigInt::from(b.value);
+
+    CRTInteger::new(out_trunc, out_native, out_val)
+}
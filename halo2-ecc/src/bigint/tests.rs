@@ -0,0 +1,156 @@
+use super::{bytes, mod_pow, ProperCrtUint, ProperUint};
+use crate::halo2_proofs::halo2curves::bn256::Fr;
+use halo2_base::gates::{GateInstructions, RangeChip, RangeInstructions};
+use halo2_base::utils::testing::base_test;
+use halo2_base::utils::{decompose_biguint, fe_to_biguint, BigPrimeField, ScalarField};
+use halo2_base::{AssignedValue, Context};
+use num_bigint::{BigUint, RandBigInt};
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand_core::SeedableRng;
+
+const LIMB_BITS: usize = 88;
+const NUM_LIMBS: usize = 3;
+
+fn load_uint<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    a: &BigUint,
+    limb_bases: &[F],
+) -> ProperCrtUint<F> {
+    let limbs = ctx.assign_witnesses(decompose_biguint::<F>(a, NUM_LIMBS, LIMB_BITS));
+    for limb in &limbs {
+        range.range_check(ctx, *limb, LIMB_BITS);
+    }
+    ProperUint(limbs).into_crt(ctx, range.gate(), a.clone(), limb_bases, LIMB_BITS)
+}
+
+fn mod_pow_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    base: &BigUint,
+    exp: u64,
+    exp_bits: usize,
+    modulus: &BigUint,
+) -> BigUint {
+    let limb_bases =
+        (0..NUM_LIMBS).map(|i| range.gate().pow_of_two()[LIMB_BITS * i]).collect::<Vec<_>>();
+    let [base, modulus] = [base, modulus].map(|a| load_uint(ctx, range, a, &limb_bases));
+    let exp = ctx.load_witness(Fr::from(exp));
+    let exp_bits = range.gate().num_to_bits(ctx, exp, exp_bits);
+    let out = mod_pow::crt(range, ctx, &base, &exp_bits, &modulus, LIMB_BITS, &limb_bases);
+    out.limbs()
+        .iter()
+        .rev()
+        .fold(BigUint::from(0u64), |acc, limb| (acc << LIMB_BITS) + fe_to_biguint(limb.value()))
+}
+
+#[test]
+fn test_mod_pow() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let moduli = [1u64, 2, 7, 97, 65537, (1 << 61) - 1].map(BigUint::from);
+    base_test().k(17).lookup_bits(16).run(|ctx, range| {
+        for modulus in &moduli {
+            // `base` is not reduced mod `modulus`
+            let base = rng.gen_biguint(200);
+            for exp in [0, 1, 2, rng.gen::<u16>() as u64] {
+                let out = mod_pow_test(ctx, range, &base, exp, 16, modulus);
+                assert_eq!(out, base.modpow(&BigUint::from(exp), modulus));
+            }
+        }
+    });
+}
+
+#[test]
+fn test_mod_pow_large_modulus() {
+    let mut rng = StdRng::seed_from_u64(0);
+    // a modulus that needs all `NUM_LIMBS` limbs
+    let modulus = rng.gen_biguint((LIMB_BITS * NUM_LIMBS) as u64) | BigUint::from(1u64);
+    let base = rng.gen_biguint_below(&modulus);
+    let exp = rng.gen::<u64>();
+    base_test().k(17).lookup_bits(16).run(|ctx, range| {
+        let out = mod_pow_test(ctx, range, &base, exp, 64, &modulus);
+        assert_eq!(out, base.modpow(&BigUint::from(exp), &modulus));
+    });
+}
+
+fn bytes_round_trip_test(ctx: &mut Context<Fr>, range: &RangeChip<Fr>, bytes: &[u8]) {
+    let gate = range.gate();
+    let bytes_assigned = ctx.assign_witnesses(bytes.iter().map(|b| Fr::from(*b as u64)));
+    let value_of = |bytes: &[AssignedValue<Fr>]| {
+        bytes.iter().map(|b| b.value().get_lower_32() as u8).collect::<Vec<_>>()
+    };
+
+    let a = bytes::from_bytes_le(gate, ctx, &bytes_assigned, LIMB_BITS);
+    assert_eq!(a.limbs().len(), (8 * bytes.len() + LIMB_BITS - 1) / LIMB_BITS);
+    assert_eq!(a.value(), BigUint::from_bytes_le(bytes));
+    let bytes_le = bytes::to_bytes_le(gate, ctx, &a, LIMB_BITS, bytes.len());
+    assert_eq!(value_of(&bytes_le), bytes);
+
+    let b = bytes::from_bytes_be(gate, ctx, &bytes_assigned, LIMB_BITS);
+    assert_eq!(b.value(), BigUint::from_bytes_be(bytes));
+    let bytes_be = bytes::to_bytes_be(gate, ctx, &b, LIMB_BITS, bytes.len());
+    assert_eq!(value_of(&bytes_be), bytes);
+}
+
+#[test]
+fn test_bytes_round_trip() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for len in [32, 48] {
+        let mut bytes = vec![0u8; len];
+        rng.fill(&mut bytes[..]);
+        base_test().k(14).lookup_bits(13).run(|ctx, range| {
+            bytes_round_trip_test(ctx, range, &bytes);
+            bytes_round_trip_test(ctx, range, &vec![0xff; len]);
+            bytes_round_trip_test(ctx, range, &vec![0; len]);
+        });
+    }
+}
+
+#[test]
+fn test_to_bytes_overflow() {
+    // 2^{8 * 31} does not fit in 31 bytes
+    let mut bytes = vec![0u8; 32];
+    bytes[31] = 1;
+    base_test().k(14).lookup_bits(13).expect_satisfied(false).run(|ctx, range| {
+        let bytes = ctx.assign_witnesses(bytes.iter().map(|b| Fr::from(*b as u64)));
+        let a = bytes::from_bytes_le(range.gate(), ctx, &bytes, LIMB_BITS);
+        bytes::to_bytes_le(range.gate(), ctx, &a, LIMB_BITS, 31);
+    });
+}
+
+fn to_bits_le_test(ctx: &mut Context<Fr>, range: &RangeChip<Fr>, a: &BigUint, max_bits: usize) {
+    let limb_bases =
+        (0..NUM_LIMBS).map(|i| range.gate().pow_of_two()[LIMB_BITS * i]).collect::<Vec<_>>();
+    let a_assigned = load_uint(ctx, range, a, &limb_bases);
+    let bits = bytes::to_bits_le(range.gate(), ctx, &a_assigned, LIMB_BITS, max_bits);
+    assert_eq!(bits.len(), max_bits);
+    let recomposed = bits
+        .iter()
+        .rev()
+        .fold(BigUint::from(0u64), |acc, bit| (acc << 1) + fe_to_biguint(bit.value()));
+    assert_eq!(&recomposed, a);
+}
+
+#[test]
+fn test_to_bits_le() {
+    let mut rng = StdRng::seed_from_u64(0);
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        for max_bits in [1, 64, 200, LIMB_BITS * NUM_LIMBS, LIMB_BITS * NUM_LIMBS + 10] {
+            let a = rng.gen_biguint(max_bits.min(LIMB_BITS * NUM_LIMBS) as u64);
+            to_bits_le_test(ctx, range, &a, max_bits);
+        }
+        // largest value with `max_bits` bits
+        to_bits_le_test(ctx, range, &((BigUint::from(1u64) << 200) - 1u64), 200);
+    });
+}
+
+#[test]
+fn test_to_bits_le_overflow() {
+    base_test().k(14).lookup_bits(13).expect_satisfied(false).run(|ctx, range| {
+        let limb_bases =
+            (0..NUM_LIMBS).map(|i| range.gate().pow_of_two()[LIMB_BITS * i]).collect::<Vec<_>>();
+        let a = load_uint(ctx, range, &(BigUint::from(1u64) << 200), &limb_bases);
+        bytes::to_bits_le(range.gate(), ctx, &a, LIMB_BITS, 200);
+    });
+}
@@ -1,11 +1,12 @@
 #![allow(non_snake_case)]
 
-use super::pairing::PairingChip;
-use super::{Fp12Chip, Fp2Chip, FpChip};
+use super::pairing::{prepared_g2::PreparedG2, PairingChip};
+use super::{Fp12Chip, Fp2Chip, FpChip, G1Point, G2Point};
 use crate::ecc::EccChip;
 use crate::fields::FieldChip;
-use crate::halo2_proofs::halo2curves::bn256::Fq12;
-use crate::halo2_proofs::halo2curves::bn256::{G1Affine, G2Affine};
+use crate::group::prime::PrimeCurveAffine;
+use crate::halo2_proofs::halo2curves::bn256::{Fq12, G1Affine, G2Affine};
+use halo2_base::gates::GateInstructions;
 use halo2_base::utils::BigPrimeField;
 use halo2_base::{AssignedValue, Context};
 
@@ -63,17 +64,135 @@ impl<'chip, F: BigPrimeField> BlsSignatureChip<'chip, F> {
         let g12_chip = EccChip::new(&fp12_chip);
         let neg_signature_assigned_g12 = g12_chip.negate(ctx, &signature_agg_assigned);
 
-        let multi_paired = self.pairing_chip.multi_miller_loop(
+        // Check signatures are verified
+        self.pairing_chip.is_pairing_product_one(
             ctx,
-            vec![
+            &[
                 (&g1_assigned, &neg_signature_assigned_g12),
                 (&pubkey_agg_assigned, &hash_m_assigned),
             ],
+        )
+    }
+
+    /// Verifies a single signature in the minimal-signature-size variant, where signatures and message
+    /// hashes are in G1 and public keys are in G2: returns whether `pubkey` is in G2 and
+    /// `e(sig, g2) == e(msg_hash, pubkey)`.
+    ///
+    /// This is [`Self::verify_aggregate`] with a single `(pubkey, msg_hash)` pair.
+    ///
+    /// # Assumptions
+    /// * `pubkey`, `msg_hash` and `sig` are on their curves and not the point at infinity
+    pub fn verify(
+        &self,
+        ctx: &mut Context<F>,
+        pubkey: &G2Point<F>,
+        msg_hash: &G1Point<F>,
+        sig: &G1Point<F>,
+    ) -> AssignedValue<F> {
+        self.verify_aggregate(ctx, &[(pubkey, msg_hash)], sig)
+    }
+
+    /// Same as [`Self::verify`], but runs the Miller loop for `g2` in circuit instead of using the prepared `-g2`.
+    ///
+    /// `g2` is loaded as a constant and negated in circuit with [`EccChip::conditional_negate`], and the pairing
+    /// equation is checked as `e(sig, -g2) * e(msg_hash, pubkey) == 1` with one [`PairingChip::multi_miller_loop`]
+    /// and one final exponentiation. This costs one more G2 Miller loop than [`Self::verify`].
+    ///
+    /// # Assumptions
+    /// * `pubkey`, `msg_hash` and `sig` are on their curves and not the point at infinity
+    pub fn verify_unprepared(
+        &self,
+        ctx: &mut Context<F>,
+        pubkey: &G2Point<F>,
+        msg_hash: &G1Point<F>,
+        sig: &G1Point<F>,
+    ) -> AssignedValue<F> {
+        let gate = self.fp_chip.gate();
+        let fp2_chip = Fp2Chip::<F>::new(self.fp_chip);
+        let g2_chip = EccChip::new(&fp2_chip);
+        let g2 = self.pairing_chip.load_g2(ctx, G2Affine::generator());
+        let negate = ctx.load_constant(F::ONE);
+        let neg_g2 = g2_chip.conditional_negate(ctx, g2, negate);
+        let pubkey_in_g2 = self.pairing_chip.is_in_subgroup_g2(ctx, pubkey);
+
+        let f = self.pairing_chip.multi_miller_loop(ctx, vec![(sig, &neg_g2), (msg_hash, pubkey)]);
+        let fp12_chip = Fp12Chip::<F>::new(self.fp_chip);
+        let f = fp12_chip.final_exp(ctx, f);
+        let fp12_one = fp12_chip.load_constant(ctx, Fq12::one());
+        let is_one = fp12_chip.is_equal(ctx, f, fp12_one);
+        gate.and(ctx, pubkey_in_g2, is_one)
+    }
+
+    /// Verifies an aggregate signature `agg_sig = sum_i sig_i` over distinct messages, in the same variant as
+    /// [`Self::verify`]: returns whether every `pubkey_i` is in G2, the `msg_hash_i` are pairwise distinct and
+    /// `e(agg_sig, g2) == prod_i e(msg_hash_i, pubkey_i)` for `pairs = [(pubkey_i, msg_hash_i)]`.
+    ///
+    /// Each `pubkey_i` is checked with [`PairingChip::is_in_subgroup_g2`]. G1 is the whole curve for BN254, so
+    /// `agg_sig` and `msg_hash_i` only need to be on the curve, which the caller is assumed to have constrained,
+    /// e.g. with [`PairingChip::load_g1_witness`].
+    ///
+    /// Aggregation over distinct messages is only secure against rogue key attacks when the messages really
+    /// are distinct, so repeated message hashes make the output false. This costs `n (n - 1) / 2` point
+    /// comparisons for `n` pairs. Use [`Self::verify`] on each pair when signers may sign the same message.
+    ///
+    /// The pairing equation is checked as `e(agg_sig, -g2) * prod_i e(msg_hash_i, pubkey_i) == 1` with one final
+    /// exponentiation. The Miller loop for the fixed `-g2` uses its precomputed line functions ([`PreparedG2`]),
+    /// so only the loops for the `pubkey_i` do G2 arithmetic in circuit.
+    ///
+    /// # Assumptions
+    /// * `pairs` is nonempty
+    /// * all points are on their curves and not the point at infinity
+    pub fn verify_aggregate(
+        &self,
+        ctx: &mut Context<F>,
+        pairs: &[(&G2Point<F>, &G1Point<F>)],
+        agg_sig: &G1Point<F>,
+    ) -> AssignedValue<F> {
+        assert!(!pairs.is_empty(), "pairs must not be empty");
+        let gate = self.fp_chip.gate();
+        let g1_chip = EccChip::new(self.fp_chip);
+        let mut checks = pairs
+            .iter()
+            .map(|&(pubkey, _)| self.pairing_chip.is_in_subgroup_g2(ctx, pubkey))
+            .collect::<Vec<_>>();
+        for (i, &(_, msg_hash)) in pairs.iter().enumerate() {
+            for &(_, other) in &pairs[i + 1..] {
+                let is_equal = g1_chip.is_equal(ctx, msg_hash.clone(), other.clone());
+                checks.push(gate.not(ctx, is_equal));
+            }
+        }
+
+        let neg_g2 = PreparedG2::new(-G2Affine::generator());
+        let f_sig = self.pairing_chip.miller_loop_prepared(ctx, &[(agg_sig, &neg_g2)]);
+        let f_msgs = self.pairing_chip.multi_miller_loop(
+            ctx,
+            pairs.iter().map(|&(pubkey, msg_hash)| (msg_hash, pubkey)).collect(),
         );
-        let result = fp12_chip.final_exp(ctx, multi_paired);
 
-        // Check signatures are verified
+        let fp12_chip = Fp12Chip::<F>::new(self.fp_chip);
+        let f = fp12_chip.mul(ctx, f_sig, f_msgs);
+        let f = fp12_chip.final_exp(ctx, f);
         let fp12_one = fp12_chip.load_constant(ctx, Fq12::one());
-        fp12_chip.is_equal(ctx, result, fp12_one)
+        checks.push(fp12_chip.is_equal(ctx, f, fp12_one));
+
+        checks.into_iter().reduce(|acc, check| gate.and(ctx, acc, check)).unwrap()
+    }
+
+    /// Same as [`Self::verify_aggregate`], with the public keys and message hashes given as separate slices:
+    /// verifies `agg_sig` for the pairs `(pks[i], msgs[i])`.
+    ///
+    /// # Assumptions
+    /// * `pks` and `msgs` have the same nonzero length
+    /// * all points are on their curves and not the point at infinity
+    pub fn verify_aggregate_distinct(
+        &self,
+        ctx: &mut Context<F>,
+        pks: &[G2Point<F>],
+        msgs: &[G1Point<F>],
+        agg_sig: &G1Point<F>,
+    ) -> AssignedValue<F> {
+        assert_eq!(pks.len(), msgs.len(), "pks and msgs must be the same length");
+        let pairs = pks.iter().zip(msgs).collect::<Vec<_>>();
+        self.verify_aggregate(ctx, &pairs, agg_sig)
     }
 }
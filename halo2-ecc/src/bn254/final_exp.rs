@@ -1,21 +1,89 @@
-use super::{Fp12Chip, Fp2Chip, FpChip, FqPoint};
+use super::{Fp12Chip, Fp2Chip, Fp6Chip, FpChip, FqPoint, XI_0};
+use crate::bigint::CRTInteger;
 use crate::halo2_proofs::{
     arithmetic::Field,
-    halo2curves::bn256::{Fq, Fq2, BN_X, FROBENIUS_COEFF_FQ12_C1},
+    halo2curves::bn256::{Fq, Fq12, Fq2, Fr, BN_X, FROBENIUS_COEFF_FQ12_C1},
 };
 use crate::{
-    ecc::get_naf,
-    fields::{fp12::mul_no_carry_w6, vector::FieldVector, FieldChip},
+    ecc::{get_naf, get_wnaf},
+    fields::{
+        fp12::{fp12_coeff, fp12_coeffs, fp12_from_coeffs, mul_no_carry_w6},
+        vector::FieldVector,
+        FieldChip, Selectable,
+    },
 };
 use halo2_base::{
     gates::GateInstructions,
     utils::{modulus, BigPrimeField},
-    Context,
+    AssignedValue, Context,
     QuantumCell::Constant,
 };
 use num_bigint::BigUint;
+use std::sync::OnceLock;
+
+pub mod torus;
+
+/// Returns the table of constants `FROBENIUS_COEFF_FQ12_C1[pow]^i` for `pow in 0..12` and `i in 0..6`,
+/// indexed as `[pow][i]`, used by [`Fp12Chip::frobenius_map`].
+///
+/// The table is computed on first use and shared afterwards, so witness generation does not redo these exponentiations on every call.
+pub fn frobenius_coeffs() -> &'static [[Fq2; 6]; 12] {
+    static FROBENIUS_COEFFS: OnceLock<[[Fq2; 6]; 12]> = OnceLock::new();
+    FROBENIUS_COEFFS.get_or_init(|| {
+        core::array::from_fn(|pow| {
+            core::array::from_fn(|i| FROBENIUS_COEFF_FQ12_C1[pow].pow_vartime([i as u64]))
+        })
+    })
+}
+
+/// Addition chain used for the hard part of the final exponentiation, see [`Fp12Chip::final_exp_with`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HardPart {
+    /// [`Fp12Chip::hard_part_BN`]: computes exactly `m^{(p^4 - p^2 + 1)/r}`, so the output agrees with
+    /// `halo2curves` pairings.
+    #[default]
+    Scott,
+    /// [`Fp12Chip::hard_part_fuentes`]: computes `m^{2x(6x^2 + 3x + 1)(p^4 - p^2 + 1)/r}` with a different
+    /// addition chain. The output is the `2x(6x^2 + 3x + 1)`-th power of the default one,
+    /// which is coprime to `r`, so it is equally good for pairing checks but not equal to `halo2curves` pairings.
+    FuentesCastaneda,
+}
 
-const XI_0: i64 = 9;
+impl<'chip, F: BigPrimeField> Fp6Chip<'chip, F> {
+    /// Returns `a^{p^power}`, using that `v = w^2` so the Frobenius coefficients are those of [`Fp12Chip::frobenius_map`]
+    /// at even powers of `w`.
+    pub fn frobenius_map(
+        &self,
+        ctx: &mut Context<F>,
+        a: &<Self as FieldChip<F>>::FieldPoint,
+        power: usize,
+    ) -> <Self as FieldChip<F>>::FieldPoint {
+        assert_eq!(a.0.len(), 6);
+        let pow = power % 12;
+        let mut out_fp2 = Vec::with_capacity(3);
+
+        let fp_chip = self.fp_chip();
+        let fp2_chip = Fp2Chip::<F>::new(fp_chip);
+        for i in 0..3 {
+            let frob_coeff = frobenius_coeffs()[pow][2 * i];
+            let a_fp2 = FieldVector(vec![a[i].clone(), a[i + 3].clone()]);
+            if frob_coeff == Fq2::one() {
+                out_fp2.push(fp2_chip.frobenius_map(ctx, a_fp2, pow));
+            } else if pow % 2 != 0 {
+                let out_nocarry = fp2_chip.conjugate_mul_constant_no_carry(ctx, a_fp2, frob_coeff);
+                out_fp2.push(fp2_chip.carry_mod(ctx, out_nocarry));
+            } else if frob_coeff.c1 == Fq::zero() {
+                let out_nocarry = fp2_chip.fp_mul_constant_no_carry(ctx, a_fp2, frob_coeff.c0);
+                out_fp2.push(fp2_chip.carry_mod(ctx, out_nocarry));
+            } else {
+                let out_nocarry = fp2_chip.mul_constant_no_carry(ctx, a_fp2, frob_coeff);
+                out_fp2.push(fp2_chip.carry_mod(ctx, out_nocarry));
+            }
+        }
+
+        out_fp2.iter().map(|x| x[0].clone()).chain(out_fp2.iter().map(|x| x[1].clone())).collect()
+    }
+}
 
 impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
     // computes a ** (p ** power)
@@ -34,42 +102,34 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
 
         let fp_chip = self.fp_chip();
         let fp2_chip = Fp2Chip::<F>::new(fp_chip);
-        for i in 0..6 {
-            let frob_coeff = FROBENIUS_COEFF_FQ12_C1[pow].pow_vartime([i as u64]);
-            // possible optimization (not implemented): load `frob_coeff` as we multiply instead of loading first
-            // frobenius map is used infrequently so this is a small optimization
-
-            let mut a_fp2 = FieldVector(vec![a[i].clone(), a[i + 6].clone()]);
-            if pow % 2 != 0 {
-                a_fp2 = fp2_chip.conjugate(ctx, a_fp2);
-            }
+        for (i, &frob_coeff) in frobenius_coeffs()[pow].iter().enumerate() {
+            // `frob_coeff` is folded into the multiplication as a constant, so it is never loaded
+
+            let a_fp2 = fp12_coeff(a, i);
+            // for odd `pow` the coefficient is conjugated; unless `frob_coeff` is one, the conjugation is folded
+            // into the constant multiplication
             // if `frob_coeff` is in `Fp` and not just `Fp2`, then we can be more efficient in multiplication
             if frob_coeff == Fq2::one() {
-                out_fp2.push(a_fp2);
+                out_fp2.push(fp2_chip.frobenius_map(ctx, a_fp2, pow));
+            } else if pow % 2 != 0 {
+                let out_nocarry = fp2_chip.conjugate_mul_constant_no_carry(ctx, a_fp2, frob_coeff);
+                out_fp2.push(fp2_chip.carry_mod(ctx, out_nocarry));
             } else if frob_coeff.c1 == Fq::zero() {
-                let frob_fixed = fp_chip.load_constant(ctx, frob_coeff.c0);
-                {
-                    let out_nocarry = fp2_chip.0.fp_mul_no_carry(ctx, a_fp2, frob_fixed);
-                    out_fp2.push(fp2_chip.carry_mod(ctx, out_nocarry));
-                }
+                let out_nocarry = fp2_chip.fp_mul_constant_no_carry(ctx, a_fp2, frob_coeff.c0);
+                out_fp2.push(fp2_chip.carry_mod(ctx, out_nocarry));
             } else {
-                let frob_fixed = fp2_chip.load_constant(ctx, frob_coeff);
-                out_fp2.push(fp2_chip.mul(ctx, a_fp2, frob_fixed));
+                let out_nocarry = fp2_chip.mul_constant_no_carry(ctx, a_fp2, frob_coeff);
+                out_fp2.push(fp2_chip.carry_mod(ctx, out_nocarry));
             }
         }
 
-        let out_coeffs = out_fp2
-            .iter()
-            .map(|x| x[0].clone())
-            .chain(out_fp2.iter().map(|x| x[1].clone()))
-            .collect();
-
-        FieldVector(out_coeffs)
+        fp12_from_coeffs(out_fp2.try_into().unwrap())
     }
 
     // exp is in little-endian
-    /// # Assumptions
-    /// * `a` is nonzero field point
+    /// Constrains `a` to be nonzero whenever the NAF of `exp` has a `-1` digit.
+    ///
+    /// `exp` is an unsigned magnitude, as for [`Self::cyclotomic_pow`]; use [`Self::pow_signed`] for a negative exponent.
     pub fn pow(
         &self,
         ctx: &mut Context<F>,
@@ -88,11 +148,7 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
             if z != 0 {
                 assert!(z == 1 || z == -1);
                 if is_started {
-                    res = if z == 1 {
-                        self.mul(ctx, &res, a)
-                    } else {
-                        self.divide_unsafe(ctx, &res, a)
-                    };
+                    res = if z == 1 { self.mul(ctx, &res, a) } else { self.divide(ctx, &res, a) };
                 } else {
                     assert_eq!(z, 1);
                     is_started = true;
@@ -102,6 +158,29 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
         res
     }
 
+    // magnitude is in little-endian
+    /// Returns `a^{-magnitude}` if `negative` and `a^{magnitude}` otherwise.
+    ///
+    /// Curve parameters such as the BLS12 `x` are negative and usually stored as their absolute value, so
+    /// [`Self::pow`] and [`Self::cyclotomic_pow`] on that constant compute `a^{|x|}`. The negative case inverts the
+    /// result with a division, which constrains `a` to be nonzero. For `a` in the cyclotomic subgroup it is cheaper
+    /// to apply [`Self::cyclotomic_inverse`] to the output of [`Self::cyclotomic_pow`] instead.
+    pub fn pow_signed(
+        &self,
+        ctx: &mut Context<F>,
+        a: &<Self as FieldChip<F>>::FieldPoint,
+        magnitude: Vec<u64>,
+        negative: bool,
+    ) -> <Self as FieldChip<F>>::FieldPoint {
+        let res = self.pow(ctx, a, magnitude);
+        if negative {
+            let one = self.load_constant(ctx, Fq12::one());
+            self.divide(ctx, one, res)
+        } else {
+            res
+        }
+    }
+
     // assume input is an element of Fp12 in the cyclotomic subgroup GΦ₁₂
     // A cyclotomic group is a subgroup of Fp^n defined by
     //   GΦₙ(p) = {α ∈ Fpⁿ : α^{Φₙ(p)} = 1}
@@ -113,11 +192,7 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
     /// in = g0 + g2 w + g4 w^2 + g1 w^3 + g3 w^4 + g5 w^5 where g_i = g_i0 + g_i1 * u are elements of Fp2
     /// out = Compress(in) = [ g2, g3, g4, g5 ]
     pub fn cyclotomic_compress(&self, a: &FqPoint<F>) -> Vec<FqPoint<F>> {
-        let a = &a.0;
-        let g2 = FieldVector(vec![a[1].clone(), a[1 + 6].clone()]);
-        let g3 = FieldVector(vec![a[4].clone(), a[4 + 6].clone()]);
-        let g4 = FieldVector(vec![a[2].clone(), a[2 + 6].clone()]);
-        let g5 = FieldVector(vec![a[5].clone(), a[5 + 6].clone()]);
+        let [_g0, g2, g4, _g1, g3, g5] = fp12_coeffs(a);
         vec![g2, g3, g4, g5]
     }
 
@@ -161,7 +236,7 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
 
         let g2_is_zero = fp2_chip.is_zero(ctx, &g2);
         // resulting `g1` is already in "carried" format (witness is in `[0, p)`)
-        let g1 = fp2_chip.0.select(ctx, g1_0, g1_1, g2_is_zero);
+        let g1 = fp2_chip.select(ctx, g1_0, g1_1, g2_is_zero);
 
         // share the computation of 2 g1^2 between the two cases
         let g1_sq = fp2_chip.mul_no_carry(ctx, &g1, &g1);
@@ -171,7 +246,7 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
         let g3_g4 = fp2_chip.mul_no_carry(ctx, &g3, &g4);
         let g3_g4_3 = fp2_chip.scalar_mul_no_carry(ctx, &g3_g4, 3);
         let temp = fp2_chip.add_no_carry(ctx, &g1_sq_2, &g2_g5);
-        let temp = fp2_chip.0.select(ctx, g1_sq_2, temp, g2_is_zero);
+        let temp = fp2_chip.select(ctx, g1_sq_2, temp, g2_is_zero);
         let temp = fp2_chip.sub_no_carry(ctx, &temp, &g3_g4_3);
         let mut g0 = mul_no_carry_w6::<_, _, XI_0>(fp_chip, ctx, temp);
 
@@ -185,25 +260,7 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
         // finally, carry g0
         let g0 = fp2_chip.carry_mod(ctx, g0);
 
-        let mut g0 = g0.into_iter();
-        let mut g1 = g1.into_iter();
-        let mut g2 = g2.into_iter();
-        let mut g3 = g3.into_iter();
-        let mut g4 = g4.into_iter();
-        let mut g5 = g5.into_iter();
-
-        let mut out_coeffs = Vec::with_capacity(12);
-        for _ in 0..2 {
-            out_coeffs.append(&mut vec![
-                g0.next().unwrap(),
-                g2.next().unwrap(),
-                g4.next().unwrap(),
-                g1.next().unwrap(),
-                g3.next().unwrap(),
-                g5.next().unwrap(),
-            ]);
-        }
-        FieldVector(out_coeffs)
+        fp12_from_coeffs([g0, g2, g4, g1, g3, g5])
     }
 
     // input is [g2, g3, g4, g5] = C(g) in compressed format of `cyclotomic_compress`
@@ -254,9 +311,9 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
         temp = fp2_chip.scalar_mul_no_carry(ctx, temp, 3);
         let h3 = fp2_chip.scalar_mul_and_add_no_carry(ctx, g3, temp, -2);
 
-        const XI0_PLUS_1: i64 = XI_0 + 1;
-        // (c + 1) = (XI_0 + 1) + u
-        temp = mul_no_carry_w6::<F, FpChip<F>, XI0_PLUS_1>(fp_chip, ctx, b23.clone());
+        // (c + 1) * B_23 = c * B_23 + B_23
+        let b23_c = mul_no_carry_w6::<_, _, XI_0>(fp_chip, ctx, b23.clone());
+        temp = fp2_chip.add_no_carry(ctx, b23_c, &b23);
         temp = fp2_chip.sub_no_carry(ctx, &a23, temp);
         temp = fp2_chip.scalar_mul_no_carry(ctx, temp, 3);
         let h4 = fp2_chip.scalar_mul_and_add_no_carry(ctx, g4, temp, -2);
@@ -264,49 +321,266 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
         temp = fp2_chip.scalar_mul_and_add_no_carry(ctx, b23, g5, 3);
         let h5 = fp2_chip.scalar_mul_no_carry(ctx, temp, 2);
 
+        // Overflow: with proper inputs every `Fp` limb is `< 2^n` (`n` = limb bits, `k` = number of limbs) and every
+        // value is `< p`. `c * g` scales by at most `XI_0 + 1 = 10`, so `g_i + c g_j` has limbs `< 11 * 2^n` and `A_ij`
+        // (an `Fp2` product, i.e. a difference of two `Fp` products) has limbs `< 2 * k * 2 * 11 * 2^{2n}` and value
+        // `< 44 p^2`. Likewise `B_ij` is `< 2k * 2^{2n}`, `< 2 p^2`, and `c B_45` is 10 times that.
+        // The largest outputs are `h3, h4 <= 3 (A + c B + B) + 2 g`, with limbs `< 198k * 2^{2n}`, i.e.
+        // `2n + 10` bits for `k = 3`, and value `< 198 p^2 < 2^515`. Both are within what `carry_mod` accepts for
+        // `n = 88, k = 3` (limbs below `F::CAPACITY`, value below `2^{nk - 1 + F::NUM_BITS - 2}`), so no
+        // intermediate carries are needed. Each output is an input of the next squaring and must be proper, so
+        // one `carry_mod` per output is the minimum.
         [h2, h3, h4, h5].into_iter().map(|h| fp2_chip.carry_mod(ctx, h)).collect()
     }
 
+    // returns (t0, t1) with t0 + t1 * w^3 = (a + b * w^3)^2 in Fp4 = Fp2(w^3), without carry
+    //  t0 = a^2 + c * b^2
+    //  t1 = 2ab
+    fn fp4_square_no_carry(
+        &self,
+        ctx: &mut Context<F>,
+        a: &FqPoint<F>,
+        b: &FqPoint<F>,
+    ) -> (FieldVector<CRTInteger<F>>, FieldVector<CRTInteger<F>>) {
+        let fp_chip = self.fp_chip();
+        let fp2_chip = Fp2Chip::<F>::new(fp_chip);
+        let a_sq = fp2_chip.mul_no_carry(ctx, a, a);
+        let b_sq = fp2_chip.mul_no_carry(ctx, b, b);
+        let b_sq_c = mul_no_carry_w6::<_, _, XI_0>(fp_chip, ctx, b_sq);
+        let t0 = fp2_chip.add_no_carry(ctx, a_sq, b_sq_c);
+        let ab = fp2_chip.mul_no_carry(ctx, a, b);
+        let t1 = fp2_chip.scalar_mul_no_carry(ctx, ab, 2);
+        (t0, t1)
+    }
+
+    /// Squares `a` in the cyclotomic subgroup without compression, following Section 3.2 of
+    /// https://eprint.iacr.org/2009/565.pdf (Granger–Scott).
+    ///
+    /// Write `a = A0 + A1 w + A2 w^2` with `A0 = g0 + g1 w^3, A1 = g2 + g3 w^3, A2 = g4 + g5 w^3` in
+    /// `Fp4 = Fp2(w^3)`, using the naming of [`Self::cyclotomic_compress`]. Then
+    /// `a^2 = (3 A0^2 - 2 conj(A0)) + (3 w^3 A2^2 + 2 conj(A1)) w + (3 A1^2 - 2 conj(A2)) w^2`
+    /// where `conj(x + y w^3) = x - y w^3`. This costs three Fp4 squarings (9 Fp2 multiplications)
+    /// and does not need the divisions of [`Self::cyclotomic_decompress`].
+    ///
+    /// # Assumptions
+    /// * `a` is in the cyclotomic subgroup
+    pub fn cyclotomic_square_uncompressed(
+        &self,
+        ctx: &mut Context<F>,
+        a: &FqPoint<F>,
+    ) -> FqPoint<F> {
+        let fp2_chip = Fp2Chip::<F>::new(self.fp_chip());
+        let [g0, g2, g4, g1, g3, g5] = fp12_coeffs(a);
+
+        let (t0, t1) = self.fp4_square_no_carry(ctx, &g0, &g1);
+        // 3 t0 - 2 g0
+        let g0_2 = fp2_chip.scalar_mul_no_carry(ctx, &g0, -2);
+        let h0 = fp2_chip.scalar_mul_and_add_no_carry(ctx, t0, g0_2, 3);
+        // 3 t1 + 2 g1
+        let g1_2 = fp2_chip.scalar_mul_no_carry(ctx, &g1, 2);
+        let h1 = fp2_chip.scalar_mul_and_add_no_carry(ctx, t1, g1_2, 3);
+
+        let (t0, t1) = self.fp4_square_no_carry(ctx, &g2, &g3);
+        // 3 t0 - 2 g4
+        let g4_2 = fp2_chip.scalar_mul_no_carry(ctx, &g4, -2);
+        let h4 = fp2_chip.scalar_mul_and_add_no_carry(ctx, t0, g4_2, 3);
+        // 3 t1 + 2 g5
+        let g5_2 = fp2_chip.scalar_mul_no_carry(ctx, &g5, 2);
+        let h5 = fp2_chip.scalar_mul_and_add_no_carry(ctx, t1, g5_2, 3);
+
+        let (t0, t1) = self.fp4_square_no_carry(ctx, &g4, &g5);
+        // 3 c t1 + 2 g2
+        let t1_c = mul_no_carry_w6::<_, _, XI_0>(self.fp_chip(), ctx, t1);
+        let g2_2 = fp2_chip.scalar_mul_no_carry(ctx, &g2, 2);
+        let h2 = fp2_chip.scalar_mul_and_add_no_carry(ctx, t1_c, g2_2, 3);
+        // 3 t0 - 2 g3
+        let g3_2 = fp2_chip.scalar_mul_no_carry(ctx, &g3, -2);
+        let h3 = fp2_chip.scalar_mul_and_add_no_carry(ctx, t0, g3_2, 3);
+
+        fp12_from_coeffs([h0, h2, h4, h1, h3, h5].map(|h| fp2_chip.carry_mod(ctx, h)))
+    }
+
+    /// Constrains `a` to be in the cyclotomic subgroup `GΦ₁₂ = {α ∈ Fp¹² : α^{p^4 - p^2 + 1} = 1}`
+    /// by checking that `a` is nonzero and `a^{p^4} * a == a^{p^2}`.
+    ///
+    /// Costs an Fp12 `is_zero`, two Frobenius maps, one Fp12 multiplication and an Fp12 `assert_equal`,
+    /// which is mostly useful for debugging callers of [`Self::cyclotomic_square`] and
+    /// [`Self::cyclotomic_pow`]: inside [`Self::final_exp`] membership already follows from the easy part.
+    pub fn assert_in_cyclotomic_subgroup(
+        &self,
+        ctx: &mut Context<F>,
+        a: &<Self as FieldChip<F>>::FieldPoint,
+    ) {
+        let a_is_zero = self.is_zero(ctx, a.clone());
+        self.gate().assert_is_const(ctx, &a_is_zero, &F::ZERO);
+
+        let a_p2 = self.frobenius_map(ctx, a, 2);
+        let a_p4 = self.frobenius_map(ctx, a, 4);
+        let a_p4_a = self.mul(ctx, &a_p4, a);
+        self.assert_equal(ctx, a_p4_a, a_p2);
+    }
+
+    /// Same as [`Self::cyclotomic_decompress`], but additionally constrains the output to be in the
+    /// cyclotomic subgroup with [`Self::assert_in_cyclotomic_subgroup`], for compressions that come
+    /// from an untrusted source. Most `[g2, g3, g4, g5]` are not the compression of any element of
+    /// `GΦ₁₂`, and for those the circuit is unsatisfiable.
+    ///
+    /// On top of the decompression this costs an Fp12 `is_zero`, two Frobenius maps, one Fp12
+    /// multiplication and an Fp12 `assert_equal`.
+    pub fn cyclotomic_decompress_checked(
+        &self,
+        ctx: &mut Context<F>,
+        compression: Vec<FqPoint<F>>,
+    ) -> FqPoint<F> {
+        let a = self.cyclotomic_decompress(ctx, compression);
+        self.assert_in_cyclotomic_subgroup(ctx, &a);
+        a
+    }
+
+    /// Constrains `a` to be in `GT`, the subgroup of order `r` of `Fp¹²^*`, where `r` is the order of
+    /// BN254 `G1` and `G2`.
+    ///
+    /// Checks membership in the cyclotomic subgroup with [`Self::assert_in_cyclotomic_subgroup`] and
+    /// then `a^r == 1` with [`Self::cyclotomic_pow`], whose squarings are only valid once the former holds.
+    /// This is meant for `GT` elements from an untrusted source; outputs of [`Self::final_exp`] are in `GT`
+    /// by construction, which makes this an end-to-end check of the final exponentiation in tests.
+    pub fn assert_in_gt_subgroup(&self, ctx: &mut Context<F>, a: &FqPoint<F>) {
+        self.assert_in_cyclotomic_subgroup(ctx, a);
+        let a_r = self.cyclotomic_pow(ctx, a.clone(), modulus::<Fr>().to_u64_digits());
+        let one = self.load_constant(ctx, Fq12::one());
+        self.assert_equal(ctx, a_r, one);
+    }
+
+    /// Returns `a^{-1}` for `a` in the cyclotomic subgroup, where `a^{-1} = a^{p^6}` is the conjugate of `a`.
+    /// This costs no multiplications, unlike [`Fp12Chip::invert`] or a division.
+    ///
+    /// # Assumptions
+    /// * `a` is in the cyclotomic subgroup; for any other `a` the output is not the inverse and nothing fails
+    pub fn cyclotomic_inverse(&self, ctx: &mut Context<F>, a: FqPoint<F>) -> FqPoint<F> {
+        self.conjugate(ctx, a)
+    }
+
     // exp is in little-endian
     /// # Assumptions
     /// * `a` is a nonzero element in the cyclotomic subgroup
+    ///
+    /// Returns the constant `1` if `exp` is zero. Leading zero digits of the NAF are skipped, so the
+    /// number of squarings only depends on the bit length of `exp`, not on `exp.len()`.
+    ///
+    /// The NAF digits of `exp` are processed from least to most significant: the base `a^{2^i}` is
+    /// squared upward with [`Self::cyclotomic_square_uncompressed`] and multiplied into the accumulator
+    /// at nonzero digits. For a `-1` digit we multiply by [`Self::cyclotomic_inverse`] of the base, so no
+    /// division is needed.
+    ///
+    /// Squaring uncompressed avoids the `cyclotomic_decompress` (two Fp2 divisions) at every nonzero
+    /// digit of the compressed squaring [`Self::cyclotomic_square`], and unlike decompression it has no
+    /// special case when `g2 = g3 = 0`.
     pub fn cyclotomic_pow(&self, ctx: &mut Context<F>, a: FqPoint<F>, exp: Vec<u64>) -> FqPoint<F> {
-        let mut compression = self.cyclotomic_compress(&a);
-        let mut out = None;
-        let mut is_started = false;
-        let naf = get_naf(exp);
+        let mut base = a;
+        let mut out: Option<FqPoint<F>> = None;
+        let mut naf = get_naf(exp);
+        while naf.last() == Some(&0) {
+            naf.pop();
+        }
 
-        for &z in naf.iter().rev() {
-            if is_started {
-                compression = self.cyclotomic_square(ctx, &compression);
+        for (i, &z) in naf.iter().enumerate() {
+            if i != 0 {
+                // base = a^{2^i}
+                base = self.cyclotomic_square_uncompressed(ctx, &base);
             }
             if z != 0 {
                 assert!(z == 1 || z == -1);
-                if is_started {
-                    let mut res = self.cyclotomic_decompress(ctx, compression);
-                    res = if z == 1 {
-                        self.mul(ctx, &res, &a)
-                    } else {
-                        self.divide_unsafe(ctx, &res, &a)
-                    };
-                    // compression is free, so it doesn't hurt (except possibly witness generation runtime) to do it
-                    // TODO: alternatively we go from small bits to large to avoid this compression
-                    compression = self.cyclotomic_compress(&res);
-                    out = Some(res);
-                } else {
-                    assert_eq!(z, 1);
-                    is_started = true;
-                }
+                let term =
+                    if z == 1 { base.clone() } else { self.cyclotomic_inverse(ctx, base.clone()) };
+                out = Some(match out {
+                    None => term,
+                    Some(out) => self.mul(ctx, &out, &term),
+                });
+            }
+        }
+        out.unwrap_or_else(|| self.load_constant(ctx, Fq12::one()))
+    }
+
+    // exp is in little-endian
+    /// Same as [`Self::cyclotomic_pow`], but using the width-`w` NAF of `exp` from [`get_wnaf`].
+    ///
+    /// The odd powers `a, a^3, ..., a^{2^{w-1} - 1}` are precomputed, and the digits are processed from most
+    /// to least significant: the accumulator is squared once per digit and multiplied by a table entry at
+    /// each nonzero digit, using the [`Self::cyclotomic_inverse`] of the entry for a negative digit. The table
+    /// costs one squaring and `2^{w-2} - 1` multiplications, in exchange for a nonzero digit density of about
+    /// `1 / (w + 1)` instead of `1 / 3`. The table entries are kept uncompressed since they are only used as
+    /// multiplicands.
+    ///
+    /// # Assumptions
+    /// * `a` is a nonzero element in the cyclotomic subgroup
+    pub fn cyclotomic_pow_wnaf(
+        &self,
+        ctx: &mut Context<F>,
+        a: FqPoint<F>,
+        exp: Vec<u64>,
+        w: usize,
+    ) -> FqPoint<F> {
+        let wnaf = get_wnaf(exp, w);
+        let Some(&top) = wnaf.last() else {
+            return self.load_constant(ctx, Fq12::one());
+        };
+        // only build the part of the table that is used
+        let max_digit = wnaf.iter().map(|z| z.unsigned_abs() as usize).max().unwrap();
+        let mut table = vec![a];
+        if max_digit > 1 {
+            let a_sq = self.cyclotomic_square_uncompressed(ctx, &table[0]);
+            while 2 * table.len() - 1 < max_digit {
+                let next = self.mul(ctx, table.last().unwrap(), &a_sq);
+                table.push(next);
             }
         }
-        if naf[0] == 0 {
-            out = Some(self.cyclotomic_decompress(ctx, compression));
+        let entry = |ctx: &mut Context<F>, z: i64| {
+            let power = table[z.unsigned_abs() as usize / 2].clone();
+            if z > 0 {
+                power
+            } else {
+                self.cyclotomic_inverse(ctx, power)
+            }
+        };
+
+        let mut out = entry(ctx, top);
+        for &z in wnaf.iter().rev().skip(1) {
+            out = self.cyclotomic_square_uncompressed(ctx, &out);
+            if z != 0 {
+                let term = entry(ctx, z);
+                out = self.mul(ctx, &out, &term);
+            }
         }
-        out.unwrap_or(a)
+        out
+    }
+
+    /// Returns `a^e` where `e` is given by its little-endian bits `exp`, which are witnesses rather than constants.
+    ///
+    /// Same as [`FieldChip::pow_var`] with fixed windows of `window_bits` bits, but squares with
+    /// [`Self::cyclotomic_square_uncompressed`]. Unlike [`Self::cyclotomic_pow`], the constraints do not depend on the
+    /// value of `e`. `window_bits = 4` is a good default.
+    ///
+    /// # Assumptions
+    /// * `a` is in the cyclotomic subgroup
+    /// * every element of `exp` is constrained to be a bit (e.g., it comes from `num_to_bits`)
+    pub fn cyclotomic_pow_var(
+        &self,
+        ctx: &mut Context<F>,
+        a: &FqPoint<F>,
+        exp: &[AssignedValue<F>],
+        window_bits: usize,
+    ) -> FqPoint<F> {
+        self.pow_var_with_square(ctx, a, exp, window_bits, |ctx, x| {
+            self.cyclotomic_square_uncompressed(ctx, x)
+        })
     }
 
     #[allow(non_snake_case)]
     // use equation for (p^4 - p^2 + 1)/r in Section 5 of https://eprint.iacr.org/2008/490.pdf for BN curves
+    /// Computes `m^{(p^4 - p^2 + 1)/r}`, the hard part of [`Self::final_exp`].
+    ///
+    /// # Assumptions
+    /// * `m` is a nonzero element in the cyclotomic subgroup, e.g. the output of [`Self::easy_part`]
     pub fn hard_part_BN(
         &self,
         ctx: &mut Context<F>,
@@ -374,22 +648,97 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
         T0
     }
 
-    // out = in^{ (q^6 - 1)*(q^2 + 1) }
+    /// Computes `m^{2x(6x^2 + 3x + 1)(p^4 - p^2 + 1)/r}` for `x = BN_X`, using Algorithm 6 of
+    /// Fuentes-Castañeda, Knapp and Rodríguez-Henríquez (https://eprint.iacr.org/2011/297.pdf) as
+    /// implemented in gnark. Like [`Self::hard_part_BN`] it uses three [`Self::cyclotomic_pow`] by `x`.
+    ///
     /// # Assumptions
-    /// * `a` is nonzero field point
+    /// * `m` is a nonzero element in the cyclotomic subgroup, e.g. the output of [`Self::easy_part`]
+    pub fn hard_part_fuentes(
+        &self,
+        ctx: &mut Context<F>,
+        m: <Self as FieldChip<F>>::FieldPoint,
+    ) -> <Self as FieldChip<F>>::FieldPoint {
+        // t0 = m^{-2x}
+        let t0 = self.cyclotomic_pow(ctx, m.clone(), vec![BN_X]);
+        let t0 = self.conjugate(ctx, t0);
+        let t0 = self.cyclotomic_square_uncompressed(ctx, &t0);
+        // t1 = m^{-6x}
+        let t1 = self.cyclotomic_square_uncompressed(ctx, &t0);
+        let t1 = self.mul(ctx, &t0, &t1);
+        // t2 = m^{6x^2}
+        let t2 = self.cyclotomic_pow(ctx, t1.clone(), vec![BN_X]);
+        let t2 = self.conjugate(ctx, t2);
+        // t1 = m^{6x^2 + 6x}
+        let t3 = self.conjugate(ctx, t1);
+        let t1 = self.mul(ctx, &t2, &t3);
+        // t4 = m^{12x^3 + 6x^2 + 6x}
+        let t3 = self.cyclotomic_square_uncompressed(ctx, &t2);
+        let t4 = self.cyclotomic_pow(ctx, t3, vec![BN_X]);
+        let t4 = self.mul(ctx, &t1, &t4);
+        // t3 = m^{12x^3 + 6x^2 + 4x}
+        let t3 = self.mul(ctx, &t0, &t4);
+        // out = m^{12x^3 + 12x^2 + 6x + 1} * (t3)^p * (t4)^{p^2} * (m^{-1} t3)^{p^3}
+        let out = self.mul(ctx, &t2, &t4);
+        let out = self.mul(ctx, &m, &out);
+        let t3p = self.frobenius_map(ctx, &t3, 1);
+        let out = self.mul(ctx, &t3p, &out);
+        let t4p2 = self.frobenius_map(ctx, &t4, 2);
+        let out = self.mul(ctx, &t4p2, &out);
+        let m_inv = self.cyclotomic_inverse(ctx, m);
+        let m_inv_t3 = self.mul(ctx, &m_inv, &t3);
+        let m_inv_t3_p3 = self.frobenius_map(ctx, &m_inv_t3, 3);
+        self.mul(ctx, &m_inv_t3_p3, &out)
+    }
+
+    // out = in^{ (q^6 - 1)*(q^2 + 1) }
+    /// Constrains `a` to be a nonzero field point.
+    ///
+    /// The output is in the cyclotomic subgroup `GΦ₁₂`, so it is a valid input to
+    /// [`Self::cyclotomic_compress`], [`Self::cyclotomic_square_uncompressed`] and [`Self::cyclotomic_pow`].
     pub fn easy_part(
         &self,
         ctx: &mut Context<F>,
         a: <Self as FieldChip<F>>::FieldPoint,
     ) -> <Self as FieldChip<F>>::FieldPoint {
-        // a^{q^6} = conjugate of a
-        let f1 = self.conjugate(ctx, a.clone());
-        let f2 = self.divide_unsafe(ctx, &f1, a);
+        // a^{q^6} = conjugate of a, so f2 = a^{q^6 - 1} = conj(a) / a
+        // `conj(a)` is never assigned: the division constraint `f2 * a - conj(a) == 0` is checked without carrying,
+        // subtracting the coefficients of even powers of `w` and adding those of odd powers. Its overflow is one
+        // `Fp12` product plus a proper element, the same as `divide_unsafe`, and it saves the six `Fp` negations.
+        // The frobenius map and final multiplication stay carried: a no-carry Frobenius coefficient is already
+        // of size `p^2`, and multiplying it by `f2` would exceed what `carry_mod` accepts.
+        let a_val = self.get_assigned_value(&(&a).into());
+        let mut f1_val = a_val;
+        f1_val.conjugate();
+        let a_inv: Fq12 = Option::from(a_val.invert()).unwrap_or_default();
+        let f2 = self.load_private(ctx, f1_val * a_inv);
+
+        // constrained division: a zero `a` would otherwise let the prover pick any `f2`
+        let a_is_zero = self.is_zero(ctx, a.clone());
+        self.gate().assert_is_const(ctx, &a_is_zero, &F::ZERO);
+        let fp_chip = self.fp_chip();
+        let f2_a = self.mul_no_carry(ctx, &f2, &a);
+        let f2_a_minus_f1 = f2_a
+            .into_iter()
+            .zip(&a)
+            .enumerate()
+            .map(|(i, (f2_a_i, a_i))| {
+                if i % 2 == 1 {
+                    fp_chip.add_no_carry(ctx, f2_a_i, a_i)
+                } else {
+                    fp_chip.sub_no_carry(ctx, f2_a_i, a_i)
+                }
+            })
+            .collect();
+        self.check_carry_mod_to_zero(ctx, FieldVector(f2_a_minus_f1));
+
         let f3 = self.frobenius_map(ctx, &f2, 2);
         self.mul(ctx, &f3, &f2)
     }
 
     // out = in^{(q^12 - 1)/r}
+    /// Equal to `hard_part_BN(easy_part(a))`; callers that already hold a cyclotomic element can call
+    /// [`Self::hard_part_BN`] directly.
     pub fn final_exp(
         &self,
         ctx: &mut Context<F>,
@@ -399,4 +748,34 @@ impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
         let f = self.hard_part_BN(ctx, f0);
         f
     }
+
+    /// Same as [`Self::final_exp`], with the addition chain for the hard part chosen by `hard_part`.
+    /// See [`HardPart`] for how the outputs of the two differ.
+    pub fn final_exp_with(
+        &self,
+        ctx: &mut Context<F>,
+        a: <Self as FieldChip<F>>::FieldPoint,
+        hard_part: HardPart,
+    ) -> <Self as FieldChip<F>>::FieldPoint {
+        let f0 = self.easy_part(ctx, a);
+        match hard_part {
+            HardPart::Scott => self.hard_part_BN(ctx, f0),
+            HardPart::FuentesCastaneda => self.hard_part_fuentes(ctx, f0),
+        }
+    }
+
+    /// Computes [`Self::final_exp`] of each element of `inputs`, returning the outputs in the same order.
+    ///
+    /// The easy parts of all inputs are computed first, then the hard parts. Every division, Frobenius map and
+    /// cyclotomic squaring is a constraint on its own input, so no arithmetic is shared between inputs. If only
+    /// the product of several pairings is needed, prefer [`super::pairing::PairingChip::multi_miller_loop`]
+    /// followed by a single `final_exp`.
+    pub fn final_exp_batch(
+        &self,
+        ctx: &mut Context<F>,
+        inputs: &[<Self as FieldChip<F>>::FieldPoint],
+    ) -> Vec<<Self as FieldChip<F>>::FieldPoint> {
+        let easy_parts = inputs.iter().map(|a| self.easy_part(ctx, a.clone())).collect::<Vec<_>>();
+        easy_parts.into_iter().map(|f0| self.hard_part_BN(ctx, f0)).collect()
+    }
 }
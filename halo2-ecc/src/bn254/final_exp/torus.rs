@@ -0,0 +1,250 @@
+//! Torus-based (T2) compression of the cyclotomic subgroup `GΦ₁₂`, following Section 4 of
+//! https://eprint.iacr.org/2007/429.pdf.
+//!
+//! Write `Fp12 = Fp6(w)` with `w^2 = v` and `Fp6 = Fp2(v)` with `v^3 = XI_0 + u`. An element
+//! `a = a0 + a1 w` of `GΦ₁₂` other than `±1` is represented by `c = (1 + a0) / a1` in `Fp6`, and
+//! `a = (c + w) / (c - w)`. In this representation
+//! * `a * b` is `(c_a c_b + v) / (c_a + c_b)`
+//! * `a^2` is `(c^2 + v) / (2c)`
+//! * `a^{-1} = conj(a)` is `-c`
+//!
+//! so the hard part of the final exponentiation can run on 6 instead of 12 Fp coordinates.
+//! Every torus multiplication and squaring is a division in `Fp6`, constrained by one `Fp6`
+//! multiplication, so the constraint count is comparable to [`Fp12Chip::cyclotomic_square_uncompressed`]
+//! rather than a large saving; the benefit is the smaller representation.
+//!
+//! `±1` are exactly the elements of `GΦ₁₂` with `a1 = 0`. [`Fp12Chip::torus_compress`] constrains `a1`
+//! to be nonzero, and any other operation whose result is `1` makes the circuit unsatisfiable. `-1` is
+//! represented by `c = 0` once obtained as a product. [`Fp12Chip::final_exp_torus`] handles both
+//! degenerate inputs separately.
+//!
+//! A torus element is an `Fp6` point in the layout of [`Fp6Chip`].
+use super::super::{Fp12Chip, Fp2Chip, Fp6Chip, FqPoint};
+use crate::ecc::get_naf;
+use crate::ff::Field;
+use crate::fields::{
+    fp12::{merge_fp6, split_fp6},
+    vector::FieldVector,
+    FieldChip, Selectable,
+};
+use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq12, Fq2, Fq6, BN_X, FROBENIUS_COEFF_FQ12_C1};
+use halo2_base::{
+    gates::GateInstructions,
+    utils::{modulus, BigPrimeField},
+    Context,
+};
+
+// returns `v` in `Fp6 = Fp2(v)`
+fn fp6_v() -> Fq6 {
+    Fq6 { c0: Fq2::ZERO, c1: Fq2::ONE, c2: Fq2::ZERO }
+}
+
+/// Returns `g^{(p^6 - 1)(p^2 + 1)}` for `g = 1 + w`, a fixed element of `GΦ₁₂` of large order.
+///
+/// [`Fp12Chip::final_exp_torus`] runs the hard part on it in place of `±1`, which have no torus representation.
+pub fn torus_substitute() -> Fq12 {
+    let p = modulus::<Fq>();
+    let exp = (p.pow(6) - 1u64) * (p.pow(2) + 1u64);
+    Fq12 { c0: Fq6::ONE, c1: Fq6::ONE }.pow_vartime(exp.to_u64_digits())
+}
+
+impl<'chip, F: BigPrimeField> Fp12Chip<'chip, F> {
+    /// Returns the torus representation `c = (1 + a0) / a1` of `a = a0 + a1 w`.
+    ///
+    /// # Assumptions
+    /// * `a` is in the cyclotomic subgroup
+    /// * `a != ±1`, i.e. `a1 != 0`; this is constrained, so the circuit is unsatisfiable otherwise
+    pub fn torus_compress(&self, ctx: &mut Context<F>, a: &FqPoint<F>) -> FqPoint<F> {
+        let fp6_chip = Fp6Chip::<F>::new(self.fp_chip());
+        let (a0, a1) = split_fp6(a.clone());
+        let a1_is_zero = fp6_chip.is_zero(ctx, a1.clone());
+        self.gate().assert_is_const(ctx, &a1_is_zero, &F::ZERO);
+        let num = fp6_chip.add_constant_no_carry(ctx, a0, Fq6::ONE);
+        fp6_chip.divide_unsafe(ctx, num, a1)
+    }
+
+    /// Returns `a = (c + w) / (c - w) = ((c^2 + v) + 2c w) / (c^2 - v)` as an Fp12 point.
+    ///
+    /// `c^2 - v` is never zero because `v` is not a square in `Fp6`.
+    pub fn torus_decompress(&self, ctx: &mut Context<F>, c: &FqPoint<F>) -> FqPoint<F> {
+        let fp6_chip = Fp6Chip::<F>::new(self.fp_chip());
+        let c_sq = fp6_chip.mul_no_carry(ctx, c, c);
+
+        let a0_num = fp6_chip.add_constant_no_carry(ctx, c_sq.clone(), fp6_v());
+        let den = fp6_chip.add_constant_no_carry(ctx, c_sq, -fp6_v());
+        let a1_num = fp6_chip.scalar_mul_no_carry(ctx, c, 2);
+
+        let a0 = fp6_chip.divide_unsafe(ctx, a0_num, den.clone());
+        let a1 = fp6_chip.divide_unsafe(ctx, a1_num, den);
+        merge_fp6(a0, a1)
+    }
+
+    /// Returns the torus representation of the product, `(a b + v) / (a + b)`.
+    ///
+    /// The circuit is unsatisfiable if the product is `1`.
+    pub fn torus_mul(&self, ctx: &mut Context<F>, a: &FqPoint<F>, b: &FqPoint<F>) -> FqPoint<F> {
+        let fp6_chip = Fp6Chip::<F>::new(self.fp_chip());
+        let ab = fp6_chip.mul_no_carry(ctx, a, b);
+        let num = fp6_chip.add_constant_no_carry(ctx, ab, fp6_v());
+        let den = fp6_chip.add_no_carry(ctx, a, b);
+        fp6_chip.divide_unsafe(ctx, num, den)
+    }
+
+    /// Returns the torus representation of the square, `(a^2 + v) / (2a)`.
+    ///
+    /// The circuit is unsatisfiable if the square is `1`.
+    pub fn torus_square(&self, ctx: &mut Context<F>, a: &FqPoint<F>) -> FqPoint<F> {
+        let fp6_chip = Fp6Chip::<F>::new(self.fp_chip());
+        let a_sq = fp6_chip.mul_no_carry(ctx, a, a);
+        let num = fp6_chip.add_constant_no_carry(ctx, a_sq, fp6_v());
+        let den = fp6_chip.scalar_mul_no_carry(ctx, a, 2);
+        fp6_chip.divide_unsafe(ctx, num, den)
+    }
+
+    /// Returns the torus representation of `conj(a) = a^{-1}`, which is `-c`.
+    pub fn torus_conjugate(&self, ctx: &mut Context<F>, a: &FqPoint<F>) -> FqPoint<F> {
+        Fp6Chip::<F>::new(self.fp_chip()).negate(ctx, a.clone())
+    }
+
+    /// Returns the torus representation of `a^{p^power}`.
+    ///
+    /// With `gamma = w^{p^power - 1}` in `Fp2`, `a^{p^power} = a0^{p^power} + a1^{p^power} gamma w`,
+    /// so the output is `c^{p^power} / gamma`. Its coefficient of `v^j` is
+    /// `c_j^{p^power} * gamma^{2j - 1}`, a constant multiple of `c_j` or its conjugate.
+    pub fn torus_frobenius_map(
+        &self,
+        ctx: &mut Context<F>,
+        a: &FqPoint<F>,
+        power: usize,
+    ) -> FqPoint<F> {
+        assert_eq!(a.0.len(), 6);
+        let pow = power % 12;
+        let fp_chip = self.fp_chip();
+        let fp2_chip = Fp2Chip::<F>::new(fp_chip);
+        let gamma = FROBENIUS_COEFF_FQ12_C1[pow];
+        let gamma_inv = gamma.invert().unwrap();
+        let out_fp2 = (0..3)
+            .map(|j| {
+                let c_j = FieldVector(vec![a[j].clone(), a[j + 3].clone()]);
+                let c_j = if pow % 2 != 0 { fp2_chip.conjugate(ctx, c_j) } else { c_j };
+                let coeff = gamma.pow_vartime([2 * j as u64]) * gamma_inv;
+                if coeff == Fq2::ONE {
+                    c_j
+                } else {
+                    let out_nocarry = fp2_chip.mul_constant_no_carry(ctx, c_j, coeff);
+                    fp2_chip.carry_mod(ctx, out_nocarry)
+                }
+            })
+            .collect::<Vec<_>>();
+        out_fp2.iter().map(|x| x[0].clone()).chain(out_fp2.iter().map(|x| x[1].clone())).collect()
+    }
+
+    // exp is in little-endian
+    /// Same as [`Self::cyclotomic_pow`] in the torus representation.
+    ///
+    /// # Assumptions
+    /// * `exp` is nonzero
+    /// * no partial product or square is `1`, which holds for `a` of large order
+    pub fn torus_pow(&self, ctx: &mut Context<F>, a: FqPoint<F>, exp: Vec<u64>) -> FqPoint<F> {
+        let mut base = a;
+        let mut out: Option<FqPoint<F>> = None;
+        let mut naf = get_naf(exp);
+        while naf.last() == Some(&0) {
+            naf.pop();
+        }
+
+        for (i, &z) in naf.iter().enumerate() {
+            if i != 0 {
+                // base = a^{2^i}
+                base = self.torus_square(ctx, &base);
+            }
+            if z != 0 {
+                assert!(z == 1 || z == -1);
+                let term = if z == 1 { base.clone() } else { self.torus_conjugate(ctx, &base) };
+                out = Some(match out {
+                    None => term,
+                    Some(out) => self.torus_mul(ctx, &out, &term),
+                });
+            }
+        }
+        out.expect("exp should be nonzero")
+    }
+
+    #[allow(non_snake_case)]
+    /// Same as [`Self::hard_part_BN`], with `m` and all intermediate values in the torus
+    /// representation. The last multiplication is done in `Fp12` so that the output may be `1`.
+    ///
+    /// # Assumptions
+    /// * `m` is the torus representation of an element of large order in the cyclotomic subgroup
+    pub fn hard_part_BN_torus(&self, ctx: &mut Context<F>, m: FqPoint<F>) -> FqPoint<F> {
+        // see `hard_part_BN` for the meaning of each variable
+        let mp = self.torus_frobenius_map(ctx, &m, 1);
+        let mp2 = self.torus_frobenius_map(ctx, &m, 2);
+        let mp3 = self.torus_frobenius_map(ctx, &m, 3);
+
+        let mp2_mp3 = self.torus_mul(ctx, &mp2, &mp3);
+        let y0 = self.torus_mul(ctx, &mp, &mp2_mp3);
+        let y1 = self.torus_conjugate(ctx, &m);
+
+        let mx = self.torus_pow(ctx, m, vec![BN_X]);
+        let mxp = self.torus_frobenius_map(ctx, &mx, 1);
+        let mx2 = self.torus_pow(ctx, mx.clone(), vec![BN_X]);
+        let mx2p = self.torus_frobenius_map(ctx, &mx2, 1);
+        let y2 = self.torus_frobenius_map(ctx, &mx2, 2);
+        let y5 = self.torus_conjugate(ctx, &mx2);
+
+        let mx3 = self.torus_pow(ctx, mx2, vec![BN_X]);
+        let mx3p = self.torus_frobenius_map(ctx, &mx3, 1);
+
+        let y3 = self.torus_conjugate(ctx, &mxp);
+        let mx_mx2p = self.torus_mul(ctx, &mx, &mx2p);
+        let y4 = self.torus_conjugate(ctx, &mx_mx2p);
+        let mx3_mx3p = self.torus_mul(ctx, &mx3, &mx3p);
+        let y6 = self.torus_conjugate(ctx, &mx3_mx3p);
+
+        // out = y0 * y1^2 * y2^6 * y3^12 * y4^18 * y5^30 * y6^36
+        let mut T0 = self.torus_square(ctx, &y6);
+        T0 = self.torus_mul(ctx, &T0, &y4);
+        T0 = self.torus_mul(ctx, &T0, &y5);
+        let mut T1 = self.torus_mul(ctx, &y3, &y5);
+        T1 = self.torus_mul(ctx, &T1, &T0);
+        T0 = self.torus_mul(ctx, &T0, &y2);
+        T1 = self.torus_square(ctx, &T1);
+        T1 = self.torus_mul(ctx, &T1, &T0);
+        T1 = self.torus_square(ctx, &T1);
+        T0 = self.torus_mul(ctx, &T1, &y1);
+        T1 = self.torus_mul(ctx, &T1, &y0);
+        T0 = self.torus_square(ctx, &T0);
+
+        let T0 = self.torus_decompress(ctx, &T0);
+        let T1 = self.torus_decompress(ctx, &T1);
+        self.mul(ctx, &T0, &T1)
+    }
+
+    /// Same output as [`Self::final_exp`], computing the hard part in the torus representation with
+    /// [`Self::hard_part_BN_torus`].
+    ///
+    /// `m = easy_part(a)` is `±1` exactly when its `w`-odd half is zero. In that case the hard part runs on
+    /// [`torus_substitute`] instead and the output is `m` itself: the hard part exponent `(p^4 - p^2 + 1) / r`
+    /// is odd, so it maps `±1` to itself.
+    ///
+    /// # Assumptions
+    /// * `a` is nonzero
+    /// * no intermediate value of the hard part is `1`, which holds unless `easy_part(a) != ±1` has small order
+    pub fn final_exp_torus(
+        &self,
+        ctx: &mut Context<F>,
+        a: <Self as FieldChip<F>>::FieldPoint,
+    ) -> <Self as FieldChip<F>>::FieldPoint {
+        let m = self.easy_part(ctx, a);
+        let fp6_chip = Fp6Chip::<F>::new(self.fp_chip());
+        let (_, m1) = split_fp6(m.clone());
+        let is_degenerate = fp6_chip.is_zero(ctx, m1);
+        let substitute = self.load_constant(ctx, torus_substitute());
+        let m_safe = self.select(ctx, substitute, m.clone(), is_degenerate);
+
+        let c = self.torus_compress(ctx, &m_safe);
+        let out = self.hard_part_BN_torus(ctx, c);
+        self.select(ctx, m, out, is_degenerate)
+    }
+}
@@ -0,0 +1,110 @@
+//! GLV scalar multiplication on BN254 G1.
+//!
+//! G1 has the efficiently computable endomorphism `phi(x, y) = (BETA * x, y)` with `phi(P) = [LAMBDA] P`,
+//! where `BETA` and `LAMBDA` are cube roots of unity in `Fq` and `Fr` respectively. A full width scalar
+//! `k` is split as `k = k1 + k2 * LAMBDA (mod r)` with `|k1|, |k2| < 2^{GLV_SCALAR_BITS}`, so `[k] P` is
+//! computed as a two point multi-scalar multiplication with half length scalars.
+//!
+//! Since the decomposition is constrained in the native field, this is only implemented for native
+//! field `Fr`, the scalar field of BN254.
+use super::{FpChip, FpPoint};
+use crate::ecc::{multi_scalar_multiply, EcPoint, EccChip};
+use crate::ff::PrimeField;
+use crate::fields::FieldChip;
+use crate::halo2_proofs::halo2curves::bn256::{Fq, Fr, G1Affine};
+use halo2_base::gates::{GateInstructions, RangeInstructions};
+use halo2_base::utils::{biguint_to_fe, fe_to_biguint, modulus};
+use halo2_base::{AssignedValue, Context, QuantumCell::Constant};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::Signed;
+
+/// Cube root of unity in `Fq` such that `(BETA * x, y) = [LAMBDA] (x, y)` on G1.
+pub const BETA: &str = "2203960485148121921418603742825762020974279258880205651966";
+/// Cube root of unity in `Fr`, the eigenvalue of `phi` on G1.
+pub const LAMBDA: &str = "4407920970296243842393367215006156084916469457145843978461";
+
+// Short basis `(A1, B1), (A2, B2)` of the lattice `{(a, b) : a + b * LAMBDA = 0 (mod r)}`,
+// with `A1 * B2 - A2 * B1 = r`
+const A1: &str = "9931322734385697763";
+const B1: &str = "-147946756881789319000765030803803410728";
+const A2: &str = "147946756881789319010696353538189108491";
+const B2: &str = "9931322734385697763";
+
+/// Upper bound on the bit length of `|k1|, |k2|` from [`glv_decompose`].
+pub const GLV_SCALAR_BITS: usize = 128;
+
+/// Returns `[(k1_is_neg, |k1|), (k2_is_neg, |k2|)]` with `k = k1 + k2 * LAMBDA (mod r)` and
+/// `|k1|, |k2| < 2^{GLV_SCALAR_BITS}`, using Babai rounding against the short lattice basis.
+pub fn glv_decompose(k: &BigUint) -> [(bool, BigUint); 2] {
+    let r = BigInt::from(modulus::<Fr>());
+    let k = BigInt::from(k % modulus::<Fr>());
+    let [a1, b1, a2, b2] = [A1, B1, A2, B2].map(|x| x.parse::<BigInt>().unwrap());
+    // round(x / r) for x >= 0
+    let round = |x: BigInt| (x * 2 + &r) / (&r * 2);
+    let c1 = round(&b2 * &k);
+    let c2 = round(-&b1 * &k);
+    let k1 = &k - &c1 * &a1 - &c2 * &a2;
+    let k2 = -&c1 * &b1 - &c2 * &b2;
+    [k1, k2].map(|x| {
+        debug_assert!(x.abs().bits() <= GLV_SCALAR_BITS as u64);
+        (x.sign() == Sign::Minus, x.abs().to_biguint().unwrap())
+    })
+}
+
+/// Computes `[k] P` for `P` in BN254 G1 using the GLV endomorphism.
+///
+/// The decomposition `(k1, k2)` is witnessed as signs and absolute values. The circuit constrains
+/// `k == k1 + k2 * LAMBDA` in `Fr`, range checks `|k1|, |k2|` to `GLV_SCALAR_BITS` bits, and
+/// conditionally negates `P` and `phi(P)` by the signs before a two point [`multi_scalar_multiply`].
+///
+/// # Assumptions
+/// * `P` is in G1 (for BN254 this is any point on the curve) and not the point at infinity
+/// * `[k] P` is not the point at infinity, as in [`crate::ecc::scalar_multiply`]
+/// * `window_bits != 0`
+pub fn glv_scalar_mult(
+    chip: &EccChip<Fr, FpChip<Fr>>,
+    ctx: &mut Context<Fr>,
+    P: EcPoint<Fr, FpPoint<Fr>>,
+    k: AssignedValue<Fr>,
+    window_bits: usize,
+) -> EcPoint<Fr, FpPoint<Fr>> {
+    let fp_chip = chip.field_chip();
+    let range = fp_chip.range();
+    let gate = range.gate();
+
+    let decomposition = glv_decompose(&fe_to_biguint(k.value()));
+    let [(k1_neg, k1_abs), (k2_neg, k2_abs)] = decomposition.map(|(is_neg, abs)| {
+        let is_neg = ctx.load_witness(Fr::from(is_neg as u64));
+        gate.assert_bit(ctx, is_neg);
+        let abs = ctx.load_witness(biguint_to_fe(&abs));
+        range.range_check(ctx, abs, GLV_SCALAR_BITS);
+        (is_neg, abs)
+    });
+
+    // k_i = |k_i| - 2 * is_neg_i * |k_i|
+    let [k1, k2] = [(k1_neg, k1_abs), (k2_neg, k2_abs)].map(|(is_neg, abs)| {
+        let neg_abs = gate.mul(ctx, is_neg, abs);
+        gate.mul_add(ctx, neg_abs, Constant(-Fr::from(2)), abs)
+    });
+    let lambda = Fr::from_str_vartime(LAMBDA).unwrap();
+    let k_decomposed = gate.mul_add(ctx, k2, Constant(lambda), k1);
+    ctx.constrain_equal(&k_decomposed, &k);
+
+    // phi(P) = (BETA * P.x, P.y)
+    let beta = Fq::from_str_vartime(BETA).unwrap();
+    let beta_x = fp_chip.mul_constant_no_carry(ctx, P.x.clone(), beta);
+    let beta_x = fp_chip.carry_mod(ctx, beta_x);
+    let phi_P = EcPoint::new(beta_x, P.y.clone());
+
+    let [P, phi_P] =
+        [(P, k1_neg), (phi_P, k2_neg)].map(|(Q, is_neg)| chip.conditional_negate(ctx, Q, is_neg));
+
+    multi_scalar_multiply::<Fr, FpChip<Fr>, G1Affine>(
+        fp_chip,
+        ctx,
+        &[P, phi_P],
+        vec![vec![k1_abs], vec![k2_abs]],
+        GLV_SCALAR_BITS,
+        window_bits,
+    )
+}
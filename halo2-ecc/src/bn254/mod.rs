@@ -1,17 +1,32 @@
 use crate::bigint::ProperCrtUint;
+use crate::ecc::EcPoint;
 use crate::fields::vector::FieldVector;
-use crate::fields::{fp, fp12, fp2};
-use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq12, Fq2};
+use crate::fields::{fp, fp12, fp2, fp6};
+use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq12, Fq2, Fq6};
 
 pub mod bls_signature;
 pub mod final_exp;
+pub mod glv;
 pub mod pairing;
 
+/// `Fp6 = Fp2[v] / (v^3 - (XI_0 + u))` and `Fp12 = Fp2[w] / (w^6 - (XI_0 + u))` for BN254, matching the
+/// quadratic nonresidue `9 + u` of halo2curves' `Fq2::mul_by_nonresidue`.
+///
+/// This is the single source of the constant for the BN254 chips: [`Fp6Chip`], [`Fp12Chip`], the line
+/// functions in [`pairing`] and the torus arithmetic in [`final_exp::torus`]. The cyclotomic compression
+/// formulas depend on it, so a curve with a different tower (e.g. `XI_0 = 1` for BLS12-381) needs its own value.
+pub const XI_0: i64 = 9;
+
 pub type FpChip<'range, F> = fp::FpChip<'range, F, Fq>;
 pub type FpPoint<F> = ProperCrtUint<F>;
 pub type FqPoint<F> = FieldVector<FpPoint<F>>;
 pub type Fp2Chip<'chip, F> = fp2::Fp2Chip<'chip, F, FpChip<'chip, F>, Fq2>;
-pub type Fp12Chip<'chip, F> = fp12::Fp12Chip<'chip, F, FpChip<'chip, F>, Fq12, 9>;
+pub type Fp6Chip<'chip, F> = fp6::Fp6Chip<'chip, F, FpChip<'chip, F>, Fq6, XI_0>;
+pub type Fp12Chip<'chip, F> = fp12::Fp12Chip<'chip, F, FpChip<'chip, F>, Fq12, XI_0>;
+/// A point of `E(Fq)`, i.e. of G1, in affine coordinates.
+pub type G1Point<F> = EcPoint<F, FpPoint<F>>;
+/// A point of the twist `E'(Fq2)`, i.e. of G2, in affine coordinates.
+pub type G2Point<F> = EcPoint<F, FqPoint<F>>;
 
 #[cfg(test)]
 pub(crate) mod tests;
@@ -1,18 +1,21 @@
 #![allow(non_snake_case)]
-use super::{Fp12Chip, Fp2Chip, FpChip, FpPoint, Fq, FqPoint};
-use crate::fields::vector::FieldVector;
+use super::{Fp12Chip, Fp2Chip, FpChip, FpPoint, Fq, FqPoint, G1Point, G2Point, XI_0};
+use crate::halo2_proofs::arithmetic::CurveAffine;
 use crate::halo2_proofs::halo2curves::bn256::{
-    Fq12, G1Affine, G2Affine, FROBENIUS_COEFF_FQ12_C1, SIX_U_PLUS_2_NAF,
+    Fq12, Fq2, G1Affine, G2Affine, BN_X, FROBENIUS_COEFF_FQ12_C1, SIX_U_PLUS_2_NAF,
 };
 use crate::{
     ecc::{EcPoint, EccChip},
-    fields::fp12::mul_no_carry_w6,
+    fields::fp12::{fp12_coeffs, fp12_from_coeffs, mul_no_carry_w6},
     fields::FieldChip,
 };
-use halo2_base::utils::BigPrimeField;
-use halo2_base::Context;
+use halo2_base::gates::{flex_gate::threads::SinglePhaseCoreManager, GateInstructions};
+use halo2_base::utils::{biguint_to_fe, BigPrimeField};
+use halo2_base::{AssignedValue, Context};
+use num_bigint::BigUint;
+use prepared_g2::PreparedG2;
 
-const XI_0: i64 = 9;
+pub mod prepared_g2;
 
 // Inputs:
 //  Q0 = (x_1, y_1) and Q1 = (x_2, y_2) are points in E(Fp2)
@@ -104,10 +107,7 @@ pub fn sparse_fp12_multiply<F: BigPrimeField>(
 ) -> FqPoint<F> {
     assert_eq!(a.0.len(), 12);
     assert_eq!(b_fp2_coeffs.len(), 6);
-    let mut a_fp2_coeffs = Vec::with_capacity(6);
-    for i in 0..6 {
-        a_fp2_coeffs.push(FieldVector(vec![a[i].clone(), a[i + 6].clone()]));
-    }
+    let a_fp2_coeffs = fp12_coeffs(a);
     // a * b as element of Fp2[w] without evaluating w^6 = (XI_0 + u)
     let mut prod_2d = vec![None; 11];
     for i in 0..6 {
@@ -147,14 +147,7 @@ pub fn sparse_fp12_multiply<F: BigPrimeField>(
         out_fp2.push(prod);
     }
 
-    let mut out_coeffs = Vec::with_capacity(12);
-    for fp2_coeff in &out_fp2 {
-        out_coeffs.push(fp2_coeff[0].clone());
-    }
-    for fp2_coeff in &out_fp2 {
-        out_coeffs.push(fp2_coeff[1].clone());
-    }
-    FieldVector(out_coeffs)
+    fp12_from_coeffs(out_fp2.try_into().unwrap())
 }
 
 // Input:
@@ -191,6 +184,26 @@ pub fn fp12_multiply_with_line_equal<F: BigPrimeField>(
     sparse_fp12_multiply::<F>(fp2_chip, ctx, g, &line)
 }
 
+// Input:
+// - sparse = [c_0, ..., c_5] where each `c_i` is an optional Fp2 coefficient of `w^i`, `None` meaning zero
+// Output:
+// - the dense Fp12 point `sum c_i w^i`, with the `None` coefficients loaded as constant zeros
+fn sparse_to_dense<F: BigPrimeField>(
+    fp_chip: &FpChip<F>,
+    ctx: &mut Context<F>,
+    sparse: Vec<Option<FqPoint<F>>>,
+) -> FqPoint<F> {
+    assert_eq!(sparse.len(), 6);
+    let zero_fp = fp_chip.load_constant(ctx, Fq::zero());
+    let coeffs = sparse
+        .into_iter()
+        .map(|coeff| {
+            coeff.unwrap_or_else(|| [zero_fp.clone(), zero_fp.clone()].into_iter().collect())
+        })
+        .collect::<Vec<_>>();
+    fp12_from_coeffs(coeffs.try_into().unwrap())
+}
+
 // Assuming curve is of form `y^2 = x^3 + b` for now (a = 0) for less operations
 // Value of `b` is never used
 // Inputs:
@@ -232,24 +245,7 @@ pub fn miller_loop_BN<F: BigPrimeField>(
     assert_eq!(sparse_f.len(), 6);
 
     let fp_chip = ecc_chip.field_chip.fp_chip();
-    let zero_fp = fp_chip.load_constant(ctx, Fq::zero());
-    let mut f_coeffs = Vec::with_capacity(12);
-    for coeff in &sparse_f {
-        if let Some(fp2_point) = coeff {
-            f_coeffs.push(fp2_point[0].clone());
-        } else {
-            f_coeffs.push(zero_fp.clone());
-        }
-    }
-    for coeff in &sparse_f {
-        if let Some(fp2_point) = coeff {
-            f_coeffs.push(fp2_point[1].clone());
-        } else {
-            f_coeffs.push(zero_fp.clone());
-        }
-    }
-
-    let mut f = FieldVector(f_coeffs);
+    let mut f = sparse_to_dense(fp_chip, ctx, sparse_f);
 
     let fp12_chip = Fp12Chip::<F>::new(fp_chip);
     loop {
@@ -315,25 +311,7 @@ pub fn multi_miller_loop_BN<F: BigPrimeField>(
     let mut f = {
         let sparse_f =
             sparse_line_function_equal::<F>(ecc_chip.field_chip(), ctx, pairs[0].1, pairs[0].0);
-        assert_eq!(sparse_f.len(), 6);
-
-        let zero_fp = fp_chip.load_constant(ctx, Fq::zero());
-        let mut f_coeffs = Vec::with_capacity(12);
-        for coeff in &sparse_f {
-            if let Some(fp2_point) = coeff {
-                f_coeffs.push(fp2_point[0].clone());
-            } else {
-                f_coeffs.push(zero_fp.clone());
-            }
-        }
-        for coeff in &sparse_f {
-            if let Some(fp2_point) = coeff {
-                f_coeffs.push(fp2_point[1].clone());
-            } else {
-                f_coeffs.push(zero_fp.clone());
-            }
-        }
-        FieldVector(f_coeffs)
+        sparse_to_dense(fp_chip, ctx, sparse_f)
     };
     for &(a, b) in pairs.iter().skip(1) {
         f = fp12_multiply_with_line_equal::<F>(ecc_chip.field_chip(), ctx, &f, b, a);
@@ -444,6 +422,48 @@ pub fn neg_twisted_frobenius<F: BigPrimeField>(
     EcPoint::new(out_x, out_y)
 }
 
+impl<'chip, 'fp, F: BigPrimeField> EccChip<'chip, F, Fp2Chip<'fp, F>> {
+    /// Returns whether `Q` lies in the prime order subgroup G2 of the twist `E'(Fp2)`.
+    ///
+    /// For BN curves `p = 6x^2 (mod r)`, so the untwist-Frobenius-twist endomorphism `psi` acts on G2
+    /// as multiplication by `6x^2`. We check `psi(Q) == [6x^2]Q`, which only holds for points in G2.
+    ///
+    /// `[6x^2]Q` is computed with [`EccChip::scalar_mult_naf`], whose additions are all complete. A point outside
+    /// G2 may have small order, so the incomplete additions of [`EccChip::scalar_mult`] could hit `x_1 == x_2`
+    /// and leave the slope unconstrained.
+    ///
+    /// # Assumptions
+    /// * `Q` is a point on the twist or the point at infinity `(0, 0)`, which lies in G2
+    pub fn is_in_subgroup_g2(
+        &self,
+        ctx: &mut Context<F>,
+        Q: &EcPoint<F, FqPoint<F>>,
+    ) -> AssignedValue<F> {
+        // Frobenius coefficient coeff[1][j] = ((9+u)^{(p-1)/6})^j
+        let c2 = FROBENIUS_COEFF_FQ12_C1[1] * FROBENIUS_COEFF_FQ12_C1[1];
+        let c3 = c2 * FROBENIUS_COEFF_FQ12_C1[1];
+        let c2 = self.field_chip.load_constant(ctx, c2);
+        let c3 = self.field_chip.load_constant(ctx, c3);
+        let psi_Q = twisted_frobenius::<F>(self, ctx, Q.clone(), c2, c3);
+
+        let six_x_sq = BigUint::from(BN_X) * BN_X * 6u64;
+        let scalar = ctx.load_constant(biguint_to_fe(&six_x_sq));
+        let (six_x_sq_Q, _) =
+            self.scalar_mult_naf(ctx, Q.clone(), scalar, six_x_sq.bits() as usize, 4);
+        self.is_equal(ctx, psi_Q, six_x_sq_Q)
+    }
+
+    /// Constrains `Q` to lie in G2, see [`Self::is_in_subgroup_g2`]. The circuit is unsatisfiable for a
+    /// point on the twist outside of G2.
+    ///
+    /// # Assumptions
+    /// * `Q` is a point on the twist or the point at infinity `(0, 0)`
+    pub fn assert_in_subgroup_g2(&self, ctx: &mut Context<F>, Q: &EcPoint<F, FqPoint<F>>) {
+        let is_in_subgroup = self.is_in_subgroup_g2(ctx, Q);
+        self.field_chip.gate().assert_is_const(ctx, &is_in_subgroup, &F::ONE);
+    }
+}
+
 // To avoid issues with mutably borrowing twice (not allowed in Rust), we only store fp_chip and construct g2_chip and fp12_chip in scope when needed for temporary mutable borrows
 pub struct PairingChip<'chip, F: BigPrimeField> {
     pub fp_chip: &'chip FpChip<'chip, F>,
@@ -454,17 +474,102 @@ impl<'chip, F: BigPrimeField> PairingChip<'chip, F> {
         Self { fp_chip }
     }
 
+    /// Loads `point` as a private witness, constrained to lie on `E: y^2 = x^3 + 3`.
+    /// See [`Self::load_g1_witness`].
     pub fn load_private_g1(&self, ctx: &mut Context<F>, point: G1Affine) -> EcPoint<F, FpPoint<F>> {
-        let g1_chip = EccChip::new(self.fp_chip);
-        g1_chip.load_private::<G1Affine>(ctx, (point.x, point.y))
+        self.load_g1_witness(ctx, (point.x, point.y))
     }
 
+    /// Loads `point` as a private witness, constrained to lie on the twist.
+    /// See [`Self::load_g2_witness`].
     pub fn load_private_g2(&self, ctx: &mut Context<F>, point: G2Affine) -> EcPoint<F, FqPoint<F>> {
+        self.load_g2_witness(ctx, (point.x, point.y))
+    }
+
+    /// Loads a known G1 point as a constant. Panics if `point` is not on the curve.
+    pub fn load_g1(&self, ctx: &mut Context<F>, point: G1Affine) -> G1Point<F> {
+        assert!(bool::from(point.is_on_curve()), "point is not on the curve");
+        let g1_chip = EccChip::new(self.fp_chip);
+        g1_chip.assign_constant_point(ctx, point)
+    }
+
+    /// Loads a known G2 point as a constant. Panics if `point` is not on the twist.
+    pub fn load_g2(&self, ctx: &mut Context<F>, point: G2Affine) -> G2Point<F> {
+        assert!(bool::from(point.is_on_curve()), "point is not on the twist");
         let fp2_chip = Fp2Chip::new(self.fp_chip);
         let g2_chip = EccChip::new(&fp2_chip);
-        g2_chip.load_private::<G2Affine>(ctx, (point.x, point.y))
+        g2_chip.assign_constant_point(ctx, point)
     }
 
+    /// Loads the affine coordinates `(x, y)` as private witnesses and constrains `y^2 = x^3 + 3`, so the circuit
+    /// is unsatisfiable for a point off the curve. The point at infinity is not allowed.
+    ///
+    /// This does not check membership in G1, which is the whole curve for BN254.
+    pub fn load_g1_witness(&self, ctx: &mut Context<F>, (x, y): (Fq, Fq)) -> G1Point<F> {
+        let g1_chip = EccChip::new(self.fp_chip);
+        g1_chip.load_private::<G1Affine>(ctx, (x, y))
+    }
+
+    /// Loads the affine coordinates `(x, y)` as private witnesses and constrains `y^2 = x^3 + 3 / (9 + u)`, so the
+    /// circuit is unsatisfiable for a point off the twist. The point at infinity is not allowed.
+    ///
+    /// This does not check membership in G2; use [`EccChip::assert_in_subgroup_g2`] for that.
+    pub fn load_g2_witness(&self, ctx: &mut Context<F>, (x, y): (Fq2, Fq2)) -> G2Point<F> {
+        let fp2_chip = Fp2Chip::new(self.fp_chip);
+        let g2_chip = EccChip::new(&fp2_chip);
+        g2_chip.load_private::<G2Affine>(ctx, (x, y))
+    }
+
+    /// Returns whether `Q` lies in G2, see [`EccChip::is_in_subgroup_g2`].
+    ///
+    /// # Assumptions
+    /// * `Q` is a point on the twist or the point at infinity `(0, 0)`
+    pub fn is_in_subgroup_g2(
+        &self,
+        ctx: &mut Context<F>,
+        Q: &EcPoint<F, FqPoint<F>>,
+    ) -> AssignedValue<F> {
+        let fp2_chip = Fp2Chip::<F>::new(self.fp_chip);
+        EccChip::new(&fp2_chip).is_in_subgroup_g2(ctx, Q)
+    }
+
+    /// Computes `sum_i [scalars[i]] points[i]` for points on the twist, see [`EccChip::variable_base_msm_custom`].
+    ///
+    /// If `check_subgroup` is true, each point is also constrained to lie in G2 with [`EccChip::assert_in_subgroup_g2`],
+    /// which accepts the point at infinity `(0, 0)`.
+    ///
+    /// # Assumptions
+    /// * Same as [`EccChip::variable_base_msm_custom`]; in particular any point may be `(0, 0)`
+    pub fn variable_base_msm_g2(
+        &self,
+        builder: &mut SinglePhaseCoreManager<F>,
+        points: &[EcPoint<F, FqPoint<F>>],
+        scalars: Vec<Vec<AssignedValue<F>>>,
+        max_bits: usize,
+        window_bits: usize,
+        check_subgroup: bool,
+    ) -> EcPoint<F, FqPoint<F>> {
+        let fp2_chip = Fp2Chip::<F>::new(self.fp_chip);
+        let g2_chip = EccChip::new(&fp2_chip);
+        if check_subgroup {
+            let ctx = builder.main();
+            for point in points {
+                g2_chip.assert_in_subgroup_g2(ctx, point);
+            }
+        }
+        g2_chip.variable_base_msm_custom::<G2Affine>(
+            builder,
+            points,
+            scalars,
+            max_bits,
+            window_bits,
+        )
+    }
+
+    /// Returns the optimal ate Miller loop `f_{6x+2,Q}(P)` without the final exponentiation.
+    ///
+    /// The output is exactly the input consumed by [`Self::final_exp`] in [`Self::pairing`], so callers
+    /// may combine several Miller loops and apply a single `final_exp`.
     pub fn miller_loop(
         &self,
         ctx: &mut Context<F>,
@@ -482,6 +587,10 @@ impl<'chip, F: BigPrimeField> PairingChip<'chip, F> {
         )
     }
 
+    /// Returns `prod_i miller_loop(Q_i, P_i)` for `pairs = [(P_i, Q_i)]`.
+    ///
+    /// The squarings of the Miller loop accumulator are shared across all pairs, so this is cheaper
+    /// than multiplying separate [`Self::miller_loop`] outputs. Apply [`Self::final_exp`] once to the result.
     pub fn multi_miller_loop(
         &self,
         ctx: &mut Context<F>,
@@ -497,11 +606,29 @@ impl<'chip, F: BigPrimeField> PairingChip<'chip, F> {
         )
     }
 
+    /// Same as [`Self::multi_miller_loop`] for G2 points whose line functions were precomputed
+    /// with [`PreparedG2::new`], e.g. a fixed verification key. No G2 arithmetic is done in circuit.
+    pub fn miller_loop_prepared(
+        &self,
+        ctx: &mut Context<F>,
+        pairs: &[(&EcPoint<F, FpPoint<F>>, &PreparedG2)],
+    ) -> FqPoint<F> {
+        let fp2_chip = Fp2Chip::<F>::new(self.fp_chip);
+        prepared_g2::miller_loop_prepared(&fp2_chip, ctx, pairs)
+    }
+
     pub fn final_exp(&self, ctx: &mut Context<F>, f: FqPoint<F>) -> FqPoint<F> {
         let fp12_chip = Fp12Chip::<F>::new(self.fp_chip);
         fp12_chip.final_exp(ctx, f)
     }
 
+    /// Returns `f^{(p^6 - 1)(p^2 + 1)}`, the easy part of [`Self::final_exp`], which maps `f` into the
+    /// cyclotomic subgroup. Constrains `f` to be nonzero.
+    pub fn final_exp_easy_part(&self, ctx: &mut Context<F>, f: FqPoint<F>) -> FqPoint<F> {
+        let fp12_chip = Fp12Chip::<F>::new(self.fp_chip);
+        fp12_chip.easy_part(ctx, f)
+    }
+
     // optimal Ate pairing
     pub fn pairing(
         &self,
@@ -515,14 +642,34 @@ impl<'chip, F: BigPrimeField> PairingChip<'chip, F> {
         fp12_chip.final_exp(ctx, f0)
     }
 
+    /// Returns whether `prod_i e(P_i, Q_i) == 1` for `pairs = [(P_i, Q_i)]`, as a constrained boolean.
+    ///
+    /// Runs a single [`Self::multi_miller_loop`] followed by one [`Self::final_exp`].
+    /// An empty product is 1, so an empty `pairs` returns a constant 1.
+    pub fn is_pairing_product_one(
+        &self,
+        ctx: &mut Context<F>,
+        pairs: &[(&EcPoint<F, FpPoint<F>>, &EcPoint<F, FqPoint<F>>)],
+    ) -> AssignedValue<F> {
+        if pairs.is_empty() {
+            return ctx.load_constant(F::ONE);
+        }
+        let mml = self.multi_miller_loop(ctx, pairs.to_vec());
+        let fp12_chip = Fp12Chip::<F>::new(self.fp_chip);
+        let fe = fp12_chip.final_exp(ctx, mml);
+        let fp12_one = fp12_chip.load_constant(ctx, Fq12::one());
+        fp12_chip.is_equal(ctx, fe, fp12_one)
+    }
+
     /*
      * Conducts an efficient pairing check e(P, Q) = e(S, T) using only one
      * final exponentiation. In particular, this constraints
      * (e'(-P, Q)e'(S, T))^x = 1, where e' is the optimal ate pairing without
      * the final exponentiation. Reduces number of necessary advice cells by
      * ~30%.
+     * Cheaper than `is_pairing_product_one` since it does not need to output a boolean.
      */
-    pub fn pairing_check(
+    pub fn assert_pairing_eq(
         &self,
         ctx: &mut Context<F>,
         Q: &EcPoint<F, FqPoint<F>>,
@@ -538,4 +685,19 @@ impl<'chip, F: BigPrimeField> PairingChip<'chip, F> {
         let fp12_one = fp12_chip.load_constant(ctx, Fq12::one());
         fp12_chip.assert_equal(ctx, fe, fp12_one);
     }
+
+    /// Constrains `e(P, Q) == e(S, T)`.
+    #[deprecated(
+        note = "renamed to `assert_pairing_eq`; use `is_pairing_product_one` for a boolean result"
+    )]
+    pub fn pairing_check(
+        &self,
+        ctx: &mut Context<F>,
+        Q: &EcPoint<F, FqPoint<F>>,
+        P: &EcPoint<F, FpPoint<F>>,
+        T: &EcPoint<F, FqPoint<F>>,
+        S: &EcPoint<F, FpPoint<F>>,
+    ) {
+        self.assert_pairing_eq(ctx, Q, P, T, S)
+    }
 }
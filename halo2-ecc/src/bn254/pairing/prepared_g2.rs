@@ -0,0 +1,182 @@
+//! Miller loop for G2 points that are known when the circuit is built, e.g. a fixed verification key.
+//!
+//! The line functions of the Miller loop only depend on `P` through linear terms in `P.x` and `P.y`.
+//! When `Q` is fixed, all the G2 arithmetic (doublings, additions and the Fp2 coefficients of each line)
+//! can be done out of circuit, and only the products with `P.x, P.y` are constrained.
+use super::{sparse_fp12_multiply, sparse_to_dense};
+use crate::bn254::{Fp12Chip, Fp2Chip, FpPoint, FqPoint, XI_0};
+use crate::ecc::EcPoint;
+use crate::ff::Field;
+use crate::fields::{vector::FieldVector, FieldChip};
+use crate::group::{Curve, Group};
+use crate::halo2_proofs::halo2curves::bn256::{
+    Fq, Fq2, G2Affine, FROBENIUS_COEFF_FQ12_C1, G2, SIX_U_PLUS_2_NAF,
+};
+use halo2_base::utils::BigPrimeField;
+use halo2_base::Context;
+
+/// Constant coefficients of a sparse line function, as in `sparse_line_function_equal` and
+/// `sparse_line_function_unequal`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineCoeffs {
+    /// Tangent line at `R = (x, y)`:
+    /// `l(P) = c0 + w^3 * c3 * P.y + w^4 * c4 * P.x` with `c0 = (3x^3 - 2y^2)(XI_0 + u)`, `c3 = 2y`, `c4 = -3x^2`
+    Double { c0: Fq2, c3: Fq2, c4: Fq2 },
+    /// Line through `R = (x_1, y_1)` and `S = (x_2, y_2)`:
+    /// `l(P) = w^2 * c2 * P.y + w^3 * c3 * P.x + w^5 * c5` with `c2 = x_2 - x_1`, `c3 = y_1 - y_2`, `c5 = x_1 y_2 - x_2 y_1`
+    Add { c2: Fq2, c3: Fq2, c5: Fq2 },
+}
+
+/// Precomputed line functions of the BN254 optimal ate Miller loop for a fixed G2 point `Q`,
+/// in the order they are consumed by [`miller_loop_prepared`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedG2 {
+    pub lines: Vec<LineCoeffs>,
+}
+
+fn double_line(R: &G2Affine) -> LineCoeffs {
+    let xi = Fq2 { c0: Fq::from(XI_0 as u64), c1: Fq::one() };
+    let three = Fq2 { c0: Fq::from(3), c1: Fq::zero() };
+    let x_sq = R.x.square();
+    let c0 = (three * x_sq * R.x - R.y.square().double()) * xi;
+    let c3 = R.y.double();
+    let c4 = -(three * x_sq);
+    LineCoeffs::Double { c0, c3, c4 }
+}
+
+fn add_line(R: &G2Affine, S: &G2Affine) -> LineCoeffs {
+    let c2 = S.x - R.x;
+    let c3 = R.y - S.y;
+    let c5 = R.x * S.y - S.x * R.y;
+    LineCoeffs::Add { c2, c3, c5 }
+}
+
+fn conjugate(a: Fq2) -> Fq2 {
+    Fq2 { c0: a.c0, c1: -a.c1 }
+}
+
+impl PreparedG2 {
+    /// Computes the line functions of `miller_loop_BN` for `Q` out of circuit.
+    ///
+    /// # Assumptions
+    /// * `Q` is not the point at infinity and is in G2
+    pub fn new(Q: G2Affine) -> Self {
+        let naf = &SIX_U_PLUS_2_NAF;
+        let mut i = naf.len() - 1;
+        while naf[i] == 0 {
+            i -= 1;
+        }
+        let last_index = i;
+        assert_eq!(naf[last_index], 1);
+        let neg_Q = -Q;
+
+        let mut lines = vec![double_line(&Q)];
+        let mut R = Q;
+        i -= 1;
+        loop {
+            if i != last_index - 1 {
+                lines.push(double_line(&R));
+            }
+            R = G2::from(R).double().to_affine();
+
+            if naf[i] != 0 {
+                let sign_Q = if naf[i] == 1 { Q } else { neg_Q };
+                lines.push(add_line(&R, &sign_Q));
+                R = (G2::from(R) + sign_Q).to_affine();
+            }
+            if i == 0 {
+                break;
+            }
+            i -= 1;
+        }
+
+        // Frobenius coefficient coeff[1][j] = ((9+u)^{(p-1)/6})^j
+        let c2 = FROBENIUS_COEFF_FQ12_C1[1] * FROBENIUS_COEFF_FQ12_C1[1];
+        let c3 = c2 * FROBENIUS_COEFF_FQ12_C1[1];
+        // same as `twisted_frobenius` and `neg_twisted_frobenius`
+        let Q_1 = G2Affine { x: c2 * conjugate(Q.x), y: c3 * conjugate(Q.y) };
+        let neg_Q_2 = G2Affine { x: c2 * conjugate(Q_1.x), y: -(c3 * conjugate(Q_1.y)) };
+        lines.push(add_line(&R, &Q_1));
+        R = (G2::from(R) + Q_1).to_affine();
+        lines.push(add_line(&R, &neg_Q_2));
+
+        Self { lines }
+    }
+}
+
+impl From<G2Affine> for PreparedG2 {
+    fn from(Q: G2Affine) -> Self {
+        Self::new(Q)
+    }
+}
+
+// Returns `c * a` for constant `c` in Fp2 and `a` in Fp, without loading `c`
+fn fp2_constant_mul<F: BigPrimeField>(
+    fp2_chip: &Fp2Chip<F>,
+    ctx: &mut Context<F>,
+    c: Fq2,
+    a: &FpPoint<F>,
+) -> FqPoint<F> {
+    let fp_chip = fp2_chip.fp_chip();
+    let out = [c.c0, c.c1].map(|c| fp_chip.mul_constant_no_carry(ctx, a.clone(), c));
+    fp2_chip.carry_mod(ctx, FieldVector(out.to_vec()))
+}
+
+fn evaluate_line<F: BigPrimeField>(
+    fp2_chip: &Fp2Chip<F>,
+    ctx: &mut Context<F>,
+    line: &LineCoeffs,
+    P: &EcPoint<F, FpPoint<F>>,
+) -> Vec<Option<FqPoint<F>>> {
+    match *line {
+        LineCoeffs::Double { c0, c3, c4 } => {
+            let out0 = fp2_chip.load_constant(ctx, c0);
+            let out3 = fp2_constant_mul(fp2_chip, ctx, c3, &P.y);
+            let out4 = fp2_constant_mul(fp2_chip, ctx, c4, &P.x);
+            vec![Some(out0), None, None, Some(out3), Some(out4), None]
+        }
+        LineCoeffs::Add { c2, c3, c5 } => {
+            let out2 = fp2_constant_mul(fp2_chip, ctx, c2, &P.y);
+            let out3 = fp2_constant_mul(fp2_chip, ctx, c3, &P.x);
+            let out5 = fp2_chip.load_constant(ctx, c5);
+            vec![None, None, Some(out2), Some(out3), None, Some(out5)]
+        }
+    }
+}
+
+/// Same output as `multi_miller_loop_BN` with `pseudo_binary_encoding = SIX_U_PLUS_2_NAF`, where each
+/// G2 point is given by its precomputed [`PreparedG2`]. No G2 arithmetic is done in circuit.
+///
+/// # Assumptions
+/// * `pairs` is nonempty
+pub fn miller_loop_prepared<F: BigPrimeField>(
+    fp2_chip: &Fp2Chip<F>,
+    ctx: &mut Context<F>,
+    pairs: &[(&EcPoint<F, FpPoint<F>>, &PreparedG2)],
+) -> FqPoint<F> {
+    assert!(!pairs.is_empty());
+    let num_lines = pairs[0].1.lines.len();
+    assert!(pairs.iter().all(|(_, Q)| Q.lines.len() == num_lines));
+
+    let fp_chip = fp2_chip.fp_chip();
+    let fp12_chip = Fp12Chip::<F>::new(fp_chip);
+    // initialize the first line function into Fq12 point
+    let sparse_f = evaluate_line(fp2_chip, ctx, &pairs[0].1.lines[0], pairs[0].0);
+    let mut f = sparse_to_dense(fp_chip, ctx, sparse_f);
+    for (P, Q) in pairs.iter().skip(1) {
+        let line = evaluate_line(fp2_chip, ctx, &Q.lines[0], P);
+        f = sparse_fp12_multiply(fp2_chip, ctx, &f, &line);
+    }
+
+    for idx in 1..num_lines {
+        // every doubling step after the first squares the accumulator
+        if matches!(pairs[0].1.lines[idx], LineCoeffs::Double { .. }) {
+            f = fp12_chip.mul(ctx, &f, &f);
+        }
+        for (P, Q) in pairs {
+            let line = evaluate_line(fp2_chip, ctx, &Q.lines[idx], P);
+            f = sparse_fp12_multiply(fp2_chip, ctx, &f, &line);
+        }
+    }
+    f
+}
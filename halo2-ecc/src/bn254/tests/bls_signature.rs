@@ -3,8 +3,12 @@ use std::{
     io::{BufRead, BufReader},
 };
 
+use super::pairing::random_twist_point;
 use super::*;
-use crate::halo2curves::pairing::{group::ff::Field, MillerLoopResult};
+use crate::halo2curves::pairing::{
+    group::{ff::Field, Group},
+    MillerLoopResult,
+};
 use crate::{
     bn254::bls_signature::BlsSignatureChip, fields::FpStrategy,
     halo2_proofs::halo2curves::bn256::G2Affine,
@@ -97,6 +101,221 @@ fn test_bls_signature() {
     })
 }
 
+#[test]
+fn test_bls_signature_invalid() {
+    let path = "configs/bn254/bls_signature_circuit.config";
+    let params: BlsSignatureCircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+
+    let msg_hash = G2Affine::random(OsRng);
+    let g1 = G1Affine::generator();
+    let sk = Fr::random(OsRng);
+    // signed with a different secret key than the one in `pubkey`
+    let signature = G2Affine::from(msg_hash * (sk + Fr::one()));
+    let pubkey = G1Affine::from(G1Affine::generator() * sk);
+
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let pairing_chip = PairingChip::new(&fp_chip);
+        let bls_signature_chip = BlsSignatureChip::new(&fp_chip, &pairing_chip);
+        let result =
+            bls_signature_chip.bls_signature_verify(ctx, g1, &[signature], &[pubkey], msg_hash);
+        assert_eq!(result.value(), &Fr::zero());
+    })
+}
+
+fn bls_verify_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    params: BlsSignatureCircuitParams,
+    pubkey: G2Affine,
+    msg_hash: G1Affine,
+    sig: G1Affine,
+) -> bool {
+    let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+    let pairing_chip = PairingChip::new(&fp_chip);
+    let bls_signature_chip = BlsSignatureChip::new(&fp_chip, &pairing_chip);
+    let pubkey = pairing_chip.load_private_g2(ctx, pubkey);
+    let [msg_hash, sig] = [msg_hash, sig].map(|pt| pairing_chip.load_private_g1(ctx, pt));
+    let result = bls_signature_chip.verify(ctx, &pubkey, &msg_hash, &sig);
+    result.value() == &Fr::one()
+}
+
+#[test]
+fn test_bls_verify() {
+    let path = "configs/bn254/bls_signature_circuit.config";
+    let params: BlsSignatureCircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+
+    let msg_hash = G1Affine::random(OsRng);
+    let sk = Fr::random(OsRng);
+    let pubkey = G2Affine::from(G2Affine::generator() * sk);
+    let sig = G1Affine::from(msg_hash * sk);
+    // signed with a different secret key than the one in `pubkey`
+    let bad_sig = G1Affine::from(msg_hash * (sk + Fr::one()));
+    let tampered_sig = G1Affine::from(sig + G1Affine::generator());
+    let bad_pubkey = random_twist_point(OsRng);
+
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+        assert!(bls_verify_test(ctx, range, params, pubkey, msg_hash, sig));
+    });
+    for (pubkey, sig) in [(pubkey, bad_sig), (pubkey, tampered_sig), (bad_pubkey, sig)] {
+        base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+            assert!(!bls_verify_test(ctx, range, params, pubkey, msg_hash, sig));
+        });
+    }
+}
+
+fn bls_verify_unprepared_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    params: BlsSignatureCircuitParams,
+    pubkey: G2Affine,
+    msg_hash: G1Affine,
+    sig: G1Affine,
+) -> bool {
+    let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+    let pairing_chip = PairingChip::new(&fp_chip);
+    let bls_signature_chip = BlsSignatureChip::new(&fp_chip, &pairing_chip);
+    let pubkey = pairing_chip.load_private_g2(ctx, pubkey);
+    let [msg_hash, sig] = [msg_hash, sig].map(|pt| pairing_chip.load_private_g1(ctx, pt));
+    let result = bls_signature_chip.verify_unprepared(ctx, &pubkey, &msg_hash, &sig);
+    result.value() == &Fr::one()
+}
+
+#[test]
+fn test_bls_verify_unprepared() {
+    let path = "configs/bn254/bls_signature_circuit.config";
+    let params: BlsSignatureCircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+
+    let msg_hash = G1Affine::random(OsRng);
+    let sk = Fr::random(OsRng);
+    let pubkey = G2Affine::from(G2Affine::generator() * sk);
+    let sig = G1Affine::from(msg_hash * sk);
+    let bad_sig = G1Affine::from(msg_hash * (sk + Fr::one()));
+
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+        assert!(bls_verify_unprepared_test(ctx, range, params, pubkey, msg_hash, sig));
+    });
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+        assert!(!bls_verify_unprepared_test(ctx, range, params, pubkey, msg_hash, bad_sig));
+    });
+}
+
+fn bls_verify_aggregate_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    params: BlsSignatureCircuitParams,
+    pairs: &[(G2Affine, G1Affine)],
+    agg_sig: G1Affine,
+) -> bool {
+    let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+    let pairing_chip = PairingChip::new(&fp_chip);
+    let bls_signature_chip = BlsSignatureChip::new(&fp_chip, &pairing_chip);
+    let assigned = pairs
+        .iter()
+        .map(|&(pubkey, msg_hash)| {
+            (pairing_chip.load_private_g2(ctx, pubkey), pairing_chip.load_private_g1(ctx, msg_hash))
+        })
+        .collect::<Vec<_>>();
+    let agg_sig = pairing_chip.load_private_g1(ctx, agg_sig);
+    let pairs = assigned.iter().map(|(pubkey, msg_hash)| (pubkey, msg_hash)).collect::<Vec<_>>();
+    let result = bls_signature_chip.verify_aggregate(ctx, &pairs, &agg_sig);
+    result.value() == &Fr::one()
+}
+
+/// Returns `(pairs, agg_sig)` for three signers over three distinct messages, and a valid aggregate signature by the
+/// same signers, except that the first and the last signed the same message.
+fn three_signer_cases() -> [(Vec<(G2Affine, G1Affine)>, G1Affine); 2] {
+    let sks = [(); 3].map(|_| Fr::random(OsRng));
+    let sign = |msg_hashes: &[G1Affine]| {
+        let pairs = sks
+            .iter()
+            .zip(msg_hashes)
+            .map(|(sk, msg_hash)| (G2Affine::from(G2Affine::generator() * *sk), *msg_hash))
+            .collect::<Vec<_>>();
+        let agg_sig = sks
+            .iter()
+            .zip(msg_hashes)
+            .fold(G1::identity(), |acc, (sk, msg_hash)| acc + *msg_hash * *sk);
+        (pairs, G1Affine::from(agg_sig))
+    };
+    let msg_hashes = [(); 3].map(|_| G1Affine::random(OsRng));
+    [sign(&msg_hashes), sign(&[msg_hashes[0], msg_hashes[1], msg_hashes[0]])]
+}
+
+#[test]
+fn test_bls_verify_aggregate() {
+    let path = "configs/bn254/bls_signature_circuit.config";
+    let params: BlsSignatureCircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+
+    let [(pairs, agg_sig), (repeated_pairs, repeated_sig)] = three_signer_cases();
+    let tampered_sig = G1Affine::from(agg_sig + G1Affine::generator());
+
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+        assert!(bls_verify_aggregate_test(ctx, range, params, &pairs, agg_sig));
+    });
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+        assert!(!bls_verify_aggregate_test(ctx, range, params, &pairs, tampered_sig));
+    });
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+        assert!(!bls_verify_aggregate_test(ctx, range, params, &repeated_pairs, repeated_sig));
+    });
+}
+
+fn verify_aggregate_distinct_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    params: BlsSignatureCircuitParams,
+    pairs: &[(G2Affine, G1Affine)],
+    agg_sig: G1Affine,
+) -> bool {
+    let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+    let pairing_chip = PairingChip::new(&fp_chip);
+    let bls_signature_chip = BlsSignatureChip::new(&fp_chip, &pairing_chip);
+    let (pks, msgs): (Vec<_>, Vec<_>) = pairs
+        .iter()
+        .map(|&(pk, msg_hash)| {
+            (pairing_chip.load_private_g2(ctx, pk), pairing_chip.load_private_g1(ctx, msg_hash))
+        })
+        .unzip();
+    let agg_sig = pairing_chip.load_private_g1(ctx, agg_sig);
+    let result = bls_signature_chip.verify_aggregate_distinct(ctx, &pks, &msgs, &agg_sig);
+    result.value() == &Fr::one()
+}
+
+#[test]
+fn test_verify_aggregate_distinct() {
+    let path = "configs/bn254/bls_signature_circuit.config";
+    let params: BlsSignatureCircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+
+    let [(pairs, agg_sig), (repeated_pairs, repeated_sig)] = three_signer_cases();
+    let tampered_sig = G1Affine::from(agg_sig + G1Affine::generator());
+
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+        assert!(verify_aggregate_distinct_test(ctx, range, params, &pairs, agg_sig));
+    });
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+        assert!(!verify_aggregate_distinct_test(ctx, range, params, &pairs, tampered_sig));
+    });
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+        assert!(!verify_aggregate_distinct_test(ctx, range, params, &repeated_pairs, repeated_sig));
+    });
+}
+
 #[test]
 fn bench_bls_signature() -> Result<(), Box<dyn std::error::Error>> {
     let config_path = "configs/bn254/bench_bls_signature.config";
@@ -0,0 +1,148 @@
+//! Compares the witness values of in-circuit field arithmetic against `halo2curves` on inputs drawn by
+//! `proptest`. The test runner is seeded deterministically, so a failing case reproduces on every run.
+use super::final_exp::random_cyclotomic;
+use super::*;
+use crate::ff::Field;
+use crate::fields::FieldChip;
+use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq12, Fq2};
+use halo2_base::utils::modulus;
+use halo2_base::Context;
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestRng, TestRunner};
+
+fn rand_field<T: Field>() -> impl Strategy<Value = T> {
+    any::<u64>().prop_map(|seed| T::random(StdRng::seed_from_u64(seed)))
+}
+
+// Runs `test` on `cases` values of `strategy`. Inputs are derived from random seeds, which have no useful
+// notion of a smaller case, so shrinking is disabled.
+fn run_differential<S: Strategy>(cases: u32, strategy: S, test: impl Fn(S::Value)) {
+    let config =
+        Config { cases, max_shrink_iters: 0, failure_persistence: None, ..Config::default() };
+    let rng = TestRng::deterministic_rng(config.rng_algorithm);
+    let mut runner = TestRunner::new_with_rng(config, rng);
+    runner
+        .run(&strategy, |value| {
+            test(value);
+            Ok(())
+        })
+        .unwrap();
+}
+
+// checks add, sub, negate, mul, square and invert (as `1 / a`) against native arithmetic
+fn field_ops_test<FC: FieldChip<Fr>>(
+    ctx: &mut Context<Fr>,
+    chip: &FC,
+    a: FC::FieldType,
+    b: FC::FieldType,
+) {
+    let value = |x: FC::FieldPoint| chip.get_assigned_value(&x.into());
+    let [a_assigned, b_assigned] = [a, b].map(|x| chip.load_private(ctx, x));
+
+    let sum = chip.add_no_carry(ctx, &a_assigned, &b_assigned);
+    let sum = chip.carry_mod(ctx, sum);
+    assert_eq!(value(sum), a + b);
+
+    let diff = chip.sub_no_carry(ctx, &a_assigned, &b_assigned);
+    let diff = chip.carry_mod(ctx, diff);
+    assert_eq!(value(diff), a - b);
+
+    let neg = chip.negate(ctx, a_assigned.clone());
+    assert_eq!(value(neg), -a);
+
+    let prod = chip.mul(ctx, &a_assigned, &b_assigned);
+    assert_eq!(value(prod), a * b);
+
+    let sq = chip.mul(ctx, &a_assigned, &a_assigned);
+    assert_eq!(value(sq), a.square());
+
+    let one = chip.load_constant(ctx, <FC::FieldType as Field>::ONE);
+    let inv = chip.divide(ctx, one, &a_assigned);
+    assert_eq!(value(inv), a.invert().unwrap());
+}
+
+#[test]
+fn test_fp_differential() {
+    run_differential(16, (rand_field::<Fq>(), rand_field::<Fq>()), |(a, b)| {
+        base_test().k(12).lookup_bits(11).run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+            field_ops_test(ctx, &fp_chip, a, b);
+        });
+    });
+}
+
+#[test]
+fn test_fp2_differential() {
+    run_differential(16, (rand_field::<Fq2>(), rand_field::<Fq2>()), |(a, b)| {
+        base_test().k(12).lookup_bits(11).run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+            let fp2_chip = Fp2Chip::<Fr>::new(&fp_chip);
+            field_ops_test(ctx, &fp2_chip, a, b);
+
+            let a_assigned = fp2_chip.load_private(ctx, a);
+            for power in 0..4 {
+                let frob = fp2_chip.frobenius_map(ctx, a_assigned.clone(), power);
+                let mut expected = a;
+                expected.frobenius_map(power);
+                assert_eq!(fp2_chip.get_assigned_value(&frob.into()), expected);
+            }
+        });
+    });
+}
+
+#[test]
+fn test_fp12_differential() {
+    run_differential(8, (rand_field::<Fq12>(), rand_field::<Fq12>()), |(a, b)| {
+        base_test().k(14).lookup_bits(13).run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+            let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+            field_ops_test(ctx, &fp12_chip, a, b);
+
+            let a_assigned = fp12_chip.load_private(ctx, a);
+            let inv = fp12_chip.invert(ctx, a_assigned.clone());
+            assert_eq!(fp12_chip.get_assigned_value(&inv.into()), a.invert().unwrap());
+            for power in 0..12 {
+                let frob = fp12_chip.frobenius_map(ctx, &a_assigned, power);
+                let mut expected = a;
+                expected.frobenius_map(power);
+                assert_eq!(fp12_chip.get_assigned_value(&frob.into()), expected);
+            }
+        });
+    });
+}
+
+#[test]
+fn test_cyclotomic_square_differential() {
+    let strategy = any::<u64>().prop_map(|seed| random_cyclotomic(StdRng::seed_from_u64(seed)));
+    run_differential(8, strategy, |a| {
+        base_test().k(14).lookup_bits(13).run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+            let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+            let a_assigned = fp12_chip.load_private(ctx, a);
+
+            let compression = fp12_chip.cyclotomic_compress(&a_assigned);
+            let compression = fp12_chip.cyclotomic_square(ctx, &compression);
+            let a_sq = fp12_chip.cyclotomic_decompress(ctx, compression);
+            assert_eq!(fp12_chip.get_assigned_value(&a_sq.into()), a.square());
+
+            let a_sq = fp12_chip.cyclotomic_square_uncompressed(ctx, &a_assigned);
+            assert_eq!(fp12_chip.get_assigned_value(&a_sq.into()), a.square());
+        });
+    });
+}
+
+#[test]
+fn test_final_exp_differential() {
+    // (p^12 - 1) / r
+    let exp = (modulus::<Fq>().pow(12) - 1u64) / modulus::<Fr>();
+    let exp = exp.to_u64_digits();
+    run_differential(2, rand_field::<Fq12>(), |a| {
+        base_test().k(19).lookup_bits(18).run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, 90, 3);
+            let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+            let a_assigned = fp12_chip.load_private(ctx, a);
+            let f = fp12_chip.final_exp(ctx, a_assigned);
+            assert_eq!(fp12_chip.get_assigned_value(&f.into()), a.pow_vartime(&exp));
+        });
+    });
+}
@@ -5,7 +5,7 @@ use std::io::{BufRead, BufReader};
 use super::*;
 use crate::fields::{FieldChip, FpStrategy};
 use crate::group::cofactor::CofactorCurveAffine;
-use crate::halo2_proofs::halo2curves::bn256::G2Affine;
+use crate::halo2_proofs::halo2curves::bn256::{Fq2, G2Affine};
 use halo2_base::gates::RangeChip;
 use halo2_base::utils::testing::base_test;
 use halo2_base::utils::BigPrimeField;
@@ -109,3 +109,84 @@ fn bench_ec_add() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+// `None` is the point at infinity
+fn g2_add_complete_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    P: Option<G2Affine>,
+    Q: Option<G2Affine>,
+) -> Option<G2Affine> {
+    let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+    let fp2_chip = Fp2Chip::<Fr>::new(&fp_chip);
+    let g2_chip = EccChip::new(&fp2_chip);
+    let [P, Q] = [P, Q].map(|pt| {
+        let (x, y) = pt.map(|pt| (pt.x, pt.y)).unwrap_or((Fq2::zero(), Fq2::zero()));
+        g2_chip.load_private_unchecked(ctx, (x, y))
+    });
+    let (sum, is_inf) = g2_chip.add_complete(ctx, P, Q);
+    let [x, y] = [sum.x, sum.y].map(|c| fp2_chip.get_assigned_value(&c.into()));
+    if is_inf.value() == &Fr::one() {
+        assert_eq!((x, y), (Fq2::zero(), Fq2::zero()));
+        None
+    } else {
+        assert_eq!(is_inf.value(), &Fr::zero());
+        Some(G2Affine { x, y })
+    }
+}
+
+#[test]
+fn test_g2_add_complete() {
+    let P = G2Affine::random(OsRng);
+    let Q = G2Affine::random(OsRng);
+    let cases = [
+        (Some(P), Some(Q), Some((P + Q).to_affine())),
+        (Some(P), Some(P), Some((P + P).to_affine())),
+        (Some(P), Some(-P), None),
+        (Some(P), None, Some(P)),
+        (None, Some(Q), Some(Q)),
+        (None, None, None),
+    ];
+    base_test().k(16).lookup_bits(15).run(|ctx, range| {
+        for (P, Q, expected) in cases {
+            assert_eq!(g2_add_complete_test(ctx, range, P, Q), expected);
+        }
+    });
+}
+
+#[test]
+fn test_g2_add_complete_random() {
+    let mut rng = StdRng::seed_from_u64(0);
+    // alternate between additions of independent points and doublings
+    let cases = (0..100)
+        .map(|i| {
+            let P = G2Affine::random(&mut rng);
+            let Q = if i % 2 == 0 { G2Affine::random(&mut rng) } else { P };
+            (P, Q)
+        })
+        .collect_vec();
+    base_test().k(19).lookup_bits(18).run(|ctx, range| {
+        for (P, Q) in cases {
+            let expected = (P + Q).to_affine();
+            assert_eq!(g2_add_complete_test(ctx, range, Some(P), Some(Q)), Some(expected));
+        }
+    });
+}
+
+#[test]
+fn test_g2_negate_add_complete() {
+    let Q = G2Affine::random(OsRng);
+    base_test().k(16).lookup_bits(15).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp2_chip = Fp2Chip::<Fr>::new(&fp_chip);
+        let g2_chip = EccChip::new(&fp2_chip);
+        let infinity = g2_chip.load_private_unchecked(ctx, (Fq2::zero(), Fq2::zero()));
+        for Q in [g2_chip.load_private_unchecked(ctx, (Q.x, Q.y)), infinity] {
+            let neg_Q = g2_chip.negate(ctx, &Q);
+            let (_, is_inf) = g2_chip.add_complete(ctx, Q.clone(), neg_Q.clone());
+            assert_eq!(is_inf.value(), &Fr::one());
+            let neg_neg_Q = g2_chip.negate(ctx, neg_Q);
+            g2_chip.assert_equal(ctx, Q, neg_neg_Q);
+        }
+    });
+}
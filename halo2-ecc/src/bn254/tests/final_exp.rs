@@ -0,0 +1,767 @@
+use super::*;
+use crate::bn254::final_exp::HardPart;
+use crate::ff::{Field as _, PrimeField as _};
+use crate::fields::{fp12, FieldChip};
+use crate::halo2_proofs::halo2curves::bn256::{
+    multi_miller_loop, Fq, Fq12, Fq2, Fq6, G2Affine, G2Prepared, BN_X, FROBENIUS_COEFF_FQ12_C1,
+};
+use crate::halo2curves::pairing::MillerLoopResult;
+use halo2_base::gates::GateInstructions;
+use halo2_base::gates::RangeChip;
+use halo2_base::utils::{fe_to_biguint, modulus, BigPrimeField};
+use halo2_base::Context;
+use num_bigint::BigUint;
+use rand_core::RngCore;
+
+fn fp12_divide_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    a: Fq12,
+    b: Fq12,
+) {
+    let fp_chip = FpChip::<F>::new(range, 88, 3);
+    let fp12_chip = Fp12Chip::<F>::new(&fp_chip);
+    let [a_assigned, b_assigned] = [a, b].map(|x| fp12_chip.load_private(ctx, x));
+    let quot = fp12_chip.divide(ctx, a_assigned.clone(), b_assigned.clone());
+    let quot_b = fp12_chip.mul(ctx, quot, b_assigned);
+    fp12_chip.assert_equal(ctx, quot_b, a_assigned);
+}
+
+#[test]
+fn test_fp12_divide() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..4 {
+        let a = Fq12::random(&mut rng);
+        let b = Fq12::random(&mut rng);
+        base_test().k(14).lookup_bits(13).run(|ctx, range| {
+            fp12_divide_test(ctx, range, a, b);
+        });
+    }
+}
+
+#[test]
+fn test_fp12_divide_by_zero_fails() {
+    // `0 / 0` passes the `quot * b - a == 0` check for any `quot`, so only the nonzero constraint on `b` can reject it
+    base_test().k(14).lookup_bits(13).expect_satisfied(false).run(|ctx, range| {
+        fp12_divide_test(ctx, range, Fq12::zero(), Fq12::zero());
+    });
+}
+
+#[test]
+fn test_frobenius_map() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = Fq12::random(&mut rng);
+    let p = modulus::<Fq>().to_u64_digits();
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp12_chip.load_private(ctx, a);
+        // a^{p^power}
+        let mut a_pow = a;
+        for power in 0..12 {
+            let frob = fp12_chip.frobenius_map(ctx, &a_assigned, power);
+            assert_eq!(fp12_chip.get_assigned_value(&frob.into()), a_pow);
+            a_pow = a_pow.pow_vartime(&p);
+        }
+        assert_eq!(a_pow, a);
+    });
+}
+
+#[test]
+fn test_frobenius_map_odd_powers() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let a = Fq12::random(&mut rng);
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let fp2_chip = Fp2Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp12_chip.load_private(ctx, a);
+        for power in [1, 3, 5, 7, 9, 11] {
+            let (frob, fused_cells) =
+                count_advice_cells(ctx, |ctx| fp12_chip.frobenius_map(ctx, &a_assigned, power));
+            let mut expected = a;
+            expected.frobenius_map(power);
+            assert_eq!(fp12_chip.get_assigned_value(&frob.clone().into()), expected);
+
+            // conjugate every coefficient, then multiply by the Frobenius coefficient
+            let coeffs = crate::bn254::final_exp::frobenius_coeffs()[power];
+            let (unfused, unfused_cells) = count_advice_cells(ctx, |ctx| {
+                fp12::fp12_from_coeffs(std::array::from_fn(|i| {
+                    let a_fp2 = fp2_chip.conjugate(ctx, fp12::fp12_coeff(&a_assigned, i));
+                    let c = coeffs[i];
+                    if c == Fq2::one() {
+                        return a_fp2;
+                    }
+                    let out = if c.c1 == Fq::zero() {
+                        fp2_chip.fp_mul_constant_no_carry(ctx, a_fp2, c.c0)
+                    } else {
+                        fp2_chip.mul_constant_no_carry(ctx, a_fp2, c)
+                    };
+                    fp2_chip.carry_mod(ctx, out)
+                }))
+            });
+            assert!(fused_cells < unfused_cells, "{fused_cells} >= {unfused_cells}");
+            fp12_chip.assert_equal(ctx, frob, unfused);
+        }
+    });
+}
+
+#[test]
+fn test_fp6_frobenius_map() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = Fq6::random(&mut rng);
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp6_chip = Fp6Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp6_chip.load_private(ctx, a);
+        for power in 0..12 {
+            let frob = fp6_chip.frobenius_map(ctx, &a_assigned, power);
+            let mut expected = a;
+            expected.frobenius_map(power);
+            assert_eq!(fp6_chip.get_assigned_value(&frob.into()), expected);
+        }
+    });
+}
+
+#[test]
+fn test_frobenius_coeffs() {
+    let coeffs = crate::bn254::final_exp::frobenius_coeffs();
+    for (pow, row) in coeffs.iter().enumerate() {
+        for (i, coeff) in row.iter().enumerate() {
+            assert_eq!(*coeff, FROBENIUS_COEFF_FQ12_C1[pow].pow_vartime([i as u64]));
+        }
+    }
+    // repeated calls share the same table
+    assert!(std::ptr::eq(coeffs, crate::bn254::final_exp::frobenius_coeffs()));
+}
+
+#[test]
+fn test_frobenius_map_power_wraps() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let a = Fq12::random(&mut rng);
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp12_chip.load_private(ctx, a);
+        for power in 0..12 {
+            let frob = fp12_chip.frobenius_map(ctx, &a_assigned, power);
+            let frob_wrapped = fp12_chip.frobenius_map(ctx, &a_assigned, power + 12);
+            fp12_chip.assert_equal(ctx, frob, frob_wrapped);
+        }
+    });
+}
+
+// returns a^{(p^6 - 1)(p^2 + 1)}, which lies in the cyclotomic subgroup
+pub(super) fn random_cyclotomic(mut rng: impl RngCore) -> Fq12 {
+    let p = modulus::<Fq>();
+    let exp = (p.pow(6) - 1u64) * (p.pow(2) + 1u64);
+    Fq12::random(&mut rng).pow_vartime(exp.to_u64_digits())
+}
+
+fn cyclotomic_subgroup_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    a: Fq12,
+    apply_easy_part: bool,
+) {
+    let fp_chip = FpChip::<F>::new(range, 88, 3);
+    let fp12_chip = Fp12Chip::<F>::new(&fp_chip);
+    let mut a = fp12_chip.load_private(ctx, a);
+    if apply_easy_part {
+        a = fp12_chip.easy_part(ctx, a);
+    }
+    fp12_chip.assert_in_cyclotomic_subgroup(ctx, &a);
+}
+
+#[test]
+fn test_assert_in_cyclotomic_subgroup() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = Fq12::random(&mut rng);
+    base_test().k(15).lookup_bits(14).run(|ctx, range| {
+        cyclotomic_subgroup_test(ctx, range, a, true);
+    });
+    base_test().k(15).lookup_bits(14).expect_satisfied(false).run(|ctx, range| {
+        cyclotomic_subgroup_test(ctx, range, a, false);
+    });
+}
+
+#[test]
+fn test_assert_in_gt_subgroup() {
+    let (params, mut rng) = pairing_test_setup();
+    let (P, Q) = (G1Affine::random(&mut rng), G2Affine::random(&mut rng));
+    params.base_test().run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let pairing_chip = PairingChip::new(&fp_chip);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let P = pairing_chip.load_private_g1(ctx, P);
+        let Q = pairing_chip.load_private_g2(ctx, Q);
+        let f = pairing_chip.miller_loop(ctx, &Q, &P);
+        let f = fp12_chip.final_exp(ctx, f);
+        fp12_chip.assert_in_gt_subgroup(ctx, &f);
+    });
+}
+
+#[test]
+fn test_assert_in_gt_subgroup_fails() {
+    // the cyclotomic subgroup has order `p^4 - p^2 + 1`, a large multiple of `r`, so a random element is not in `GT`
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = random_cyclotomic(&mut rng);
+    base_test().k(16).lookup_bits(15).expect_satisfied(false).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a = fp12_chip.load_private(ctx, a);
+        fp12_chip.assert_in_gt_subgroup(ctx, &a);
+    });
+}
+
+#[test]
+fn test_easy_part_lazy_reduction() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let a = Fq12::random(&mut rng);
+    let p = modulus::<Fq>();
+    let exp = (p.pow(6) - 1u64) * (p.pow(2) + 1u64);
+    base_test().k(16).lookup_bits(15).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a = fp12_chip.load_private(ctx, a);
+
+        let (easy, lazy_cells) = count_advice_cells(ctx, |ctx| fp12_chip.easy_part(ctx, a.clone()));
+        // conjugate, divide, frobenius_map and mul, each fully reduced
+        let (reduced, reduced_cells) = count_advice_cells(ctx, |ctx| {
+            let f1 = fp12_chip.conjugate(ctx, a.clone());
+            let f2 = fp12_chip.divide(ctx, &f1, &a);
+            let f3 = fp12_chip.frobenius_map(ctx, &f2, 2);
+            fp12_chip.mul(ctx, &f3, &f2)
+        });
+        assert!(lazy_cells < reduced_cells, "{lazy_cells} >= {reduced_cells}");
+
+        let a_val = fp12_chip.get_assigned_value(&a.into());
+        assert_eq!(
+            fp12_chip.get_assigned_value(&easy.clone().into()),
+            a_val.pow_vartime(exp.to_u64_digits())
+        );
+        fp12_chip.assert_equal(ctx, easy, reduced);
+    });
+}
+
+#[test]
+fn test_easy_part_zero_fails() {
+    base_test().k(16).lookup_bits(15).expect_satisfied(false).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let zero = fp12_chip.load_private(ctx, Fq12::zero());
+        fp12_chip.easy_part(ctx, zero);
+    });
+}
+
+#[test]
+fn test_final_exp_batch() {
+    let (params, mut rng) = pairing_test_setup();
+    let pairs = (0..2)
+        .map(|_| (G1Affine::random(&mut rng), G2Affine::random(&mut rng)))
+        .collect::<Vec<_>>();
+    params.base_test().run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let pairing_chip = PairingChip::new(&fp_chip);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let miller_loops = pairs
+            .iter()
+            .map(|&(P, Q)| {
+                let P_assigned = pairing_chip.load_private_g1(ctx, P);
+                let Q_assigned = pairing_chip.load_private_g2(ctx, Q);
+                pairing_chip.miller_loop(ctx, &Q_assigned, &P_assigned)
+            })
+            .collect::<Vec<_>>();
+        let outputs = fp12_chip.final_exp_batch(ctx, &miller_loops);
+        assert_eq!(outputs.len(), pairs.len());
+        for (f, (P, Q)) in outputs.into_iter().zip(&pairs) {
+            let expected = multi_miller_loop(&[(P, &G2Prepared::from(*Q))]).final_exponentiation();
+            // cannot directly compare f and expected because `Gt` has private field `Fq12`
+            assert_eq!(
+                format!("Gt({:?})", fp12_chip.get_assigned_value(&f.into())),
+                format!("{expected:?}")
+            );
+        }
+    });
+}
+
+#[test]
+fn test_final_exp_easy_hard_part() {
+    let (params, mut rng) = pairing_test_setup();
+    let a = Fq12::random(&mut rng);
+    params.base_test().run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a = fp12_chip.load_private(ctx, a);
+        let f = fp12_chip.final_exp(ctx, a.clone());
+        let easy = fp12_chip.easy_part(ctx, a);
+        let hard = fp12_chip.hard_part_BN(ctx, easy);
+        for (f, hard) in f.into_iter().zip(hard) {
+            assert_eq!(
+                f.limbs().iter().map(|x| *x.value()).collect::<Vec<_>>(),
+                hard.limbs().iter().map(|x| *x.value()).collect::<Vec<_>>()
+            );
+        }
+    });
+}
+
+#[test]
+fn test_hard_part_fuentes() {
+    let (params, mut rng) = pairing_test_setup();
+    let a = Fq12::random(&mut rng);
+    // the Fuentes-Castañeda hard part is the `2x(6x^2 + 3x + 1)`-th power of the default one
+    let x = BigUint::from(BN_X);
+    let exp = &x * 2u64 * (&x * &x * 6u64 + &x * 3u64 + 1u64);
+    params.base_test().run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a = fp12_chip.load_private(ctx, a);
+        let f = fp12_chip.final_exp_with(ctx, a.clone(), HardPart::Scott);
+        let f_fuentes = fp12_chip.final_exp_with(ctx, a, HardPart::FuentesCastaneda);
+        let f = fp12_chip.get_assigned_value(&f.into());
+        let f_fuentes = fp12_chip.get_assigned_value(&f_fuentes.into());
+        assert_eq!(f_fuentes, f.pow_vartime(exp.to_u64_digits()));
+    });
+}
+
+fn pairing_check_fuentes_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    params: PairingCircuitParams,
+    pairs: &[(G1Affine, G2Affine)],
+) -> bool {
+    let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+    let pairing_chip = PairingChip::new(&fp_chip);
+    let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+    let assigned = pairs
+        .iter()
+        .map(|&(P, Q)| (pairing_chip.load_private_g1(ctx, P), pairing_chip.load_private_g2(ctx, Q)))
+        .collect::<Vec<_>>();
+    let mml = pairing_chip.multi_miller_loop(ctx, assigned.iter().map(|(P, Q)| (P, Q)).collect());
+    let f = fp12_chip.final_exp_with(ctx, mml, HardPart::FuentesCastaneda);
+    fp12_chip.get_assigned_value(&f.into()) == Fq12::one()
+}
+
+#[test]
+fn test_pairing_check_fuentes() {
+    let (params, mut rng) = pairing_test_setup();
+    let P = G1Affine::random(&mut rng);
+    let Q = G2Affine::random(&mut rng);
+    let a = Fr::random(&mut rng);
+    let aP = G1Affine::from(P * a);
+    let aQ = G2Affine::from(Q * a);
+    // e(aP, Q) * e(-P, aQ) = 1
+    params.base_test().run(|ctx, range| {
+        assert!(pairing_check_fuentes_test(ctx, range, params, &[(aP, Q), (-P, aQ)]));
+    });
+    // e(P, Q) * e(-P, aQ) != 1
+    params.base_test().run(|ctx, range| {
+        assert!(!pairing_check_fuentes_test(ctx, range, params, &[(P, Q), (-P, aQ)]));
+    });
+}
+
+#[test]
+fn test_cyclotomic_pow() {
+    let (params, mut rng) = pairing_test_setup();
+    let a = random_cyclotomic(&mut rng);
+    // the NAF of `BN_X` has both `1` and `-1` digits; also include even exponents and a single bit
+    let exps = [BN_X, rng.next_u64() | 1, rng.next_u64() & !1, 1 << 20];
+    params.base_test().run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp12_chip.load_private(ctx, a);
+        for exp in exps {
+            let out = fp12_chip.cyclotomic_pow(ctx, a_assigned.clone(), vec![exp]);
+            assert_eq!(fp12_chip.get_assigned_value(&out.into()), a.pow_vartime([exp]));
+        }
+    });
+}
+
+#[test]
+fn test_cyclotomic_inverse() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = random_cyclotomic(&mut rng);
+    let b = Fq12::random(&mut rng);
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp12_chip.load_private(ctx, a);
+        let a_inv = fp12_chip.cyclotomic_inverse(ctx, a_assigned.clone());
+        let prod = fp12_chip.mul(ctx, &a_assigned, &a_inv);
+        let one = fp12_chip.load_constant(ctx, Fq12::one());
+        fp12_chip.assert_equal(ctx, prod, one);
+
+        // outside the cyclotomic subgroup the conjugate is not the inverse
+        let b_assigned = fp12_chip.load_private(ctx, b);
+        let b_inv = fp12_chip.cyclotomic_inverse(ctx, b_assigned);
+        assert_ne!(fp12_chip.get_assigned_value(&b_inv.into()), b.invert().unwrap());
+    });
+}
+
+#[test]
+fn test_pow_signed() {
+    let mut rng = StdRng::seed_from_u64(0);
+    // a general element and one in the cyclotomic subgroup, where the inverse is the conjugate
+    let elts = [Fq12::random(&mut rng), random_cyclotomic(&mut rng)];
+    base_test().k(18).lookup_bits(17).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        for a in elts {
+            let a_assigned = fp12_chip.load_private(ctx, a);
+            let a_x = a.pow_vartime([BN_X]);
+            let out = fp12_chip.pow_signed(ctx, &a_assigned, vec![BN_X], false);
+            assert_eq!(fp12_chip.get_assigned_value(&out.into()), a_x);
+            let out = fp12_chip.pow_signed(ctx, &a_assigned, vec![BN_X], true);
+            assert_eq!(fp12_chip.get_assigned_value(&out.into()), a_x.invert().unwrap());
+        }
+        let a = elts[1];
+        let a_assigned = fp12_chip.load_private(ctx, a);
+        let out = fp12_chip.cyclotomic_pow(ctx, a_assigned, vec![BN_X]);
+        let out = fp12_chip.conjugate(ctx, out);
+        assert_eq!(
+            fp12_chip.get_assigned_value(&out.into()),
+            a.pow_vartime([BN_X]).invert().unwrap()
+        );
+    });
+}
+
+#[test]
+fn test_cyclotomic_pow_small_exp() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = random_cyclotomic(&mut rng);
+    base_test().k(15).lookup_bits(14).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp12_chip.load_private(ctx, a);
+        // includes `exp == 0`, `exp == 1`, and exponents whose high limbs are zero
+        for exp in [vec![0], vec![1], vec![2], vec![3], vec![5], vec![7, 0], vec![0, 0]] {
+            let out = fp12_chip.cyclotomic_pow(ctx, a_assigned.clone(), exp.clone());
+            assert_eq!(fp12_chip.get_assigned_value(&out.into()), a.pow_vartime(&exp));
+        }
+    });
+}
+
+#[test]
+fn test_cyclotomic_pow_wnaf() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = random_cyclotomic(&mut rng);
+    let r = modulus::<Fr>().to_u64_digits();
+    let exps = [
+        vec![0],
+        vec![1],
+        vec![2],
+        vec![7, 0],
+        vec![BN_X],
+        vec![rng.next_u64(), rng.next_u64()],
+        r,
+    ];
+    base_test().k(19).lookup_bits(18).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp12_chip.load_private(ctx, a);
+        for (i, exp) in exps.into_iter().enumerate() {
+            let w = 2 + i % 4;
+            let out = fp12_chip.cyclotomic_pow_wnaf(ctx, a_assigned.clone(), exp.clone(), w);
+            assert_eq!(fp12_chip.get_assigned_value(&out.into()), a.pow_vartime(&exp));
+        }
+    });
+}
+
+#[test]
+fn test_cyclotomic_pow_wnaf_cells() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = random_cyclotomic(&mut rng);
+    let r = modulus::<Fr>().to_u64_digits();
+    base_test().k(20).lookup_bits(19).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a = fp12_chip.load_private(ctx, a);
+        let (naf_out, naf_cells) =
+            count_advice_cells(ctx, |ctx| fp12_chip.cyclotomic_pow(ctx, a.clone(), r.clone()));
+        let (wnaf_out, wnaf_cells) =
+            count_advice_cells(ctx, |ctx| fp12_chip.cyclotomic_pow_wnaf(ctx, a, r, 4));
+        assert!(wnaf_cells < naf_cells, "{wnaf_cells} >= {naf_cells}");
+        fp12_chip.assert_equal(ctx, naf_out, wnaf_out);
+    });
+}
+
+#[test]
+fn test_cyclotomic_pow_var() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = random_cyclotomic(&mut rng);
+    let e = Fr::random(&mut rng);
+    // 254 bits is not a multiple of 3, so the top window is shorter
+    let exps = [(e, 3), (e, 4), (Fr::zero(), 4), (Fr::one(), 4), (-Fr::one(), 4)];
+    base_test().k(18).lookup_bits(17).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp12_chip.load_private(ctx, a);
+        for (exp, window_bits) in exps {
+            let exp_assigned = ctx.load_witness(exp);
+            let bits = fp12_chip.gate().num_to_bits(ctx, exp_assigned, Fr::NUM_BITS as usize);
+            let out = fp12_chip.cyclotomic_pow_var(ctx, &a_assigned, &bits, window_bits);
+            let expected = a.pow_vartime(fe_to_biguint(&exp).to_u64_digits());
+            assert_eq!(fp12_chip.get_assigned_value(&out.into()), expected);
+        }
+    });
+}
+
+#[test]
+fn test_xi_0_matches_halo2curves() {
+    let mut xi = Fq2::ONE;
+    xi.mul_by_nonresidue();
+    assert_eq!(xi, Fq2 { c0: Fq::from(XI_0 as u64), c1: Fq::ONE });
+}
+
+#[test]
+fn test_cyclotomic_square_matches_mul() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = random_cyclotomic(&mut rng);
+    base_test().k(15).lookup_bits(14).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp12_chip.load_private(ctx, a);
+        let compression = fp12_chip.cyclotomic_compress(&a_assigned);
+        let compression = fp12_chip.cyclotomic_square(ctx, &compression);
+        let a_sq = fp12_chip.cyclotomic_decompress(ctx, compression);
+        let a_mul = fp12_chip.mul(ctx, &a_assigned, &a_assigned);
+        fp12_chip.assert_equal(ctx, a_sq, a_mul);
+    });
+}
+
+#[test]
+fn test_cyclotomic_square_max_overflow() {
+    // every coefficient `p - 1` drives the no-carry intermediates of `cyclotomic_square` to the bound in its overflow
+    // comment; `carry_mod` debug-asserts that its input is within range
+    let g = Fq2 { c0: -Fq::ONE, c1: -Fq::ONE };
+    let c = |mut x: Fq2| {
+        x.mul_by_nonresidue();
+        x
+    };
+    let [two, three] = [2, 3].map(|n| Fq2 { c0: Fq::from(n), c1: Fq::ZERO });
+    let a = (g + g) * (g + c(g));
+    let b = g * g;
+    let h2 = two * (g + three * c(b));
+    let h3 = three * (a - c(b) - b) - two * g;
+    let h5 = two * (g + three * b);
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let fp2_chip = Fp2Chip::<Fr>::new(&fp_chip);
+        let compression = (0..4).map(|_| fp2_chip.load_private(ctx, g)).collect::<Vec<_>>();
+        let out = fp12_chip.cyclotomic_square(ctx, &compression);
+        let out =
+            out.into_iter().map(|h| fp2_chip.get_assigned_value(&h.into())).collect::<Vec<_>>();
+        assert_eq!(out, vec![h2, h3, h3, h5]);
+    });
+}
+
+/// Differential test of the compressed squaring against `Fp12` multiplication on `NUM_CASES` seeded random
+/// elements of the cyclotomic subgroup: `decompress(square(compress(g))) == decompress(compress(g))^2`.
+#[test]
+fn test_cyclotomic_square_differential() {
+    const NUM_CASES: usize = 16;
+    let seed = 0x5eed;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let inputs = (0..NUM_CASES).map(|_| random_cyclotomic(&mut rng)).collect::<Vec<_>>();
+    base_test().k(18).lookup_bits(17).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        for (i, g) in inputs.into_iter().enumerate() {
+            let g_assigned = fp12_chip.load_private(ctx, g);
+            let compression = fp12_chip.cyclotomic_compress(&g_assigned);
+            let g_round_trip = fp12_chip.cyclotomic_decompress(ctx, compression.clone());
+            let g_mul = fp12_chip.mul(ctx, &g_round_trip, &g_round_trip);
+            let compression = fp12_chip.cyclotomic_square(ctx, &compression);
+            let g_sq = fp12_chip.cyclotomic_decompress(ctx, compression);
+            let [g_sq_val, g_mul_val] =
+                [g_sq.clone(), g_mul.clone()].map(|x| fp12_chip.get_assigned_value(&x.into()));
+            assert_eq!(g_sq_val, g_mul_val, "case {i} with seed {seed:#x}: g = {g:?}");
+            assert_eq!(g_sq_val, g.square(), "case {i} with seed {seed:#x}: g = {g:?}");
+            fp12_chip.assert_equal(ctx, g_sq, g_mul);
+        }
+    });
+}
+
+#[test]
+fn test_cyclotomic_square_uncompressed() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let inputs = [random_cyclotomic(&mut rng), random_cyclotomic(&mut rng), Fq12::one()];
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        for a in inputs {
+            let a_assigned = fp12_chip.load_private(ctx, a);
+            let a_sq = fp12_chip.cyclotomic_square_uncompressed(ctx, &a_assigned);
+            assert_eq!(fp12_chip.get_assigned_value(&a_sq.into()), a.square());
+        }
+    });
+}
+
+#[test]
+fn test_cyclotomic_square_uncompressed_cells() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = random_cyclotomic(&mut rng);
+    base_test().k(15).lookup_bits(14).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a_assigned = fp12_chip.load_private(ctx, a);
+
+        let (a_sq, uncompressed_cells) = count_advice_cells(ctx, |ctx| {
+            fp12_chip.cyclotomic_square_uncompressed(ctx, &a_assigned)
+        });
+        let (a_mul, mul_cells) =
+            count_advice_cells(ctx, |ctx| fp12_chip.mul(ctx, &a_assigned, &a_assigned));
+        // a single squaring through the compressed form also pays for the decompression
+        let (a_compressed, compressed_cells) = count_advice_cells(ctx, |ctx| {
+            let compression = fp12_chip.cyclotomic_compress(&a_assigned);
+            let compression = fp12_chip.cyclotomic_square(ctx, &compression);
+            fp12_chip.cyclotomic_decompress(ctx, compression)
+        });
+
+        assert!(uncompressed_cells < mul_cells, "{uncompressed_cells} >= {mul_cells}");
+        assert!(
+            uncompressed_cells < compressed_cells,
+            "{uncompressed_cells} >= {compressed_cells}"
+        );
+        fp12_chip.assert_equal(ctx, a_sq.clone(), a_mul);
+        fp12_chip.assert_equal(ctx, a_sq, a_compressed);
+    });
+}
+
+fn cyclotomic_decompress_checked_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    a: Fq12,
+    tamper: bool,
+) {
+    let fp_chip = FpChip::<F>::new(range, 88, 3);
+    let fp12_chip = Fp12Chip::<F>::new(&fp_chip);
+    let fp2_chip = Fp2Chip::<F>::new(&fp_chip);
+    let a_assigned = fp12_chip.load_private(ctx, a);
+    let mut compression = fp12_chip.cyclotomic_compress(&a_assigned);
+    if tamper {
+        // replace g2 by g2 + 1
+        let g2 = fp2_chip.get_assigned_value(&compression[0].clone().into());
+        compression[0] = fp2_chip.load_private(ctx, g2 + Fq2::ONE);
+    }
+    let out = fp12_chip.cyclotomic_decompress_checked(ctx, compression);
+    if !tamper {
+        assert_eq!(fp12_chip.get_assigned_value(&out.into()), a);
+    }
+}
+
+#[test]
+fn test_cyclotomic_decompress_checked() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = random_cyclotomic(&mut rng);
+    base_test().k(15).lookup_bits(14).run(|ctx, range| {
+        cyclotomic_decompress_checked_test(ctx, range, a, false);
+    });
+    base_test().k(15).lookup_bits(14).expect_satisfied(false).run(|ctx, range| {
+        cyclotomic_decompress_checked_test(ctx, range, a, true);
+    });
+}
+
+#[test]
+fn test_final_exp_easy_part_cyclotomic_square() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = Fq12::random(&mut rng);
+    base_test().k(15).lookup_bits(14).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let pairing_chip = PairingChip::new(&fp_chip);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a = fp12_chip.load_private(ctx, a);
+        let m = pairing_chip.final_exp_easy_part(ctx, a);
+        let m_sq = fp12_chip.mul(ctx, &m, &m);
+        let m_sq = fp12_chip.get_assigned_value(&m_sq.into());
+
+        let compression = fp12_chip.cyclotomic_compress(&m);
+        let compression = fp12_chip.cyclotomic_square(ctx, &compression);
+        let m_sq_compressed = fp12_chip.cyclotomic_decompress(ctx, compression);
+        assert_eq!(fp12_chip.get_assigned_value(&m_sq_compressed.into()), m_sq);
+
+        let m_sq_uncompressed = fp12_chip.cyclotomic_square_uncompressed(ctx, &m);
+        assert_eq!(fp12_chip.get_assigned_value(&m_sq_uncompressed.into()), m_sq);
+    });
+}
+
+#[test]
+fn test_torus_ops() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let a = random_cyclotomic(&mut rng);
+    let b = random_cyclotomic(&mut rng);
+    let p = modulus::<Fq>().to_u64_digits();
+    base_test().k(16).lookup_bits(15).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let [a_assigned, b_assigned] = [a, b].map(|x| fp12_chip.load_private(ctx, x));
+        let a_t = fp12_chip.torus_compress(ctx, &a_assigned);
+        let b_t = fp12_chip.torus_compress(ctx, &b_assigned);
+
+        let ab_t = fp12_chip.torus_mul(ctx, &a_t, &b_t);
+        let a_sq_t = fp12_chip.torus_square(ctx, &a_t);
+        let a_inv_t = fp12_chip.torus_conjugate(ctx, &a_t);
+        let mut cases = vec![
+            (a_t.clone(), a),
+            (ab_t, a * b),
+            (a_sq_t, a.square()),
+            (a_inv_t, a.invert().unwrap()),
+        ];
+        let mut a_pow = a;
+        for power in 1..4 {
+            a_pow = a_pow.pow_vartime(&p);
+            cases.push((fp12_chip.torus_frobenius_map(ctx, &a_t, power), a_pow));
+        }
+        for (c, expected) in cases {
+            let out = fp12_chip.torus_decompress(ctx, &c);
+            assert_eq!(fp12_chip.get_assigned_value(&out.into()), expected);
+        }
+    });
+}
+
+#[test]
+fn test_final_exp_torus() {
+    let (params, mut rng) = pairing_test_setup();
+    let a = Fq12::random(&mut rng);
+    params.base_test().run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+        let a = fp12_chip.load_private(ctx, a);
+        let f = fp12_chip.final_exp(ctx, a.clone());
+        let f_torus = fp12_chip.final_exp_torus(ctx, a);
+        assert_eq!(
+            fp12_chip.get_assigned_value(&f.into()),
+            fp12_chip.get_assigned_value(&f_torus.into())
+        );
+    });
+}
+
+#[test]
+fn test_final_exp_torus_identity() {
+    // any `a` in `Fp6` has `easy_part(a) = 1`, which has no torus representation
+    let (params, mut rng) = pairing_test_setup();
+    let a_fp6 = Fq12 { c0: Fq6::random(&mut rng), c1: Fq6::zero() };
+    for a in [Fq12::one(), a_fp6] {
+        params.base_test().run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+            let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+            let a = fp12_chip.load_private(ctx, a);
+            let f_torus = fp12_chip.final_exp_torus(ctx, a);
+            assert_eq!(fp12_chip.get_assigned_value(&f_torus.into()), Fq12::one());
+        });
+    }
+}
+
+#[test]
+fn test_torus_compress_identity_fails() {
+    // `-1` would give `c = 0 / 0`, so `a1 = 0` must be rejected rather than leave `c` unconstrained
+    for a in [Fq12::one(), -Fq12::one()] {
+        base_test().k(16).lookup_bits(15).expect_satisfied(false).run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+            let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+            let a = fp12_chip.load_private(ctx, a);
+            fp12_chip.torus_compress(ctx, &a);
+        });
+    }
+}
@@ -6,7 +6,10 @@ use std::{
 use crate::ff::{Field, PrimeField};
 
 use super::*;
+use crate::ecc::FixedBaseTable;
+use halo2_base::utils::biguint_to_fe;
 use itertools::Itertools;
+use num_bigint::BigUint;
 
 pub fn fixed_base_msm_test(
     pool: &mut SinglePhaseCoreManager<Fr>,
@@ -113,3 +116,61 @@ fn bench_fixed_base_msm() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+#[test]
+fn test_fixed_base_table_scalar_mult() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let base = G1Affine::random(&mut rng);
+    // small scalars have mostly zero windows
+    let scalars = [Fr::random(&mut rng), Fr::random(&mut rng), Fr::from(5)];
+    let table = FixedBaseTable::new(base, Fr::NUM_BITS as usize, 4);
+    base_test().k(18).lookup_bits(17).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let ecc_chip = EccChip::new(&fp_chip);
+        let base_assigned = ecc_chip.assign_point(ctx, base);
+        for scalar in scalars {
+            let scalar = ctx.load_witness(scalar);
+            let fixed = ecc_chip.fixed_base_scalar_mult_with_table(
+                ctx,
+                &table,
+                vec![scalar],
+                Fr::NUM_BITS as usize,
+            );
+            let variable = ecc_chip.scalar_mult::<G1Affine>(
+                ctx,
+                base_assigned.clone(),
+                vec![scalar],
+                Fr::NUM_BITS as usize,
+                4,
+            );
+            assert_eq!(fixed.x.value(), variable.x.value());
+            assert_eq!(fixed.y.value(), variable.y.value());
+            ecc_chip.assert_equal(ctx, fixed, variable);
+        }
+    });
+}
+
+#[test]
+fn test_fixed_base_scalar_mult_multi_cell() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let base = G1Affine::random(&mut rng);
+    let scalar = Fr::random(&mut rng);
+    // split into two 128-bit cells; 256 bits is not a multiple of the window size 3
+    let scalar_big = fe_to_biguint(&scalar);
+    let lo = biguint_to_fe::<Fr>(&(&scalar_big % (BigUint::from(1u64) << 128)));
+    let hi = biguint_to_fe::<Fr>(&(&scalar_big >> 128));
+    base_test().k(18).lookup_bits(17).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let ecc_chip = EccChip::new(&fp_chip);
+        let scalar_assigned = [lo, hi].map(|x| ctx.load_witness(x)).to_vec();
+        let fixed = ecc_chip.fixed_base_scalar_mult(ctx, &base, scalar_assigned.clone(), 128, 3);
+        let base_assigned = ecc_chip.assign_point(ctx, base);
+        let variable =
+            ecc_chip.scalar_mult::<G1Affine>(ctx, base_assigned, scalar_assigned, 128, 3);
+
+        let expected = (base * scalar).to_affine();
+        assert_eq!(fixed.x.value(), fe_to_biguint(&expected.x));
+        assert_eq!(fixed.y.value(), fe_to_biguint(&expected.y));
+        ecc_chip.assert_equal(ctx, fixed, variable);
+    });
+}
@@ -0,0 +1,59 @@
+use super::*;
+use crate::bn254::glv::{glv_decompose, glv_scalar_mult, GLV_SCALAR_BITS, LAMBDA};
+use crate::ff::{Field as _, PrimeField as _};
+use crate::fields::FieldChip;
+use halo2_base::utils::{biguint_to_fe, fe_to_biguint};
+use num_bigint::BigUint;
+
+fn signed_fe(is_neg: bool, abs: &BigUint) -> Fr {
+    let abs: Fr = biguint_to_fe(abs);
+    if is_neg {
+        -abs
+    } else {
+        abs
+    }
+}
+
+#[test]
+fn test_glv_decompose() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let lambda = Fr::from_str_vartime(LAMBDA).unwrap();
+    let scalars = (0..100).map(|_| Fr::random(&mut rng)).chain([Fr::ZERO, Fr::ONE, -Fr::ONE]);
+    for k in scalars {
+        let [(k1_neg, k1), (k2_neg, k2)] = glv_decompose(&fe_to_biguint(&k));
+        assert!(k1.bits() <= GLV_SCALAR_BITS as u64 && k2.bits() <= GLV_SCALAR_BITS as u64);
+        assert_eq!(signed_fe(k1_neg, &k1) + signed_fe(k2_neg, &k2) * lambda, k);
+    }
+}
+
+#[test]
+fn test_glv_endomorphism() {
+    use crate::bn254::glv::BETA;
+    use crate::halo2_proofs::halo2curves::bn256::Fq;
+    let P = G1Affine::random(StdRng::seed_from_u64(0));
+    let beta = Fq::from_str_vartime(BETA).unwrap();
+    let lambda = Fr::from_str_vartime(LAMBDA).unwrap();
+    let phi_P = (P * lambda).to_affine();
+    assert_eq!((phi_P.x, phi_P.y), (beta * P.x, P.y));
+}
+
+#[test]
+fn test_glv_scalar_mult() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let P = G1Affine::random(&mut rng);
+    // random scalars exercise all four sign combinations of `(k1, k2)`
+    let scalars =
+        (0..4).map(|_| Fr::random(&mut rng)).chain([Fr::ONE, -Fr::ONE]).collect::<Vec<_>>();
+    base_test().k(18).lookup_bits(17).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let chip = EccChip::new(&fp_chip);
+        let P_assigned = chip.load_private::<G1Affine>(ctx, (P.x, P.y));
+        for &k in &scalars {
+            let k_assigned = ctx.load_witness(k);
+            let kP = glv_scalar_mult(&chip, ctx, P_assigned.clone(), k_assigned, 4);
+            let expected = (P * k).to_affine();
+            assert_eq!(fp_chip.get_assigned_value(&kP.x.into()), expected.x);
+            assert_eq!(fp_chip.get_assigned_value(&kP.y.into()), expected.y);
+        }
+    });
+}
@@ -11,21 +11,69 @@ use halo2_base::utils::fe_to_biguint;
 use halo2_base::{
     gates::{flex_gate::threads::SinglePhaseCoreManager, RangeChip},
     halo2_proofs::halo2curves::bn256::G1,
-    utils::testing::base_test,
+    utils::{
+        testing::{base_test, BaseTester},
+        ScalarField,
+    },
+    Context,
 };
 use rand::rngs::StdRng;
 use rand_core::SeedableRng;
 use serde::{Deserialize, Serialize};
+use std::fs::File;
 use std::io::Write;
 
 pub mod bls_signature;
+pub mod differential;
 pub mod ec_add;
+pub mod final_exp;
 pub mod fixed_base_msm;
+pub mod glv;
 pub mod msm;
 pub mod msm_sum_infinity;
 pub mod msm_sum_infinity_fixed_base;
 pub mod pairing;
 
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct PairingCircuitParams {
+    strategy: FpStrategy,
+    degree: u32,
+    num_advice: usize,
+    num_lookup_advice: usize,
+    num_fixed: usize,
+    lookup_bits: usize,
+    limb_bits: usize,
+    num_limbs: usize,
+}
+
+impl PairingCircuitParams {
+    /// Returns a [`BaseTester`] with the degree and lookup bits of these parameters.
+    pub fn base_test(&self) -> BaseTester {
+        base_test().k(self.degree).lookup_bits(self.lookup_bits)
+    }
+}
+
+/// Reads `configs/bn254/pairing_circuit.config` and returns its parameters together with a seeded rng, so that
+/// tests on the pairing config are reproducible.
+pub fn pairing_test_setup() -> (PairingCircuitParams, StdRng) {
+    let path = "configs/bn254/pairing_circuit.config";
+    let params: PairingCircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+    (params, StdRng::seed_from_u64(0))
+}
+
+/// Runs `f` on `ctx` and returns its output together with the number of advice cells it assigned.
+pub fn count_advice_cells<F: ScalarField, R>(
+    ctx: &mut Context<F>,
+    f: impl FnOnce(&mut Context<F>) -> R,
+) -> (R, usize) {
+    let start = ctx.advice.len();
+    let out = f(ctx);
+    (out, ctx.advice.len() - start)
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct MSMCircuitParams {
     strategy: FpStrategy,
@@ -1,10 +1,14 @@
 use crate::ff::{Field, PrimeField};
+use crate::group::cofactor::CofactorCurveAffine;
+use crate::group::Group;
 use std::{
     fs::{self, File},
     io::{BufRead, BufReader},
 };
 
 use super::*;
+use crate::halo2_proofs::halo2curves::bn256::G2Affine;
+use rand_core::RngCore;
 
 pub fn msm_test(
     pool: &mut SinglePhaseCoreManager<Fr>,
@@ -63,6 +67,110 @@ fn test_msm() {
     });
 }
 
+#[test]
+fn test_msm_identity_and_repeated_points() {
+    let path = "configs/bn254/msm_circuit.config";
+    let params: MSMCircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+    let (mut bases, scalars) = random_pairs(8, &StdRng::seed_from_u64(0));
+    bases[1] = G1Affine::identity();
+    bases[3] = bases[2];
+    bases[5] = -bases[4];
+    bases[7] = bases[2];
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run_builder(|pool, range| {
+        msm_test(pool, range, params, bases, scalars);
+    });
+}
+
+// Out of circuit double-and-add, independent of the halo2curves MSM
+fn naive_msm(bases: &[G1Affine], scalars: &[Fr]) -> G1Affine {
+    let scalars = scalars.iter().map(fe_to_biguint).collect::<Vec<_>>();
+    let mut acc = G1::identity();
+    for bit in (0..Fr::NUM_BITS as u64).rev() {
+        acc = acc.double();
+        for (base, scalar) in bases.iter().zip(scalars.iter()) {
+            if scalar.bit(bit) {
+                acc += base;
+            }
+        }
+    }
+    acc.to_affine()
+}
+
+#[test]
+fn test_variable_base_msm_with_window() {
+    // short scalars keep the bucket circuit for 64 points small
+    const MAX_BITS: usize = 16;
+    for (batch_size, window_bits) in [(1, 1), (2, 3), (64, 2)] {
+        let mut rng = StdRng::seed_from_u64(0);
+        let bases = (0..batch_size).map(|_| G1Affine::random(&mut rng)).collect::<Vec<_>>();
+        let scalars = (0..batch_size)
+            .map(|_| Fr::from(rng.next_u64() >> (64 - MAX_BITS)))
+            .collect::<Vec<_>>();
+        base_test().k(17).lookup_bits(16).run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+            let ecc_chip = EccChip::new(&fp_chip);
+            let scalars_assigned =
+                scalars.iter().map(|scalar| vec![ctx.load_witness(*scalar)]).collect::<Vec<_>>();
+            let bases_assigned = bases
+                .iter()
+                .map(|base| ecc_chip.load_private_unchecked(ctx, (base.x, base.y)))
+                .collect::<Vec<_>>();
+
+            let msm = ecc_chip.variable_base_msm_with_window(
+                ctx,
+                &bases_assigned,
+                scalars_assigned,
+                MAX_BITS,
+                window_bits,
+            );
+
+            let expected = naive_msm(&bases, &scalars);
+            assert_eq!(msm.x.value(), fe_to_biguint(&expected.x));
+            assert_eq!(msm.y.value(), fe_to_biguint(&expected.y));
+        });
+    }
+}
+
+#[test]
+fn test_variable_base_msm_g2() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let bases = (0..4).map(|_| G2Affine::random(&mut rng)).collect::<Vec<_>>();
+    let scalars = (0..4).map(|_| Fr::random(&mut rng)).collect::<Vec<_>>();
+    base_test().k(20).lookup_bits(19).run_builder(|pool, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let chip = PairingChip::new(&fp_chip);
+        let ctx = pool.main();
+        let scalars_assigned =
+            scalars.iter().map(|scalar| vec![ctx.load_witness(*scalar)]).collect::<Vec<_>>();
+        let bases_assigned =
+            bases.iter().map(|base| chip.load_private_g2(ctx, *base)).collect::<Vec<_>>();
+
+        let msm = chip.variable_base_msm_g2(
+            pool,
+            &bases_assigned,
+            scalars_assigned,
+            Fr::NUM_BITS as usize,
+            4,
+            true,
+        );
+
+        let expected = bases
+            .iter()
+            .zip(scalars.iter())
+            .map(|(base, scalar)| base * scalar)
+            .reduce(|a, b| a + b)
+            .unwrap()
+            .to_affine();
+        let [x, y] =
+            [&msm.x, &msm.y].map(|coord| coord.0.iter().map(|c| c.value()).collect::<Vec<_>>());
+        assert_eq!(x, [expected.x.c0, expected.x.c1].map(|c| fe_to_biguint(&c)));
+        assert_eq!(y, [expected.y.c0, expected.y.c1].map(|c| fe_to_biguint(&c)));
+    });
+}
+
 #[test]
 fn bench_msm() -> Result<(), Box<dyn std::error::Error>> {
     let config_path = "configs/bn254/bench_msm.config";
@@ -4,21 +4,14 @@ use std::{
 };
 
 use super::*;
-use crate::fields::FieldChip;
-use crate::{fields::FpStrategy, halo2_proofs::halo2curves::bn256::G2Affine};
+use crate::bn254::pairing::{prepared_g2::PreparedG2, sparse_fp12_multiply};
+use crate::ff::Field as _;
+use crate::fields::{FieldChip, FieldExtConstructor};
+use crate::group::Group as _;
+use crate::halo2_proofs::halo2curves::bn256::{multi_miller_loop, Fq, Fq2, G2Affine, G2Prepared};
+use crate::halo2_proofs::halo2curves::CurveAffine;
 use halo2_base::{gates::RangeChip, utils::BigPrimeField, Context};
-
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
-struct PairingCircuitParams {
-    strategy: FpStrategy,
-    degree: u32,
-    num_advice: usize,
-    num_lookup_advice: usize,
-    num_fixed: usize,
-    lookup_bits: usize,
-    limb_bits: usize,
-    num_limbs: usize,
-}
+use rand_core::RngCore;
 
 fn pairing_test<F: BigPrimeField>(
     ctx: &mut Context<F>,
@@ -44,19 +37,370 @@ fn pairing_test<F: BigPrimeField>(
 
 #[test]
 fn test_pairing() {
-    let path = "configs/bn254/pairing_circuit.config";
-    let params: PairingCircuitParams = serde_json::from_reader(
-        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
-    )
-    .unwrap();
-    let mut rng = StdRng::seed_from_u64(0);
+    let (params, mut rng) = pairing_test_setup();
     let P = G1Affine::random(&mut rng);
     let Q = G2Affine::random(&mut rng);
-    base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+    params.base_test().run(|ctx, range| {
         pairing_test(ctx, range, params, P, Q);
     });
 }
 
+fn miller_loop_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    params: PairingCircuitParams,
+    P: G1Affine,
+    Q: G2Affine,
+) {
+    let fp_chip = FpChip::<F>::new(range, params.limb_bits, params.num_limbs);
+    let chip = PairingChip::new(&fp_chip);
+    let P_assigned = chip.load_private_g1(ctx, P);
+    let Q_assigned = chip.load_private_g2(ctx, Q);
+    let f0 = chip.miller_loop(ctx, &Q_assigned, &P_assigned);
+    let f = chip.final_exp(ctx, f0);
+    let actual_f = pairing(&P, &Q);
+    let fp12_chip = Fp12Chip::new(&fp_chip);
+    assert_eq!(
+        format!("Gt({:?})", fp12_chip.get_assigned_value(&f.into())),
+        format!("{actual_f:?}")
+    );
+}
+
+#[test]
+fn test_miller_loop_final_exp() {
+    let (params, mut rng) = pairing_test_setup();
+    let P = G1Affine::random(&mut rng);
+    let Q = G2Affine::random(&mut rng);
+    params.base_test().run(|ctx, range| {
+        miller_loop_test(ctx, range, params, P, Q);
+    });
+}
+
+fn multi_miller_loop_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    params: PairingCircuitParams,
+    pairs: &[(G1Affine, G2Affine)],
+) {
+    let fp_chip = FpChip::<F>::new(range, params.limb_bits, params.num_limbs);
+    let chip = PairingChip::new(&fp_chip);
+    let assigned = pairs
+        .iter()
+        .map(|&(P, Q)| (chip.load_private_g1(ctx, P), chip.load_private_g2(ctx, Q)))
+        .collect::<Vec<_>>();
+    let f = chip.multi_miller_loop(ctx, assigned.iter().map(|(P, Q)| (P, Q)).collect());
+
+    let prepared = pairs.iter().map(|(P, Q)| (P, G2Prepared::from(*Q))).collect::<Vec<_>>();
+    let terms = prepared.iter().map(|(P, Q)| (*P, Q)).collect::<Vec<_>>();
+    let actual_f = multi_miller_loop(&terms);
+    let fp12_chip = Fp12Chip::new(&fp_chip);
+    assert_eq!(
+        format!("Gt({:?})", fp12_chip.get_assigned_value(&f.into())),
+        format!("{actual_f:?}")
+    );
+}
+
+#[test]
+fn test_multi_miller_loop() {
+    let (params, mut rng) = pairing_test_setup();
+    let pairs = (0..3)
+        .map(|_| (G1Affine::random(&mut rng), G2Affine::random(&mut rng)))
+        .collect::<Vec<_>>();
+    params.base_test().run(|ctx, range| {
+        multi_miller_loop_test(ctx, range, params, &pairs);
+    });
+}
+
+#[test]
+fn test_sparse_fp12_multiply() {
+    let mut rng = StdRng::seed_from_u64(0);
+    // nonzero slots of `sparse_line_function_equal` and `sparse_line_function_unequal`
+    for slots in [[0, 3, 4], [2, 3, 5]] {
+        let a = Fq12::random(&mut rng);
+        let mut line = [None; 6];
+        for i in slots {
+            line[i] = Some(Fq2::random(&mut rng));
+        }
+        let mut dense = [Fq::zero(); 12];
+        for (i, coeff) in line.iter().enumerate() {
+            if let Some(coeff) = coeff {
+                dense[i] = coeff.c0;
+                dense[i + 6] = coeff.c1;
+            }
+        }
+        let dense = <Fq12 as FieldExtConstructor<Fq, 12>>::new(dense);
+        base_test().k(14).lookup_bits(13).run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+            let fp2_chip = Fp2Chip::<Fr>::new(&fp_chip);
+            let fp12_chip = Fp12Chip::<Fr>::new(&fp_chip);
+            let a_assigned = fp12_chip.load_private(ctx, a);
+            let line =
+                line.map(|coeff| coeff.map(|coeff| fp2_chip.load_private(ctx, coeff))).to_vec();
+            let dense = fp12_chip.load_private(ctx, dense);
+
+            let sparse_prod = sparse_fp12_multiply(&fp2_chip, ctx, &a_assigned, &line);
+            let dense_prod = fp12_chip.mul(ctx, &a_assigned, &dense);
+            assert_eq!(
+                fp12_chip.get_assigned_value(&sparse_prod.into()),
+                fp12_chip.get_assigned_value(&dense_prod.into())
+            );
+        });
+    }
+}
+
+fn miller_loop_prepared_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    params: PairingCircuitParams,
+    pairs: &[(G1Affine, G2Affine)],
+) {
+    let fp_chip = FpChip::<F>::new(range, params.limb_bits, params.num_limbs);
+    let chip = PairingChip::new(&fp_chip);
+    let assigned = pairs
+        .iter()
+        .map(|&(P, Q)| (chip.load_private_g1(ctx, P), chip.load_private_g2(ctx, Q)))
+        .collect::<Vec<_>>();
+    let f = chip.multi_miller_loop(ctx, assigned.iter().map(|(P, Q)| (P, Q)).collect());
+
+    let prepared = pairs.iter().map(|&(_, Q)| PreparedG2::new(Q)).collect::<Vec<_>>();
+    let prepared_pairs =
+        assigned.iter().zip(&prepared).map(|((P, _), Q)| (P, Q)).collect::<Vec<_>>();
+    let f_prepared = chip.miller_loop_prepared(ctx, &prepared_pairs);
+
+    let fp12_chip = Fp12Chip::new(&fp_chip);
+    assert_eq!(
+        fp12_chip.get_assigned_value(&f.into()),
+        fp12_chip.get_assigned_value(&f_prepared.into())
+    );
+}
+
+#[test]
+fn test_miller_loop_prepared() {
+    let (params, mut rng) = pairing_test_setup();
+    for num_pairs in [1, 2] {
+        let pairs = (0..num_pairs)
+            .map(|_| (G1Affine::random(&mut rng), G2Affine::random(&mut rng)))
+            .collect::<Vec<_>>();
+        params.base_test().run(|ctx, range| {
+            miller_loop_prepared_test(ctx, range, params, &pairs);
+        });
+    }
+}
+
+#[test]
+fn test_miller_loop_prepared_cells() {
+    let (params, mut rng) = pairing_test_setup();
+    let P = G1Affine::random(&mut rng);
+    let Q = G2Affine::random(&mut rng);
+    params.base_test().run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let chip = PairingChip::new(&fp_chip);
+        let P_assigned = chip.load_private_g1(ctx, P);
+
+        let (f, cells) = count_advice_cells(ctx, |ctx| {
+            let Q_assigned = chip.load_private_g2(ctx, Q);
+            chip.miller_loop(ctx, &Q_assigned, &P_assigned)
+        });
+        let prepared = PreparedG2::new(Q);
+        let (f_prepared, prepared_cells) = count_advice_cells(ctx, |ctx| {
+            chip.miller_loop_prepared(ctx, &[(&P_assigned, &prepared)])
+        });
+        assert!(prepared_cells < cells, "{prepared_cells} >= {cells}");
+        let fp12_chip = Fp12Chip::new(&fp_chip);
+        assert_eq!(
+            fp12_chip.get_assigned_value(&f.into()),
+            fp12_chip.get_assigned_value(&f_prepared.into())
+        );
+    });
+}
+
+fn is_pairing_product_one_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    params: PairingCircuitParams,
+    pairs: &[(G1Affine, G2Affine)],
+    expected: bool,
+) {
+    let fp_chip = FpChip::<F>::new(range, params.limb_bits, params.num_limbs);
+    let chip = PairingChip::new(&fp_chip);
+    let assigned = pairs
+        .iter()
+        .map(|&(P, Q)| (chip.load_private_g1(ctx, P), chip.load_private_g2(ctx, Q)))
+        .collect::<Vec<_>>();
+    let pairs = assigned.iter().map(|(P, Q)| (P, Q)).collect::<Vec<_>>();
+    let is_one = chip.is_pairing_product_one(ctx, &pairs);
+    assert_eq!(is_one.value(), &F::from(expected as u64));
+}
+
+#[test]
+fn test_is_pairing_product_one() {
+    let (params, mut rng) = pairing_test_setup();
+    let P = G1Affine::random(&mut rng);
+    let Q = G2Affine::random(&mut rng);
+    let a = Fr::random(&mut rng);
+    let aP = G1Affine::from(P * a);
+    let aQ = G2Affine::from(Q * a);
+    // e(aP, Q) * e(-P, aQ) = 1
+    params.base_test().run(|ctx, range| {
+        is_pairing_product_one_test(ctx, range, params, &[(aP, Q), (-P, aQ)], true);
+    });
+    // e(P, Q) * e(-P, aQ) != 1
+    params.base_test().run(|ctx, range| {
+        is_pairing_product_one_test(ctx, range, params, &[(P, Q), (-P, aQ)], false);
+    });
+}
+
+#[test]
+fn test_is_pairing_product_one_empty() {
+    base_test().k(10).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let chip = PairingChip::new(&fp_chip);
+        let is_one = chip.is_pairing_product_one(ctx, &[]);
+        assert_eq!(is_one.value(), &Fr::one());
+    });
+}
+
+fn assert_pairing_eq_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    params: PairingCircuitParams,
+    (P, Q): (G1Affine, G2Affine),
+    (S, T): (G1Affine, G2Affine),
+) {
+    let fp_chip = FpChip::<F>::new(range, params.limb_bits, params.num_limbs);
+    let chip = PairingChip::new(&fp_chip);
+    let [P, S] = [P, S].map(|pt| chip.load_private_g1(ctx, pt));
+    let [Q, T] = [Q, T].map(|pt| chip.load_private_g2(ctx, pt));
+    chip.assert_pairing_eq(ctx, &Q, &P, &T, &S);
+}
+
+#[test]
+fn test_assert_pairing_eq() {
+    let (params, mut rng) = pairing_test_setup();
+    let P = G1Affine::random(&mut rng);
+    let Q = G2Affine::random(&mut rng);
+    let a = Fr::random(&mut rng);
+    let aP = G1Affine::from(P * a);
+    let aQ = G2Affine::from(Q * a);
+    // e(aP, Q) = e(P, aQ)
+    params.base_test().run(|ctx, range| {
+        assert_pairing_eq_test(ctx, range, params, (aP, Q), (P, aQ));
+    });
+    params.base_test().expect_satisfied(false).run(|ctx, range| {
+        assert_pairing_eq_test(ctx, range, params, (P, Q), (P, aQ));
+    });
+}
+
+#[test]
+fn test_load_g1_g2() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let P = G1Affine::random(&mut rng);
+    let Q = G2Affine::random(&mut rng);
+    base_test().k(15).lookup_bits(14).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let chip = PairingChip::new(&fp_chip);
+        let fp2_chip = Fp2Chip::<Fr>::new(&fp_chip);
+        let g1_chip = EccChip::new(&fp_chip);
+        let g2_chip = EccChip::new(&fp2_chip);
+        let P_witness = chip.load_g1_witness(ctx, (P.x, P.y));
+        let P_constant = chip.load_g1(ctx, P);
+        g1_chip.assert_equal(ctx, P_witness, P_constant);
+        let Q_witness = chip.load_g2_witness(ctx, (Q.x, Q.y));
+        let Q_constant = chip.load_g2(ctx, Q);
+        g2_chip.assert_equal(ctx, Q_witness, Q_constant);
+    });
+}
+
+#[test]
+fn test_load_g1_witness_off_curve() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let P = G1Affine::random(&mut rng);
+    base_test().k(15).lookup_bits(14).expect_satisfied(false).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let chip = PairingChip::new(&fp_chip);
+        chip.load_g1_witness(ctx, (P.x, P.y + Fq::one()));
+    });
+}
+
+#[test]
+fn test_load_g2_witness_off_curve() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let Q = G2Affine::random(&mut rng);
+    base_test().k(15).lookup_bits(14).expect_satisfied(false).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, 88, 3);
+        let chip = PairingChip::new(&fp_chip);
+        chip.load_g2_witness(ctx, (Q.x, Q.y + Fq2::one()));
+    });
+}
+
+fn g2_subgroup_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    params: PairingCircuitParams,
+    Q: G2Affine,
+    expected: bool,
+) {
+    let fp_chip = FpChip::<F>::new(range, params.limb_bits, params.num_limbs);
+    let chip = PairingChip::new(&fp_chip);
+    let Q_assigned = chip.load_private_g2(ctx, Q);
+    let is_in_subgroup = chip.is_in_subgroup_g2(ctx, &Q_assigned);
+    assert_eq!(is_in_subgroup.value(), &F::from(expected as u64));
+}
+
+// random point on the twist `E'(Fp2)`, which is outside of G2 with overwhelming probability
+pub(super) fn random_twist_point(mut rng: impl RngCore) -> G2Affine {
+    loop {
+        let x = Fq2::random(&mut rng);
+        let y2 = x.square() * x + G2Affine::b();
+        if let Some(y) = Option::<Fq2>::from(y2.sqrt()) {
+            return G2Affine::from_xy(x, y).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_g2_subgroup_check() {
+    let (params, mut rng) = pairing_test_setup();
+    let Q = G2Affine::random(&mut rng);
+    params.base_test().run(|ctx, range| {
+        g2_subgroup_test(ctx, range, params, Q, true);
+    });
+    let Q = random_twist_point(&mut rng);
+    // [r]Q != O
+    assert!(!bool::from((Q * -Fr::one() + Q).is_identity()));
+    params.base_test().run(|ctx, range| {
+        g2_subgroup_test(ctx, range, params, Q, false);
+    });
+}
+
+#[test]
+fn test_g2_subgroup_check_infinity() {
+    let (params, _) = pairing_test_setup();
+    params.base_test().run(|ctx, range| {
+        let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let fp2_chip = Fp2Chip::<Fr>::new(&fp_chip);
+        let chip = PairingChip::new(&fp_chip);
+        let inf = EccChip::new(&fp2_chip).load_private_unchecked(ctx, (Fq2::zero(), Fq2::zero()));
+        let is_in_subgroup = chip.is_in_subgroup_g2(ctx, &inf);
+        assert_eq!(is_in_subgroup.value(), &Fr::one());
+    });
+}
+
+#[test]
+fn test_assert_in_subgroup_g2() {
+    let (params, mut rng) = pairing_test_setup();
+    for (Q, expected) in [(G2Affine::random(&mut rng), true), (random_twist_point(&mut rng), false)]
+    {
+        params.base_test().expect_satisfied(expected).run(|ctx, range| {
+            let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+            let chip = PairingChip::new(&fp_chip);
+            let fp2_chip = Fp2Chip::<Fr>::new(&fp_chip);
+            let Q_assigned = chip.load_private_g2(ctx, Q);
+            EccChip::new(&fp2_chip).assert_in_subgroup_g2(ctx, &Q_assigned);
+        });
+    }
+}
+
 #[test]
 fn bench_pairing() -> Result<(), Box<dyn std::error::Error>> {
     let config_path = "configs/bn254/bench_pairing.config";
@@ -1,7 +1,10 @@
-use halo2_base::utils::BigPrimeField;
+use halo2_base::gates::flex_gate::threads::SinglePhaseCoreManager;
+use halo2_base::gates::RangeInstructions;
+use halo2_base::utils::{biguint_to_fe, fe_to_biguint, BigPrimeField};
 use halo2_base::{gates::GateInstructions, utils::CurveAffineExt, AssignedValue, Context};
+use num_bigint::BigUint;
 
-use crate::bigint::{big_is_equal, big_less_than, FixedOverflowInteger, ProperCrtUint};
+use crate::bigint::{big_is_equal, big_less_than, FixedOverflowInteger, ProperCrtUint, ProperUint};
 use crate::fields::{fp::FpChip, FieldChip};
 
 use super::{fixed_base, scalar_multiply, EcPoint, EccChip};
@@ -103,3 +106,273 @@ where
     let res5 = base_chip.gate().and(ctx, res4, equal_check);
     res5
 }
+
+/// Same as [`ecdsa_verify_no_pubkey_check`], but additionally returns 0 unless `s <= (n - 1) / 2`.
+///
+/// Requiring a low `s` rules out the malleability of ECDSA signatures, where `(r, n - s)` is also valid
+/// whenever `(r, s)` is. The bound is checked in circuit with [`big_less_than::crt`] against the constant
+/// `(n + 1) / 2`, so it is part of the proof.
+///
+/// **WARNING**: The same restrictions as [`ecdsa_verify_no_pubkey_check`] apply.
+pub fn ecdsa_verify_low_s_no_pubkey_check<
+    F: BigPrimeField,
+    CF: BigPrimeField,
+    SF: BigPrimeField,
+    GA,
+>(
+    chip: &EccChip<F, FpChip<F, CF>>,
+    ctx: &mut Context<F>,
+    pubkey: EcPoint<F, <FpChip<F, CF> as FieldChip<F>>::FieldPoint>,
+    r: ProperCrtUint<F>,
+    s: ProperCrtUint<F>,
+    msghash: ProperCrtUint<F>,
+    var_window_bits: usize,
+    fixed_window_bits: usize,
+) -> AssignedValue<F>
+where
+    GA: CurveAffineExt<Base = CF, ScalarExt = SF>,
+{
+    let base_chip = chip.field_chip;
+    let scalar_chip =
+        FpChip::<F, SF>::new(base_chip.range, base_chip.limb_bits, base_chip.num_limbs);
+    // s <= (n - 1) / 2 iff s < (n + 1) / 2 since n is odd
+    let half_n =
+        scalar_chip.load_constant_uint(ctx, (scalar_chip.p.to_biguint().unwrap() + 1u64) >> 1);
+    let s_low = big_less_than::crt(
+        base_chip.range(),
+        ctx,
+        &s,
+        &half_n,
+        base_chip.limb_bits,
+        base_chip.limb_bases[1],
+    );
+    let res = ecdsa_verify_no_pubkey_check::<F, CF, SF, GA>(
+        chip,
+        ctx,
+        pubkey,
+        r,
+        s,
+        msghash,
+        var_window_bits,
+        fixed_window_bits,
+    );
+    base_chip.gate().and(ctx, res, s_low)
+}
+
+/// Recovers the ECDSA public key `Q = r^{-1} (s R - msghash G)` from a signature `(r, s)` on `msghash`
+/// and its `recovery_id`, where `R` is the nonce point.
+///
+/// As in Ethereum's `ecrecover`, bit 0 of `recovery_id` is the parity of `R.y` and bit 1 is set when
+/// `R.x = r + n` instead of `R.x = r` (only possible when `r + n < p`). `R` is reconstructed with
+/// [`EccChip::decompress`] and the circuit is unsatisfiable if there is no such point.
+///
+/// # Assumptions
+/// * `r, s` are proper CRT integers in `[1, n - 1]` (constrained to be nonzero here)
+/// * `msghash` is a proper CRT integer in `[1, n - 1]`
+/// * `recovery_id` is in `[0, 3]` (constrained here)
+/// * the order of the curve is `n` (the curve has cofactor 1)
+pub fn recover_pubkey<F: BigPrimeField, CF: BigPrimeField, SF: BigPrimeField, GA>(
+    chip: &EccChip<F, FpChip<F, CF>>,
+    ctx: &mut Context<F>,
+    r: ProperCrtUint<F>,
+    s: ProperCrtUint<F>,
+    msghash: ProperCrtUint<F>,
+    recovery_id: AssignedValue<F>,
+    var_window_bits: usize,
+    fixed_window_bits: usize,
+) -> EcPoint<F, <FpChip<F, CF> as FieldChip<F>>::FieldPoint>
+where
+    GA: CurveAffineExt<Base = CF, ScalarExt = SF>,
+{
+    let base_chip = chip.field_chip;
+    let gate = base_chip.gate();
+    let scalar_chip =
+        FpChip::<F, SF>::new(base_chip.range, base_chip.limb_bits, base_chip.num_limbs);
+    let n = scalar_chip.p.to_biguint().unwrap();
+    let p = base_chip.p.to_biguint().unwrap();
+
+    let r_valid = scalar_chip.is_soft_nonzero(ctx, &r);
+    let s_valid = scalar_chip.is_soft_nonzero(ctx, &s);
+    let rs_valid = gate.and(ctx, r_valid, s_valid);
+    gate.assert_is_const(ctx, &rs_valid, &F::ONE);
+
+    let id_bits = gate.num_to_bits(ctx, recovery_id, 2);
+    let (y_is_odd, x_overflows) = (id_bits[0], id_bits[1]);
+
+    // R.x = r + n is only a valid coordinate when r < p - n, so it must not wrap around mod p
+    let x = if p > n {
+        let p_minus_n =
+            FixedOverflowInteger::from_native(&(&p - &n), base_chip.num_limbs, base_chip.limb_bits)
+                .assign(ctx);
+        let r_small = big_less_than::assign(
+            base_chip.range(),
+            ctx,
+            r.clone(),
+            p_minus_n,
+            base_chip.limb_bits,
+            base_chip.limb_bases[1],
+        );
+        let x_not_overflows = gate.not(ctx, x_overflows);
+        let x_valid = gate.or(ctx, x_not_overflows, r_small);
+        gate.assert_is_const(ctx, &x_valid, &F::ONE);
+        let r_plus_n = base_chip.add_constant_no_carry(ctx, r.clone(), biguint_to_fe(&n));
+        let r_plus_n = base_chip.carry_mod(ctx, r_plus_n);
+        base_chip.select(ctx, r_plus_n, r.clone(), x_overflows)
+    } else {
+        gate.assert_is_const(ctx, &x_overflows, &F::ZERO);
+        r.clone()
+    };
+    let R = chip.decompress::<GA>(ctx, x, y_is_odd);
+
+    // Q = u1 * R + u2 * G with u1 = s / r and u2 = -msghash / r
+    let u1 = scalar_chip.divide_unsafe(ctx, s, &r);
+    let u2 = scalar_chip.neg_divide_unsafe(ctx, msghash, r);
+    let u1: ProperCrtUint<F> = scalar_chip.enforce_less_than(ctx, u1).into();
+    let u2: ProperCrtUint<F> = scalar_chip.enforce_less_than(ctx, u2).into();
+
+    let u1_mul = scalar_multiply::<_, _, GA>(
+        base_chip,
+        ctx,
+        R,
+        u1.limbs().to_vec(),
+        base_chip.limb_bits,
+        var_window_bits,
+    );
+    let u2_mul = fixed_base::scalar_multiply(
+        base_chip,
+        ctx,
+        &GA::generator(),
+        u2.limbs().to_vec(),
+        base_chip.limb_bits,
+        fixed_window_bits,
+    );
+    // u1 * R and u2 * G may be equal, so we must use `EccChip::sum`
+    chip.sum::<GA>(ctx, [u1_mul, u2_mul])
+}
+
+/// An ECDSA signature for [`ecdsa_verify_batch`]. Besides `(r, s)`, the nonce point `R` itself must be
+/// witnessed, with `R.x = r mod n`, since batching needs the full point and not only its x-coordinate.
+#[derive(Clone, Debug)]
+pub struct BatchEcdsaInput<F: BigPrimeField> {
+    pub pubkey: EcPoint<F, ProperCrtUint<F>>,
+    pub r: ProperCrtUint<F>,
+    pub s: ProperCrtUint<F>,
+    pub msghash: ProperCrtUint<F>,
+    pub R: EcPoint<F, ProperCrtUint<F>>,
+}
+
+/// Verifies a batch of ECDSA signatures at once by a random linear combination: with `a_i = challenge^i mod n`,
+/// returns 1 iff every `(r_i, s_i)` is in `[1, n - 1]`, `R_i.x = r_i` and
+/// ```text
+/// (sum_i a_i u1_i) G + sum_i (a_i u2_i) Q_i == sum_i a_i R_i
+/// ```
+/// where `u1_i = msghash_i / s_i` and `u2_i = r_i / s_i`. The two sides are each computed with a single
+/// [`EccChip::msm`], instead of two scalar multiplications per signature.
+///
+/// If some signature is invalid, the check passes for at most `sigs.len() - 1` values of `challenge`, so
+/// `challenge` must be derived after all inputs are fixed, e.g. squeezed from a transcript of them.
+///
+/// **WARNING**: Only use this function if `1 / (p - n)` is very small (e.g., < 2<sup>-100</sup>), as in
+/// [`ecdsa_verify_no_pubkey_check`].
+///
+/// # Assumptions
+/// * `sigs` is nonempty
+/// * `pubkey` and `R` of each signature are on the curve and not the identity point
+/// * `r, s, msghash` are proper CRT integers with the limb configuration of `chip`
+/// * `challenge < 2^128` (constrained here)
+pub fn ecdsa_verify_batch<F: BigPrimeField, CF: BigPrimeField, SF: BigPrimeField, GA>(
+    chip: &EccChip<F, FpChip<F, CF>>,
+    builder: &mut SinglePhaseCoreManager<F>,
+    sigs: Vec<BatchEcdsaInput<F>>,
+    challenge: AssignedValue<F>,
+) -> AssignedValue<F>
+where
+    GA: CurveAffineExt<Base = CF, ScalarExt = SF>,
+{
+    assert!(!sigs.is_empty(), "sigs must not be empty");
+    let base_chip = chip.field_chip;
+    let scalar_chip =
+        FpChip::<F, SF>::new(base_chip.range, base_chip.limb_bits, base_chip.num_limbs);
+    let gate = base_chip.gate();
+
+    let ctx = builder.main();
+    let challenge = load_small_scalar(&scalar_chip, ctx, challenge, 128);
+    let mut a = scalar_chip.load_constant(ctx, SF::ONE);
+    let mut is_valid = ctx.load_constant(F::ONE);
+    let mut u1_sum = None;
+    let mut pubkeys = Vec::with_capacity(sigs.len() + 1);
+    let mut pubkey_scalars = Vec::with_capacity(sigs.len() + 1);
+    let mut nonces = Vec::with_capacity(sigs.len());
+    let mut nonce_scalars = Vec::with_capacity(sigs.len());
+    for (i, sig) in sigs.into_iter().enumerate() {
+        if i != 0 {
+            let a_next = scalar_chip.mul(ctx, &a, &challenge);
+            a = scalar_chip.enforce_less_than(ctx, a_next).into();
+        }
+        // check r, s in [1, n - 1]
+        let r_valid = scalar_chip.is_soft_nonzero(ctx, &sig.r);
+        let s_valid = scalar_chip.is_soft_nonzero(ctx, &sig.s);
+        // check R.x == r, see `ecdsa_verify_no_pubkey_check` for why R.x is not reduced mod n
+        let R_x = scalar_chip.enforce_less_than(ctx, sig.R.x.clone());
+        let x_valid = big_is_equal::assign(gate, ctx, R_x.0, sig.r.clone());
+        for valid in [r_valid, s_valid, x_valid] {
+            is_valid = gate.and(ctx, is_valid, valid);
+        }
+
+        let u1 = scalar_chip.divide_unsafe(ctx, sig.msghash, &sig.s);
+        let u2 = scalar_chip.divide_unsafe(ctx, sig.r, sig.s);
+        let a_u1 = scalar_chip.mul_no_carry(ctx, &a, u1);
+        u1_sum = Some(match u1_sum {
+            Some(sum) => scalar_chip.add_no_carry(ctx, sum, a_u1),
+            None => a_u1,
+        });
+        let a_u2 = scalar_chip.mul(ctx, &a, u2);
+        let a_u2: ProperCrtUint<F> = scalar_chip.enforce_less_than(ctx, a_u2).into();
+
+        pubkeys.push(sig.pubkey);
+        pubkey_scalars.push(a_u2.limbs().to_vec());
+        nonces.push(sig.R);
+        nonce_scalars.push(a.limbs().to_vec());
+    }
+    let u1_sum = scalar_chip.carry_mod(ctx, u1_sum.unwrap());
+    let u1_sum: ProperCrtUint<F> = scalar_chip.enforce_less_than(ctx, u1_sum).into();
+    pubkeys.push(chip.assign_constant_point(ctx, GA::generator()));
+    pubkey_scalars.push(u1_sum.limbs().to_vec());
+
+    let lhs = chip.msm::<GA>(builder, &pubkeys, pubkey_scalars, base_chip.limb_bits);
+    let rhs = chip.msm::<GA>(builder, &nonces, nonce_scalars, base_chip.limb_bits);
+
+    let ctx = builder.main();
+    let equal_check = chip.is_equal(ctx, lhs, rhs);
+    gate.and(ctx, is_valid, equal_check)
+}
+
+/// Converts `a < 2^num_bits` in the native field into a [`ProperCrtUint`] of `chip`, constraining the range of `a`.
+fn load_small_scalar<F: BigPrimeField, Fp: BigPrimeField>(
+    chip: &FpChip<F, Fp>,
+    ctx: &mut Context<F>,
+    a: AssignedValue<F>,
+    num_bits: usize,
+) -> ProperCrtUint<F> {
+    assert!(num_bits <= chip.limb_bits * chip.num_limbs);
+    let value = fe_to_biguint(a.value());
+    let mut limbs = Vec::with_capacity(chip.num_limbs);
+    let (mut rest, mut rest_bits) = (a, num_bits);
+    for _ in 0..chip.num_limbs {
+        if rest_bits > chip.limb_bits {
+            let (quot, rem) =
+                chip.range().div_mod(ctx, rest, BigUint::from(1u64) << chip.limb_bits, rest_bits);
+            limbs.push(rem);
+            rest = quot;
+            rest_bits -= chip.limb_bits;
+        } else {
+            if rest_bits == num_bits {
+                chip.range().range_check(ctx, rest, rest_bits);
+            }
+            limbs.push(rest);
+            rest = ctx.load_zero();
+            rest_bits = 0;
+        }
+    }
+    ProperUint(limbs).into_crt(ctx, chip.gate(), value, &chip.limb_bases, chip.limb_bits)
+}
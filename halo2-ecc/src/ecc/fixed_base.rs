@@ -11,6 +11,59 @@ use itertools::Itertools;
 use rayon::prelude::*;
 use std::cmp::min;
 
+/// Windowed multiples of a fixed point `P`, computed out of circuit for [`scalar_multiply_with_table`].
+///
+/// `points[i * 2^w + j]` holds `[j * 2^(i * w)] * P` for `j` in `{1, ..., 2^w - 1}`, where `w = window_bits`.
+/// The entry for `j = 0` holds `[2^(i * w)] * P` as a dummy value in place of the identity; it is never added
+/// since zero windows are skipped with a select. The last window has `2^(total_bits - i * w)` entries if
+/// `total_bits` is not a multiple of `w`.
+///
+/// The table is empty if `P` is the point at infinity.
+#[derive(Clone, Debug)]
+pub struct FixedBaseTable<C: CurveAffineExt> {
+    pub point: C,
+    pub total_bits: usize,
+    pub window_bits: usize,
+    pub points: Vec<C>,
+}
+
+impl<C: CurveAffineExt> FixedBaseTable<C> {
+    /// Computes the table for scalars of `total_bits` bits with windows of `window_bits` bits.
+    pub fn new(point: C, total_bits: usize, window_bits: usize) -> Self {
+        assert_ne!(window_bits, 0);
+        if point.is_identity().into() {
+            return Self { point, total_bits, window_bits, points: vec![] };
+        }
+        let num_windows = (total_bits + window_bits - 1) / window_bits;
+
+        // first we compute all cached points in Jacobian coordinates since it's fastest
+        let mut increment = point.to_curve();
+        let cached_points_jacobian = (0..num_windows)
+            .flat_map(|i| {
+                let mut curr = increment;
+                // start with increment at index 0 instead of identity just as a dummy value to avoid divide by 0 issues
+                let cache_vec = std::iter::once(increment)
+                    .chain((1..(1usize << min(window_bits, total_bits - i * window_bits))).map(
+                        |_| {
+                            let prev = curr;
+                            curr += increment;
+                            prev
+                        },
+                    ))
+                    .collect::<Vec<_>>();
+                increment = curr;
+                cache_vec
+            })
+            .collect::<Vec<_>>();
+        // for use in circuits we need affine coordinates, so we do a batch normalize: this is much more efficient than calling `to_affine` one by one since field inversion is very expensive
+        // initialize to all 0s
+        let mut points = vec![C::default(); cached_points_jacobian.len()];
+        C::Curve::batch_normalize(&cached_points_jacobian, &mut points);
+
+        Self { point, total_bits, window_bits, points }
+    }
+}
+
 /// Computes `[scalar] * P` on y^2 = x^3 + b where `P` is fixed (constant)
 /// - `scalar` is represented as a non-empty reference array of `AssignedValue`s
 /// - `scalar = sum_i scalar_i * 2^{max_bits * i}`
@@ -33,45 +86,41 @@ where
     C: CurveAffineExt,
     FC: FieldChip<F, FieldType = C::Base> + Selectable<F, FC::FieldPoint>,
 {
-    if point.is_identity().into() {
+    let table = FixedBaseTable::new(*point, max_bits * scalar.len(), window_bits);
+    scalar_multiply_with_table(chip, ctx, &table, scalar, max_bits)
+}
+
+/// Same as [`scalar_multiply`], but with the windowed multiples of `P` given by a precomputed `table`,
+/// so repeated multiplications against the same base do not recompute them.
+///
+/// # Assumptions
+/// - Same as [`scalar_multiply`]
+/// - `max_bits * scalar.len() == table.total_bits`
+pub fn scalar_multiply_with_table<F, FC, C>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    table: &FixedBaseTable<C>,
+    scalar: Vec<AssignedValue<F>>,
+    max_bits: usize,
+) -> EcPoint<F, FC::FieldPoint>
+where
+    F: BigPrimeField,
+    C: CurveAffineExt,
+    FC: FieldChip<F, FieldType = C::Base> + Selectable<F, FC::FieldPoint>,
+{
+    if table.point.is_identity().into() {
         let zero = chip.load_constant(ctx, C::Base::ZERO);
         return EcPoint::new(zero.clone(), zero);
     }
     assert!(!scalar.is_empty());
     assert!((max_bits as u32) <= F::NUM_BITS);
-
-    let total_bits = max_bits * scalar.len();
-    let num_windows = (total_bits + window_bits - 1) / window_bits;
-
-    // Jacobian coordinate
-    let base_pt = point.to_curve();
-    // cached_points[i * 2^w + j] holds `[j * 2^(i * w)] * point` for j in {0, ..., 2^w - 1}
-
-    // first we compute all cached points in Jacobian coordinates since it's fastest
-    let mut increment = base_pt;
-    let cached_points_jacobian = (0..num_windows)
-        .flat_map(|i| {
-            let mut curr = increment;
-            // start with increment at index 0 instead of identity just as a dummy value to avoid divide by 0 issues
-            let cache_vec = std::iter::once(increment)
-                .chain((1..(1usize << min(window_bits, total_bits - i * window_bits))).map(|_| {
-                    let prev = curr;
-                    curr += increment;
-                    prev
-                }))
-                .collect::<Vec<_>>();
-            increment = curr;
-            cache_vec
-        })
-        .collect::<Vec<_>>();
-    // for use in circuits we need affine coordinates, so we do a batch normalize: this is much more efficient than calling `to_affine` one by one since field inversion is very expensive
-    // initialize to all 0s
-    let mut cached_points_affine = vec![C::default(); cached_points_jacobian.len()];
-    C::Curve::batch_normalize(&cached_points_jacobian, &mut cached_points_affine);
+    assert_eq!(max_bits * scalar.len(), table.total_bits);
+    let window_bits = table.window_bits;
 
     // TODO: do not assign and use select_from_bits on Constant(_) QuantumCells
-    let cached_points = cached_points_affine
-        .into_iter()
+    let cached_points = table
+        .points
+        .iter()
         .map(|point| {
             let (x, y) = point.into_coordinates();
             let [x, y] = [x, y].map(|x| chip.load_constant(ctx, x));
@@ -1,16 +1,20 @@
 #![allow(non_snake_case)]
+use crate::bigint::ProperCrtUint;
 use crate::ff::Field;
 use crate::fields::{fp::FpChip, FieldChip, Selectable};
 use crate::group::{Curve, Group};
 use crate::halo2_proofs::arithmetic::CurveAffine;
 use halo2_base::gates::flex_gate::threads::SinglePhaseCoreManager;
-use halo2_base::utils::{modulus, BigPrimeField};
+use halo2_base::utils::{fe_to_biguint, modulus, BigPrimeField};
 use halo2_base::{
     gates::{GateInstructions, RangeInstructions},
     utils::CurveAffineExt,
     AssignedValue, Context,
+    QuantumCell::Constant,
 };
 use itertools::Itertools;
+use num_bigint::BigUint;
+use num_traits::Zero;
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use std::marker::PhantomData;
@@ -21,6 +25,8 @@ pub mod schnorr_signature;
 // pub mod fixed_base_pippenger;
 pub mod pippenger;
 
+pub use fixed_base::FixedBaseTable;
+
 // EcPoint and EccChip take in a generic `FieldChip` to implement generic elliptic curve operations on arbitrary field extensions (provided chip exists) for short Weierstrass curves (currently further assuming a4 = 0 for optimization purposes)
 #[derive(Debug)]
 pub struct EcPoint<F: BigPrimeField, FieldPoint> {
@@ -285,6 +291,129 @@ where
     ec_select(chip, ctx, EcPoint::new(zero.clone(), zero), out, is_identity)
 }
 
+/// The slope `num / den` of [`ec_add_complete`] and which case the addition is in, before any inversion.
+///
+/// `den` is replaced by one whenever it is zero, which only happens when the output does not use the slope, so it
+/// is always safe to invert.
+struct CompleteAddSlope<F: BigPrimeField, FP> {
+    num: FP,
+    den: FP,
+    p_is_inf: AssignedValue<F>,
+    q_is_inf: AssignedValue<F>,
+    sum_is_inf: AssignedValue<F>,
+    is_inf: AssignedValue<F>,
+}
+
+fn complete_add_slope<F: BigPrimeField, FC: FieldChip<F>>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    P: &EcPoint<F, FC::FieldPoint>,
+    Q: &EcPoint<F, FC::FieldPoint>,
+) -> CompleteAddSlope<F, FC::FieldPoint>
+where
+    FC: Selectable<F, FC::FieldPoint>,
+{
+    let gate = chip.gate();
+    let [p_is_inf, q_is_inf] = [P, Q].map(|pt| {
+        let x_is_zero = chip.is_zero(ctx, pt.x());
+        let y_is_zero = chip.is_zero(ctx, pt.y());
+        gate.and(ctx, x_is_zero, y_is_zero)
+    });
+    let x_is_eq = chip.is_equal(ctx, P.x(), Q.x());
+    let y_is_eq = chip.is_equal(ctx, P.y(), Q.y());
+    let py_is_zero = chip.is_zero(ctx, P.y());
+
+    // lambda = (y_2 - y_1) / (x_2 - x_1) if x_1 != x_2, otherwise lambda = 3 x_1^2 / (2 y_1)
+    let dx = chip.sub_no_carry(ctx, Q.x(), P.x());
+    let dx = chip.carry_mod(ctx, dx);
+    let dy = chip.sub_no_carry(ctx, Q.y(), P.y());
+    let dy = chip.carry_mod(ctx, dy);
+    let three_x = chip.scalar_mul_no_carry(ctx, P.x(), 3);
+    let three_x_sq = chip.mul(ctx, three_x, P.x());
+    let two_y = chip.scalar_mul_no_carry(ctx, P.y(), 2);
+    let two_y = chip.carry_mod(ctx, two_y);
+    let num = chip.select(ctx, three_x_sq, dy, x_is_eq);
+    let den = chip.select(ctx, two_y, dx, x_is_eq);
+    // `den == 0` only in cases where the output is the point at infinity or one of the inputs, so we divide by one
+    // instead to keep the witness well defined and discard the quotient
+    let den_is_zero = chip.is_zero(ctx, den.clone());
+    let one = chip.load_constant(ctx, FC::FieldType::ONE);
+    let den = chip.select(ctx, one, den, den_is_zero);
+
+    // with both inputs finite, the sum is infinity iff P == -Q, or P == Q has order 2
+    let p_is_fin = gate.not(ctx, p_is_inf);
+    let q_is_fin = gate.not(ctx, q_is_inf);
+    let both_fin = gate.and(ctx, p_is_fin, q_is_fin);
+    let y_is_neq = gate.not(ctx, y_is_eq);
+    let tangent_is_vertical = gate.or(ctx, y_is_neq, py_is_zero);
+    let sum_is_inf = gate.and(ctx, x_is_eq, tangent_is_vertical);
+    let sum_is_inf = gate.and(ctx, both_fin, sum_is_inf);
+    let both_inf = gate.and(ctx, p_is_inf, q_is_inf);
+    let is_inf = gate.or(ctx, sum_is_inf, both_inf);
+
+    CompleteAddSlope { num, den, p_is_inf, q_is_inf, sum_is_inf, is_inf }
+}
+
+// returns `(P + Q, is_infinity)` from the slope `lambda = slope.num / slope.den` and the cases in `slope`
+fn complete_add_from_slope<F: BigPrimeField, FC: FieldChip<F>>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    P: EcPoint<F, FC::FieldPoint>,
+    Q: EcPoint<F, FC::FieldPoint>,
+    lambda: FC::FieldPoint,
+    slope: &CompleteAddSlope<F, FC::FieldPoint>,
+) -> (EcPoint<F, FC::FieldPoint>, AssignedValue<F>)
+where
+    FC: Selectable<F, FC::FieldPoint>,
+{
+    // the same formulas give both P + Q and 2P once lambda is fixed, since x_2 == x_1 when doubling
+    //  x_3 = lambda^2 - x_1 - x_2 (mod p)
+    let lambda_sq = chip.mul_no_carry(ctx, &lambda, &lambda);
+    let lambda_sq_minus_px = chip.sub_no_carry(ctx, lambda_sq, P.x());
+    let x_3_no_carry = chip.sub_no_carry(ctx, lambda_sq_minus_px, Q.x());
+    let x_3 = chip.carry_mod(ctx, x_3_no_carry);
+
+    //  y_3 = lambda (x_1 - x_3) - y_1 mod p
+    let dx_13 = chip.sub_no_carry(ctx, P.x(), &x_3);
+    let lambda_dx_13 = chip.mul_no_carry(ctx, lambda, dx_13);
+    let y_3_no_carry = chip.sub_no_carry(ctx, lambda_dx_13, P.y());
+    let y_3 = chip.carry_mod(ctx, y_3_no_carry);
+
+    let zero = chip.load_constant(ctx, FC::FieldType::ZERO);
+    let out = EcPoint::new(x_3, y_3);
+    let out = ec_select(chip, ctx, P, out, slope.q_is_inf);
+    let out = ec_select(chip, ctx, Q, out, slope.p_is_inf);
+    let out = ec_select(chip, ctx, EcPoint::new(zero.clone(), zero), out, slope.sum_is_inf);
+    (out, slope.is_inf)
+}
+
+/// Complete addition: computes `P + Q` for any inputs, including `P == Q`, `P == -Q`, and either operand being
+/// the point at infinity. As elsewhere in this module, the point at infinity is represented by `(0, 0)`.
+/// For Weierstrass curves `y^2 = x^3 + b` with `b != 0` only, over any field chip (e.g., `Fp2Chip` for G2 of BN254).
+///
+/// Both the addition and the doubling slope are computed, and the right one is chosen by constrained selects,
+/// so the constraints do not depend on which case occurs.
+///
+/// Returns `(P + Q, is_infinity)`, where `P + Q` is `(0, 0)` whenever `is_infinity` is true.
+///
+/// # Assumptions
+/// * `P` and `Q` are each either on the curve or `(0, 0)`
+pub fn ec_add_complete<F: BigPrimeField, FC: FieldChip<F>>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    P: impl Into<EcPoint<F, FC::FieldPoint>>,
+    Q: impl Into<EcPoint<F, FC::FieldPoint>>,
+) -> (EcPoint<F, FC::FieldPoint>, AssignedValue<F>)
+where
+    FC: Selectable<F, FC::FieldPoint>,
+{
+    let P = P.into();
+    let Q = Q.into();
+    let slope = complete_add_slope(chip, ctx, &P, &Q);
+    let lambda = chip.divide_unsafe(ctx, slope.num.clone(), slope.den.clone());
+    complete_add_from_slope(chip, ctx, P, Q, lambda, &slope)
+}
+
 // Implements:
 // computing 2P on elliptic curve E for P = (x, y)
 // formula from https://crypto.stanford.edu/pbc/notes/elliptic/explicit.html
@@ -415,6 +544,24 @@ where
     EcPoint::new(Rx, Ry)
 }
 
+/// Returns `-P` if `flag` is 1 and `P` if `flag` is 0.
+///
+/// Only `y` is selected: `x` is shared by both candidates. As with [`EccChip::negate`], the point at infinity
+/// `(0, 0)` is mapped to itself.
+pub fn ec_conditional_negate<F: BigPrimeField, FC>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    P: EcPoint<F, FC::FieldPoint>,
+    flag: AssignedValue<F>,
+) -> EcPoint<F, FC::FieldPoint>
+where
+    FC: FieldChip<F> + Selectable<F, FC::FieldPoint>,
+{
+    let neg_y = chip.negate(ctx, P.y.clone());
+    let y = chip.select(ctx, neg_y, P.y, flag);
+    EcPoint::new(P.x, y)
+}
+
 // takes the dot product of points with sel, where each is intepreted as
 // a _vector_
 pub fn ec_select_by_indicator<F: BigPrimeField, FC, Pt>(
@@ -586,6 +733,166 @@ where
     */
 }
 
+/// Computes `[scalar] * P` for a scalar given by its witnessed little-endian `bits`, by double-and-add over
+/// the bits from most to least significant using the complete addition [`ec_add_complete`].
+///
+/// Unlike [`scalar_multiply`], there is no random starting point and no assumption on `P` or the scalar: `P` may
+/// be the point at infinity `(0, 0)`, and leading zero bits (including a zero scalar) keep the accumulator at
+/// infinity. This costs two complete additions per bit.
+///
+/// Returns `([scalar] * P, is_infinity)` as in [`ec_add_complete`].
+///
+/// # Assumptions
+/// * every element of `bits` is constrained to be a bit
+/// * `P` is on the curve or `(0, 0)`
+pub fn scalar_multiply_bits<F: BigPrimeField, FC>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    P: EcPoint<F, FC::FieldPoint>,
+    bits: &[AssignedValue<F>],
+) -> (EcPoint<F, FC::FieldPoint>, AssignedValue<F>)
+where
+    FC: FieldChip<F> + Selectable<F, FC::FieldPoint>,
+{
+    let zero = chip.load_constant(ctx, FC::FieldType::ZERO);
+    let mut acc = EcPoint::new(zero.clone(), zero);
+    let mut is_inf = ctx.load_constant(F::ONE);
+    for bit in bits.iter().rev() {
+        let (double, double_is_inf) = ec_add_complete(chip, ctx, &acc, &acc);
+        let (sum, sum_is_inf) = ec_add_complete(chip, ctx, &double, P.clone());
+        acc = ec_select(chip, ctx, sum, double, *bit);
+        is_inf = chip.gate().select(ctx, sum_is_inf, double_is_inf, *bit);
+    }
+    (acc, is_inf)
+}
+
+/// Computes `[scalar] * P` using the width-`window` NAF of `scalar`, whose digits are witnessed and constrained to
+/// recompose to `scalar`.
+///
+/// Any `window` consecutive digits of a width-`window` NAF contain at most one nonzero digit, so the digits are
+/// grouped into aligned blocks of `window` digits, each holding one witnessed digit `±(2j + 1) * 2^k` with
+/// `j < 2^{window - 2}` and `k < window` (or no digit at all). The multiples `(2j + 1) * 2^k * P` are precomputed
+/// once, and each block costs `window` doublings, one selection from the table, an [`ec_conditional_negate`] for a
+/// negative digit, and a single complete addition, versus one addition per bit for [`scalar_multiply_bits`].
+///
+/// All additions are complete, so `P` may be the point at infinity `(0, 0)`. The accumulator starts at infinity,
+/// which handles the leading block and a zero scalar without special cases.
+///
+/// Returns `([scalar] * P, is_infinity)` as in [`ec_add_complete`].
+///
+/// # Assumptions
+/// * `scalar < 2^max_bits`
+/// * `P` is on the curve or `(0, 0)`
+/// * `window >= 2` and `window * (ceil((max_bits + 1) / window) + 1) <= F::CAPACITY`, so that the recomposition
+///   of the digits cannot overflow the native field
+pub fn scalar_multiply_naf<F: BigPrimeField, FC>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    P: EcPoint<F, FC::FieldPoint>,
+    scalar: AssignedValue<F>,
+    max_bits: usize,
+    window: usize,
+) -> (EcPoint<F, FC::FieldPoint>, AssignedValue<F>)
+where
+    FC: FieldChip<F> + Selectable<F, FC::FieldPoint>,
+{
+    assert!(window >= 2);
+    // the width-`window` NAF of a `max_bits`-bit number has at most `max_bits + 1` digits
+    let num_blocks = (max_bits + window) / window;
+    assert!(window * (num_blocks + 1) <= F::CAPACITY as usize);
+    let gate = chip.gate();
+    let num_odd = 1 << (window - 2);
+
+    // table[k * num_odd + j] = (2j + 1) * 2^k * P
+    let (P2, _) = ec_add_complete(chip, ctx, &P, &P);
+    let mut odd = vec![P.clone()];
+    for _ in 1..num_odd {
+        let (next, _) = ec_add_complete(chip, ctx, odd.last().unwrap(), &P2);
+        odd.push(next);
+    }
+    let mut table = odd.clone();
+    for _ in 1..window {
+        odd = odd.into_iter().map(|pt| ec_add_complete(chip, ctx, &pt, &pt).0).collect();
+        table.extend(odd.iter().cloned());
+    }
+
+    let mut wnaf = get_wnaf(fe_to_biguint(scalar.value()).to_u64_digits(), window);
+    assert!(wnaf.len() <= num_blocks * window, "scalar does not fit in max_bits");
+    wnaf.resize(num_blocks * window, 0);
+
+    let zero = chip.load_constant(ctx, FC::FieldType::ZERO);
+    let mut acc = EcPoint::new(zero.clone(), zero);
+    let mut is_inf = ctx.load_constant(F::ONE);
+    let mut recomposed = ctx.load_zero();
+    for (idx, block) in wnaf.chunks(window).enumerate().rev() {
+        let (k, z) =
+            block.iter().enumerate().find(|(_, z)| **z != 0).map_or((0, 0), |(k, z)| (k, *z));
+        let m = z.unsigned_abs() / 2;
+        let [is_nonzero, is_neg] = [z != 0, z < 0].map(|b| {
+            let b = ctx.load_witness(F::from(b as u64));
+            gate.assert_bit(ctx, b);
+            b
+        });
+        let m_bits = (0..window - 2)
+            .map(|i| {
+                let bit = ctx.load_witness(F::from((m >> i) & 1));
+                gate.assert_bit(ctx, bit);
+                bit
+            })
+            .collect_vec();
+        let k = ctx.load_witness(F::from(k as u64));
+        let k_indicator = gate.idx_to_indicator(ctx, k, window);
+        let k_is_valid = gate.sum(ctx, k_indicator.iter().copied());
+        gate.assert_is_const(ctx, &k_is_valid, &F::ONE);
+
+        // digit = is_nonzero * (1 - 2 * is_neg) * (2m + 1) * 2^k
+        let pow2 = gate.inner_product(
+            ctx,
+            k_indicator.iter().copied(),
+            (0..window).map(|i| Constant(F::from(1u64 << i))),
+        );
+        let magnitude = if m_bits.is_empty() {
+            pow2
+        } else {
+            let odd_part = gate.inner_product(
+                ctx,
+                m_bits.iter().copied(),
+                (0..window - 2).map(|i| Constant(F::from(2u64 << i))),
+            );
+            let odd_part = gate.add(ctx, odd_part, Constant(F::ONE));
+            gate.mul(ctx, odd_part, pow2)
+        };
+        let sign = gate.mul_add(ctx, is_neg, Constant(-F::from(2u64)), Constant(F::ONE));
+        let digit = gate.mul(ctx, magnitude, sign);
+        let digit = gate.mul(ctx, digit, is_nonzero);
+        recomposed = gate.mul_add(ctx, recomposed, Constant(F::from(1u64 << window)), digit);
+
+        let entry = if m_bits.is_empty() {
+            ec_select_by_indicator(chip, ctx, &table, &k_indicator)
+        } else {
+            let m_indicator = gate.bits_to_indicator(ctx, &m_bits);
+            let indicator = k_indicator
+                .iter()
+                .flat_map(|k| m_indicator.iter().map(|m| (*k, *m)))
+                .map(|(k, m)| gate.mul(ctx, k, m))
+                .collect_vec();
+            ec_select_by_indicator(chip, ctx, &table, &indicator)
+        };
+        let entry = ec_conditional_negate(chip, ctx, entry, is_neg);
+
+        if idx != num_blocks - 1 {
+            for _ in 0..window {
+                (acc, is_inf) = ec_add_complete(chip, ctx, &acc, &acc);
+            }
+        }
+        let (sum, sum_is_inf) = ec_add_complete(chip, ctx, &acc, entry);
+        acc = ec_select(chip, ctx, sum, acc, is_nonzero);
+        is_inf = gate.select(ctx, sum_is_inf, is_inf, is_nonzero);
+    }
+    ctx.constrain_equal(&recomposed, &scalar);
+    (acc, is_inf)
+}
+
 /// Checks that `P` is indeed a point on the elliptic curve `C`.
 pub fn check_is_on_curve<F, FC, C>(chip: &FC, ctx: &mut Context<F>, P: &EcPoint<F, FC::FieldPoint>)
 where
@@ -762,6 +1069,88 @@ where
     ec_sub_strict(chip, ctx, curr_point, start_point)
 }
 
+/// [`EccChip::variable_base_msm_custom`] uses [`multi_scalar_multiply`] for at most this many points and
+/// [`pippenger::multi_exp_par`] otherwise.
+pub const MSM_MAX_POINTS_WITHOUT_CLUMPS: usize = 25;
+
+/// Multi-scalar multiplication by the bucket method. For each `window_bits`-bit window of the scalars, every point
+/// is added to the bucket of its digit, the buckets are combined with the running-sum trick
+/// `sum_j j * B_j = sum_j (B_j + B_{j+1} + ... + B_{2^window_bits - 1})`, and the windows are combined by doubling.
+///
+/// All additions are done with [`ec_add_complete`], so the points, the buckets and the output may each be the point
+/// at infinity `(0, 0)`. Each window costs `points.len() * (2^window_bits - 1)` selections and complete additions for
+/// the buckets, plus `2 * (2^window_bits - 1)` complete additions for the running sum.
+///
+/// # Assumptions
+/// * `points.len() == scalars.len()` and `points` is nonempty
+/// * `scalars[i].len() == scalars[j].len()` for all `i, j`
+/// * `scalars[i][j] < 2^{max_bits} for all j`
+/// * `points` are all on the curve or the point at infinity `(0, 0)`
+/// * `window_bits >= 1`
+pub fn multi_scalar_multiply_buckets<F: BigPrimeField, FC>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    points: &[EcPoint<F, FC::FieldPoint>],
+    scalars: Vec<Vec<AssignedValue<F>>>,
+    max_bits: usize,
+    window_bits: usize,
+) -> EcPoint<F, FC::FieldPoint>
+where
+    FC: FieldChip<F> + Selectable<F, FC::FieldPoint>,
+{
+    assert!(!points.is_empty());
+    assert_eq!(points.len(), scalars.len());
+    assert!(window_bits >= 1);
+    let gate = chip.gate();
+
+    // little-endian bits of each scalar
+    let mut scalar_bits = Vec::with_capacity(scalars.len());
+    for scalar in scalars {
+        let mut bits = Vec::with_capacity(max_bits * scalar.len());
+        for limb in scalar {
+            bits.append(&mut gate.num_to_bits(ctx, limb, max_bits));
+        }
+        scalar_bits.push(bits);
+    }
+    let total_bits = scalar_bits[0].len();
+    let num_windows = (total_bits + window_bits - 1) / window_bits;
+
+    let zero = chip.load_constant(ctx, FC::FieldType::ZERO);
+    let inf = EcPoint::new(zero.clone(), zero);
+    let mut acc = inf.clone();
+    for w in (0..num_windows).rev() {
+        if w != num_windows - 1 {
+            for _ in 0..window_bits {
+                acc = ec_add_complete(chip, ctx, acc.clone(), acc).0;
+            }
+        }
+        // buckets[j - 1] holds the sum of the points whose digit in this window is j
+        let mut buckets = vec![inf.clone(); (1 << window_bits) - 1];
+        for (point, bits) in points.iter().zip(scalar_bits.iter()) {
+            let window = &bits[w * window_bits..std::cmp::min((w + 1) * window_bits, total_bits)];
+            let digit = gate.inner_product(
+                ctx,
+                window.iter().copied(),
+                (0..window.len()).map(|i| Constant(F::from(1u64 << i))),
+            );
+            let indicator = gate.idx_to_indicator(ctx, digit, 1 << window_bits);
+            for (bucket, is_digit) in buckets.iter_mut().zip(indicator.into_iter().skip(1)) {
+                let addend = ec_select(chip, ctx, point.clone(), inf.clone(), is_digit);
+                *bucket = ec_add_complete(chip, ctx, bucket.clone(), addend).0;
+            }
+        }
+        // running-sum trick, from the top bucket down
+        let mut running = inf.clone();
+        let mut window_sum = inf.clone();
+        for bucket in buckets.into_iter().rev() {
+            running = ec_add_complete(chip, ctx, running, bucket).0;
+            window_sum = ec_add_complete(chip, ctx, window_sum, running.clone()).0;
+        }
+        acc = ec_add_complete(chip, ctx, acc, window_sum).0;
+    }
+    acc
+}
+
 pub fn get_naf(mut exp: Vec<u64>) -> Vec<i8> {
     // https://en.wikipedia.org/wiki/Non-adjacent_form
     // NAF for exp:
@@ -806,6 +1195,35 @@ pub fn get_naf(mut exp: Vec<u64>) -> Vec<i8> {
     naf
 }
 
+/// Returns the width-`w` NAF of `exp` (given in little-endian `u64` limbs), least significant digit first.
+///
+/// Every nonzero digit is odd with absolute value less than `2^{w-1}`, and any `w` consecutive digits contain at
+/// most one nonzero digit, so on average only one in `w + 1` digits is nonzero. The output has no trailing zero
+/// digits and is empty when `exp` is zero. For `w = 2` this is the same expansion as [`get_naf`].
+pub fn get_wnaf(exp: Vec<u64>, w: usize) -> Vec<i64> {
+    assert!((2..=32).contains(&w), "window width must be between 2 and 32");
+    let modulus = 1i64 << w;
+    let mut e = exp.iter().rev().fold(BigUint::zero(), |acc, &limb| (acc << 64) + limb);
+    let mut wnaf = Vec::with_capacity(e.bits() as usize + 1);
+    while !e.is_zero() {
+        let mut z = 0i64;
+        if e.bit(0) {
+            z = (e.iter_u64_digits().next().unwrap() % modulus as u64) as i64;
+            if z >= modulus / 2 {
+                z -= modulus;
+            }
+            if z > 0 {
+                e -= z as u64;
+            } else {
+                e += (-z) as u64;
+            }
+        }
+        wnaf.push(z);
+        e >>= 1;
+    }
+    wnaf
+}
+
 pub type BaseFieldEccChip<'chip, C> = EccChip<
     'chip,
     <C as CurveAffine>::ScalarExt,
@@ -928,6 +1346,10 @@ impl<'chip, F: BigPrimeField, FC: FieldChip<F>> EccChip<'chip, F, FC> {
         self.field_chip.range().gate().or_and(ctx, is_on_curve, x_is_zero, y_is_zero)
     }
 
+    /// Returns `-P = (P.x, -P.y)`, for G1 with `FpChip` and for G2 with `Fp2Chip`.
+    ///
+    /// `FieldChip::negate` maps `0` to `0` and a reduced `y` to a reduced `-y`, so the point at infinity `(0, 0)`
+    /// is its own negation and a point with reduced coordinates stays reduced.
     pub fn negate(
         &self,
         ctx: &mut Context<F>,
@@ -1025,6 +1447,48 @@ where
         ec_select(self.field_chip, ctx, P, Q, condition)
     }
 
+    /// See [`ec_conditional_negate`] for more details.
+    pub fn conditional_negate(
+        &self,
+        ctx: &mut Context<F>,
+        P: EcPoint<F, FC::FieldPoint>,
+        flag: AssignedValue<F>,
+    ) -> EcPoint<F, FC::FieldPoint> {
+        ec_conditional_negate(self.field_chip, ctx, P, flag)
+    }
+
+    /// See [`scalar_multiply_bits`] for more details.
+    pub fn scalar_mult_bits(
+        &self,
+        ctx: &mut Context<F>,
+        P: EcPoint<F, FC::FieldPoint>,
+        bits: &[AssignedValue<F>],
+    ) -> (EcPoint<F, FC::FieldPoint>, AssignedValue<F>) {
+        scalar_multiply_bits(self.field_chip, ctx, P, bits)
+    }
+
+    /// See [`scalar_multiply_naf`] for more details.
+    pub fn scalar_mult_naf(
+        &self,
+        ctx: &mut Context<F>,
+        P: EcPoint<F, FC::FieldPoint>,
+        scalar: AssignedValue<F>,
+        max_bits: usize,
+        window: usize,
+    ) -> (EcPoint<F, FC::FieldPoint>, AssignedValue<F>) {
+        scalar_multiply_naf(self.field_chip, ctx, P, scalar, max_bits, window)
+    }
+
+    /// See [`ec_add_complete`] for more details.
+    pub fn add_complete(
+        &self,
+        ctx: &mut Context<F>,
+        P: impl Into<EcPoint<F, FC::FieldPoint>>,
+        Q: impl Into<EcPoint<F, FC::FieldPoint>>,
+    ) -> (EcPoint<F, FC::FieldPoint>, AssignedValue<F>) {
+        ec_add_complete(self.field_chip, ctx, P, Q)
+    }
+
     /// See [`scalar_multiply`] for more details.
     pub fn scalar_mult<C>(
         &self,
@@ -1057,6 +1521,36 @@ where
         self.variable_base_msm_custom::<C>(thread_pool, P, scalars, max_bits, 4)
     }
 
+    /// Same as [`Self::variable_base_msm`], with the empirical default of `window_bits = 4`.
+    pub fn msm<C>(
+        &self,
+        thread_pool: &mut SinglePhaseCoreManager<F>,
+        P: &[EcPoint<F, FC::FieldPoint>],
+        scalars: Vec<Vec<AssignedValue<F>>>,
+        max_bits: usize,
+    ) -> EcPoint<F, FC::FieldPoint>
+    where
+        C: CurveAffineExt<Base = FC::FieldType>,
+        FC: Selectable<F, FC::ReducedFieldPoint>,
+    {
+        self.variable_base_msm::<C>(thread_pool, P, scalars, max_bits)
+    }
+
+    /// See [`multi_scalar_multiply_buckets`] for more details.
+    pub fn variable_base_msm_with_window(
+        &self,
+        ctx: &mut Context<F>,
+        P: &[EcPoint<F, FC::FieldPoint>],
+        scalars: Vec<Vec<AssignedValue<F>>>,
+        max_bits: usize,
+        window_bits: usize,
+    ) -> EcPoint<F, FC::FieldPoint>
+    where
+        FC: Selectable<F, FC::FieldPoint>,
+    {
+        multi_scalar_multiply_buckets(self.field_chip, ctx, P, scalars, max_bits, window_bits)
+    }
+
     // TODO: add asserts to validate input assumptions described in docs
     pub fn variable_base_msm_custom<C>(
         &self,
@@ -1073,7 +1567,7 @@ where
         #[cfg(feature = "display")]
         println!("computing length {} MSM", P.len());
 
-        if P.len() <= 25 {
+        if P.len() <= MSM_MAX_POINTS_WITHOUT_CLUMPS {
             multi_scalar_multiply::<F, FC, C>(
                 self.field_chip,
                 builder.main(),
@@ -1128,6 +1622,27 @@ impl<'chip, F: BigPrimeField, FC: FieldChip<F>> EccChip<'chip, F, FC> {
         )
     }
 
+    /// See [`fixed_base::scalar_multiply_with_table`] for more details.
+    pub fn fixed_base_scalar_mult_with_table<C>(
+        &self,
+        ctx: &mut Context<F>,
+        table: &FixedBaseTable<C>,
+        scalar: Vec<AssignedValue<F>>,
+        max_bits: usize,
+    ) -> EcPoint<F, FC::FieldPoint>
+    where
+        C: CurveAffineExt,
+        FC: FieldChip<F, FieldType = C::Base> + Selectable<F, FC::FieldPoint>,
+    {
+        fixed_base::scalar_multiply_with_table::<F, _, _>(
+            self.field_chip,
+            ctx,
+            table,
+            scalar,
+            max_bits,
+        )
+    }
+
     // default for most purposes
     pub fn fixed_base_msm<C>(
         &self,
@@ -1191,5 +1706,101 @@ impl<'chip, F: BigPrimeField, FC: FieldChip<F>> EccChip<'chip, F, FC> {
     }
 }
 
+impl<'chip, 'range, F: BigPrimeField, Fp: BigPrimeField> EccChip<'chip, F, FpChip<'range, F, Fp>> {
+    /// Returns the point `(x, y)` on `y^2 = x^3 + b` where `y`, as an integer in `[0, p)`, has parity `y_is_odd`.
+    /// This is the inverse of the usual point compression to `x` and a sign bit.
+    ///
+    /// The circuit is unsatisfiable if `x >= p`, if `x^3 + b` is not a square in `Fp`, or if `y_is_odd = 1` and
+    /// the only square root is `0`.
+    pub fn decompress<C>(
+        &self,
+        ctx: &mut Context<F>,
+        x: ProperCrtUint<F>,
+        y_is_odd: AssignedValue<F>,
+    ) -> EcPoint<F, ProperCrtUint<F>>
+    where
+        C: CurveAffineExt<Base = Fp>,
+    {
+        let chip = self.field_chip;
+        let gate = chip.gate();
+        gate.assert_bit(ctx, y_is_odd);
+        chip.enforce_less_than_p(ctx, x.clone());
+
+        // rhs = x^3 + b
+        let x_sq = chip.mul(ctx, &x, &x);
+        let x_cube = chip.mul_no_carry(ctx, x_sq, &x);
+        let rhs = chip.add_constant_no_carry(ctx, x_cube, C::b());
+        let rhs = chip.carry_mod(ctx, rhs);
+        // sqrt constrains y^2 == rhs when is_square = 1
+        let (is_square, y) = chip.sqrt(ctx, rhs);
+        gate.assert_is_const(ctx, &is_square, &F::ONE);
+
+        // `is_even` constrains y < p, so y and p - y have opposite parity unless y = 0
+        let y_is_even = chip.is_even(ctx, y.clone());
+        let wrong_parity = gate.is_equal(ctx, y_is_even, y_is_odd);
+        let neg_y = chip.negate(ctx, y.clone());
+        let y = chip.select(ctx, neg_y, y, wrong_parity);
+        let y_is_even = chip.is_even(ctx, y.clone());
+        let parity_sum = gate.add(ctx, y_is_even, y_is_odd);
+        gate.assert_is_const(ctx, &parity_sum, &F::ONE);
+
+        EcPoint::new(x, y)
+    }
+
+    /// Computes `P_i + Q_i` for every `(P_i, Q_i)` in `pairs` with the complete addition of [`ec_add_complete`],
+    /// but the slope denominators of all pairs are inverted together with [`FpChip::batch_invert`] (Montgomery's
+    /// trick) instead of one division per pair.
+    ///
+    /// Returns `(P_i + Q_i, is_infinity_i)` for each pair, with the point at infinity represented by `(0, 0)`.
+    ///
+    /// # Assumptions
+    /// * every point is either on the curve or `(0, 0)`
+    pub fn batch_add_pairs(
+        &self,
+        ctx: &mut Context<F>,
+        pairs: &[(EcPoint<F, ProperCrtUint<F>>, EcPoint<F, ProperCrtUint<F>>)],
+    ) -> Vec<(EcPoint<F, ProperCrtUint<F>>, AssignedValue<F>)> {
+        let chip = self.field_chip;
+        // first pass: the slope `num / den` of each pair and which case it is in, as in `ec_add_complete`
+        let slopes =
+            pairs.iter().map(|(P, Q)| complete_add_slope(chip, ctx, P, Q)).collect::<Vec<_>>();
+        let dens = slopes.iter().map(|slope| slope.den.clone()).collect::<Vec<_>>();
+        let den_invs = chip.batch_invert(ctx, &dens);
+
+        // second pass: the sum from each slope, then the exceptional cases
+        pairs
+            .iter()
+            .zip_eq(slopes)
+            .zip_eq(den_invs)
+            .map(|(((P, Q), slope), den_inv)| {
+                let lambda = chip.mul(ctx, &slope.num, den_inv);
+                complete_add_from_slope(chip, ctx, P.clone(), Q.clone(), lambda, &slope)
+            })
+            .collect()
+    }
+
+    /// Returns the sum of `points`, or `(0, 0)` if it is the point at infinity, by adding them pairwise in a binary
+    /// tree with [`Self::batch_add_pairs`], so each of the `ceil(log2(points.len()))` levels uses a single inversion.
+    ///
+    /// # Assumptions
+    /// * `points` is nonempty
+    /// * every point is either on the curve or `(0, 0)`
+    pub fn batch_add(
+        &self,
+        ctx: &mut Context<F>,
+        points: &[EcPoint<F, ProperCrtUint<F>>],
+    ) -> EcPoint<F, ProperCrtUint<F>> {
+        assert!(!points.is_empty(), "points must not be empty");
+        let mut level = points.to_vec();
+        while level.len() > 1 {
+            let carry = if level.len() % 2 == 1 { level.pop() } else { None };
+            let pairs = level.into_iter().tuples().collect::<Vec<_>>();
+            level = self.batch_add_pairs(ctx, &pairs).into_iter().map(|(pt, _)| pt).collect();
+            level.extend(carry);
+        }
+        level.pop().unwrap()
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests;
@@ -1,5 +1,6 @@
 #![allow(unused_assignments, unused_imports, unused_variables)]
 use super::*;
+use crate::ff::PrimeField;
 use crate::fields::fp2::Fp2Chip;
 use crate::group::Group;
 use crate::halo2_proofs::{
@@ -14,7 +15,7 @@ use halo2_base::utils::testing::base_test;
 use halo2_base::utils::value_to_option;
 use halo2_base::SKIP_FIRST_PASS;
 use num_bigint::{BigInt, RandBigInt};
-use rand_core::OsRng;
+use rand_core::{OsRng, RngCore};
 use std::marker::PhantomData;
 use std::ops::Neg;
 
@@ -66,3 +67,313 @@ fn test_ecc() {
         basic_g1_tests(ctx, range, 22, 88, 3, P, Q);
     });
 }
+
+// compress `P` out of circuit to `(x, y_is_odd)`, then decompress in circuit
+fn decompress_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    x: Fq,
+    y_is_odd: bool,
+) -> G1Affine {
+    let fp_chip = FpChip::<Fr, Fq>::new(range, 88, 3);
+    let chip = EccChip::new(&fp_chip);
+    let x = fp_chip.load_private(ctx, x);
+    let y_is_odd = ctx.load_witness(Fr::from(y_is_odd as u64));
+    let P = chip.decompress::<G1Affine>(ctx, x, y_is_odd);
+    let [x, y] = [P.x, P.y].map(|c| fp_chip.get_assigned_value(&c.into()));
+    G1Affine { x, y }
+}
+
+#[test]
+fn test_decompress() {
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        for _ in 0..4 {
+            let P = G1Affine::random(OsRng);
+            let y_is_odd = P.y.is_odd().into();
+            let Q = decompress_test(ctx, range, P.x, y_is_odd);
+            assert_eq!(P, Q);
+            // the other root
+            let Q = decompress_test(ctx, range, P.x, !y_is_odd);
+            assert_eq!(-P, Q);
+        }
+    });
+}
+
+#[test]
+fn test_decompress_not_on_curve() {
+    // find `x` such that `x^3 + 3` is not a square
+    let x = loop {
+        let x = Fq::random(OsRng);
+        if bool::from((x.square() * x + G1Affine::b()).sqrt().is_none()) {
+            break x;
+        }
+    };
+    base_test().k(14).lookup_bits(13).expect_satisfied(false).run(|ctx, range| {
+        decompress_test(ctx, range, x, false);
+    });
+}
+
+// `None` is the point at infinity
+fn add_complete_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    P: Option<G1Affine>,
+    Q: Option<G1Affine>,
+) -> Option<G1Affine> {
+    let fp_chip = FpChip::<Fr, Fq>::new(range, 88, 3);
+    let chip = EccChip::new(&fp_chip);
+    let [P, Q] = [P, Q].map(|pt| {
+        let (x, y) = pt.map(|pt| (pt.x, pt.y)).unwrap_or((Fq::zero(), Fq::zero()));
+        chip.load_private_unchecked(ctx, (x, y))
+    });
+    let (sum, is_inf) = chip.add_complete(ctx, P, Q);
+    let [x, y] = [sum.x, sum.y].map(|c| fp_chip.get_assigned_value(&c.into()));
+    if is_inf.value() == &Fr::one() {
+        assert_eq!((x, y), (Fq::zero(), Fq::zero()));
+        None
+    } else {
+        assert_eq!(is_inf.value(), &Fr::zero());
+        Some(G1Affine { x, y })
+    }
+}
+
+#[test]
+fn test_add_complete() {
+    let P = G1Affine::random(OsRng);
+    let Q = G1Affine::random(OsRng);
+    let cases = [
+        (Some(P), Some(Q), Some(G1Affine::from(P + Q))),
+        (Some(P), Some(P), Some(G1Affine::from(P + P))),
+        (Some(P), Some(-P), None),
+        (Some(P), None, Some(P)),
+        (None, Some(Q), Some(Q)),
+        (None, None, None),
+    ];
+    base_test().k(15).lookup_bits(14).run(|ctx, range| {
+        for (P, Q, expected) in cases {
+            assert_eq!(add_complete_test(ctx, range, P, Q), expected);
+        }
+    });
+}
+
+#[test]
+fn test_negate_add_complete() {
+    let P = G1Affine::random(OsRng);
+    base_test().k(15).lookup_bits(14).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr, Fq>::new(range, 88, 3);
+        let chip = EccChip::new(&fp_chip);
+        let zero = fp_chip.load_constant(ctx, Fq::zero());
+        for P in [chip.load_private_unchecked(ctx, (P.x, P.y)), EcPoint::new(zero.clone(), zero)] {
+            let neg_P = chip.negate(ctx, &P);
+            let (_, is_inf) = chip.add_complete(ctx, P.clone(), neg_P.clone());
+            assert_eq!(is_inf.value(), &Fr::one());
+            // `-P` is in reduced form, so it can be compared against `P` directly
+            let neg_neg_P = chip.negate(ctx, neg_P);
+            chip.assert_equal(ctx, P, neg_neg_P);
+        }
+    });
+}
+
+#[test]
+fn test_conditional_negate() {
+    let P = G1Affine::random(OsRng);
+    base_test().k(15).lookup_bits(14).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr, Fq>::new(range, 88, 3);
+        let chip = EccChip::new(&fp_chip);
+        let zero = fp_chip.load_constant(ctx, Fq::zero());
+        for P in [chip.load_private::<G1Affine>(ctx, (P.x, P.y)), EcPoint::new(zero.clone(), zero)]
+        {
+            let [no, yes] = [false, true].map(|flag| ctx.load_witness(Fr::from(flag as u64)));
+            let same = chip.conditional_negate(ctx, P.clone(), no);
+            chip.assert_equal(ctx, same, P.clone());
+            let neg_P = chip.conditional_negate(ctx, P.clone(), yes);
+            let expected = chip.negate(ctx, P.clone());
+            chip.assert_equal(ctx, neg_P.clone(), expected);
+            // `select` picks the first point when the condition is 1
+            let picked = chip.select(ctx, neg_P, P.clone(), no);
+            chip.assert_equal(ctx, picked, P);
+        }
+        let neg_P = -P;
+        let [x, y] = {
+            let P = chip.load_private::<G1Affine>(ctx, (P.x, P.y));
+            let yes = ctx.load_witness(Fr::one());
+            let neg = chip.conditional_negate(ctx, P, yes);
+            [neg.x, neg.y].map(|c| fp_chip.get_assigned_value(&c.into()))
+        };
+        assert_eq!(G1Affine { x, y }, neg_P);
+    });
+}
+
+fn scalar_mult_bits_test(
+    ctx: &mut Context<Fr>,
+    range: &RangeChip<Fr>,
+    P: G1Affine,
+    scalar: Fr,
+    num_bits: usize,
+) -> Option<G1Affine> {
+    let fp_chip = FpChip::<Fr, Fq>::new(range, 88, 3);
+    let chip = EccChip::new(&fp_chip);
+    let P = chip.load_private_unchecked(ctx, (P.x, P.y));
+    let scalar = ctx.load_witness(scalar);
+    let bits = fp_chip.gate().num_to_bits(ctx, scalar, num_bits);
+    let (out, is_inf) = chip.scalar_mult_bits(ctx, P, &bits);
+    let [x, y] = [out.x, out.y].map(|c| fp_chip.get_assigned_value(&c.into()));
+    if is_inf.value() == &Fr::one() {
+        assert_eq!((x, y), (Fq::zero(), Fq::zero()));
+        None
+    } else {
+        assert_eq!(is_inf.value(), &Fr::zero());
+        Some(G1Affine { x, y })
+    }
+}
+
+#[test]
+fn test_scalar_mult_bits() {
+    let P = G1Affine::random(OsRng);
+    let scalars = [Fr::random(OsRng), Fr::from(5), Fr::one()];
+    base_test().k(19).lookup_bits(18).run(|ctx, range| {
+        for scalar in scalars {
+            // 255 bits: the leading zero bits must leave the accumulator at infinity
+            let out = scalar_mult_bits_test(ctx, range, P, scalar, 255);
+            assert_eq!(out, Some(G1Affine::from(P * scalar)));
+        }
+        assert_eq!(scalar_mult_bits_test(ctx, range, P, Fr::zero(), 255), None);
+        // the largest scalar `r - 1` gives `-P`
+        assert_eq!(scalar_mult_bits_test(ctx, range, P, -Fr::one(), 254), Some(-P));
+    });
+}
+
+#[test]
+fn test_scalar_mult_naf() {
+    let P = G1Affine::random(OsRng);
+    let scalars = [
+        Fr::from_u128(OsRng.next_u64() as u128 | (OsRng.next_u64() as u128) << 64),
+        Fr::from_u128(u128::MAX),
+        Fr::from(7),
+        Fr::one(),
+        Fr::zero(),
+    ];
+    base_test().k(19).lookup_bits(18).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr, Fq>::new(range, 88, 3);
+        let chip = EccChip::new(&fp_chip);
+        let zero = fp_chip.load_constant(ctx, Fq::zero());
+        let inf = EcPoint::new(zero.clone(), zero);
+        let P_assigned = chip.load_private::<G1Affine>(ctx, (P.x, P.y));
+        for (i, scalar) in scalars.into_iter().enumerate() {
+            let window = 2 + i % 4;
+            let scalar_assigned = ctx.load_witness(scalar);
+            let (out, is_inf) =
+                chip.scalar_mult_naf(ctx, P_assigned.clone(), scalar_assigned, 128, window);
+            let bits = fp_chip.gate().num_to_bits(ctx, scalar_assigned, 128);
+            let (expected, expected_is_inf) = chip.scalar_mult_bits(ctx, P_assigned.clone(), &bits);
+            chip.assert_equal(ctx, out.clone(), expected);
+            ctx.constrain_equal(&is_inf, &expected_is_inf);
+            let expected = G1Affine::from(P * scalar);
+            let [x, y] = [out.x, out.y].map(|c| fp_chip.get_assigned_value(&c.into()));
+            if bool::from(expected.is_identity()) {
+                assert_eq!(is_inf.value(), &Fr::one());
+                assert_eq!((x, y), (Fq::zero(), Fq::zero()));
+            } else {
+                assert_eq!(is_inf.value(), &Fr::zero());
+                assert_eq!(G1Affine { x, y }, expected);
+            }
+
+            // the point at infinity is absorbing
+            let (out, is_inf) =
+                chip.scalar_mult_naf(ctx, inf.clone(), scalar_assigned, 128, window);
+            assert_eq!(is_inf.value(), &Fr::one());
+            chip.assert_equal(ctx, out, inf.clone());
+        }
+    });
+}
+
+#[test]
+fn test_batch_add_pairs() {
+    let P = G1Affine::random(OsRng);
+    let Q = G1Affine::random(OsRng);
+    let cases = [
+        (Some(P), Some(Q)),
+        (Some(P), Some(P)),
+        (Some(P), Some(-P)),
+        (None, Some(Q)),
+        (Some(P), None),
+        (None, None),
+    ];
+    base_test().k(19).lookup_bits(18).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr, Fq>::new(range, 88, 3);
+        let chip = EccChip::new(&fp_chip);
+        let load = |ctx: &mut Context<Fr>, pt: Option<G1Affine>| {
+            let (x, y) = pt.map(|pt| (pt.x, pt.y)).unwrap_or((Fq::zero(), Fq::zero()));
+            chip.load_private_unchecked(ctx, (x, y))
+        };
+        let pairs = cases.map(|(P, Q)| (load(ctx, P), load(ctx, Q)));
+        let sums = chip.batch_add_pairs(ctx, &pairs);
+        for ((P, Q), (sum, is_inf)) in cases.into_iter().zip_eq(sums) {
+            let expected = G1Affine::from(
+                P.map(G1::from).unwrap_or_else(G1::identity)
+                    + Q.map(G1::from).unwrap_or_else(G1::identity),
+            );
+            let [x, y] = [sum.x, sum.y].map(|c| fp_chip.get_assigned_value(&c.into()));
+            if bool::from(expected.is_identity()) {
+                assert_eq!(is_inf.value(), &Fr::one());
+                assert_eq!((x, y), (Fq::zero(), Fq::zero()));
+            } else {
+                assert_eq!(is_inf.value(), &Fr::zero());
+                assert_eq!(G1Affine { x, y }, expected);
+            }
+        }
+    });
+}
+
+#[test]
+fn test_batch_add() {
+    let points = (0..7).map(|_| G1Affine::random(OsRng)).collect::<Vec<_>>();
+    base_test().k(19).lookup_bits(18).run(|ctx, range| {
+        let fp_chip = FpChip::<Fr, Fq>::new(range, 88, 3);
+        let chip = EccChip::new(&fp_chip);
+        let assigned = points
+            .iter()
+            .map(|pt| chip.load_private::<G1Affine>(ctx, (pt.x, pt.y)))
+            .collect::<Vec<_>>();
+        let sum = chip.batch_add(ctx, &assigned);
+
+        // compare with repeated naive additions
+        let naive = assigned[1..]
+            .iter()
+            .fold(assigned[0].clone(), |acc, pt| chip.add_unequal(ctx, acc, pt, true));
+        chip.assert_equal(ctx, sum.clone(), naive);
+        let expected =
+            G1Affine::from(points.iter().fold(G1::identity(), |acc, pt| acc + G1::from(*pt)));
+        let [x, y] = [sum.x, sum.y].map(|c| fp_chip.get_assigned_value(&c.into()));
+        assert_eq!(G1Affine { x, y }, expected);
+    });
+}
+
+#[test]
+fn test_get_wnaf() {
+    let mut exps = vec![vec![0], vec![1], vec![u64::MAX], vec![u64::MAX, u64::MAX], vec![5, 0, 0]];
+    exps.extend((0..8).map(|_| (0..4).map(|_| OsRng.next_u64()).collect()));
+    for exp in exps {
+        let value = exp.iter().rev().fold(BigInt::from(0), |acc, &limb| (acc << 64) + limb);
+        for w in 2..=6 {
+            let wnaf = get_wnaf(exp.clone(), w);
+            assert_ne!(wnaf.last(), Some(&0));
+            let recomposed =
+                wnaf.iter().rev().fold(BigInt::from(0), |acc, &z| acc * 2 + BigInt::from(z));
+            assert_eq!(recomposed, value);
+            for (i, &z) in wnaf.iter().enumerate() {
+                if z != 0 {
+                    assert!(z % 2 != 0 && z.unsigned_abs() < 1 << (w - 1));
+                    assert!(wnaf[i + 1..].iter().take(w - 1).all(|&z| z == 0));
+                }
+            }
+            if w == 2 {
+                let mut naf = get_naf(exp.clone());
+                while naf.last() == Some(&0) {
+                    naf.pop();
+                }
+                assert_eq!(wnaf, naf.into_iter().map(i64::from).collect::<Vec<_>>());
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::edwards::{self, TwistedEdwardsCurve};
+use crate::ff::{Field, PrimeField};
+use crate::fields::fp;
+use crate::halo2_proofs::halo2curves::ed25519::{Ed25519Affine, Fq, Fr};
+
+// Fq is the base field 2^255 - 19 and Fr is the scalar field of the prime order subgroup
+pub type FqChip<'range, F> = fp::FpChip<'range, F, Fq>;
+pub type FrChip<'range, F> = fp::FpChip<'range, F, Fr>;
+pub type Ed25519Chip<'chip, F> = edwards::EdwardsChip<'chip, F, FqChip<'chip, F>>;
+
+/// Ed25519 in twisted Edwards form `-x^2 + y^2 = 1 + d x^2 y^2` with `d = -121665 / 121666`.
+impl TwistedEdwardsCurve for Ed25519Affine {
+    type Base = Fq;
+    type Scalar = Fr;
+
+    fn a() -> Fq {
+        -Fq::ONE
+    }
+
+    fn d() -> Fq {
+        -Fq::from(121665) * Fq::from(121666).invert().unwrap()
+    }
+
+    /// The base point of RFC 8032, with `y = 4 / 5` and even `x`.
+    fn generator() -> (Fq, Fq) {
+        let x = Fq::from_str_vartime(
+            "15112221349535400772501151409588531511454012693041857206046113283949847762202",
+        )
+        .unwrap();
+        let y = Fq::from(4) * Fq::from(5).invert().unwrap();
+        (x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,125 @@
+#![allow(non_snake_case)]
+use crate::ff::Field;
+use crate::group::{Curve, Group};
+use halo2_base::{
+    gates::RangeChip,
+    halo2_proofs::halo2curves::{
+        bn256::Fr,
+        ed25519::{Ed25519, Ed25519Affine, Fr as Scalar},
+    },
+    utils::testing::base_test,
+    Context,
+};
+use rand::rngs::StdRng;
+use rand_core::SeedableRng;
+
+use super::{Ed25519Chip, FqChip, FrChip};
+use crate::edwards::{eddsa, TwistedEdwardsCurve};
+use crate::fields::FieldChip;
+
+const LIMB_BITS: usize = 88;
+const NUM_LIMBS: usize = 3;
+
+#[derive(Clone, Copy, Debug)]
+struct EdDSAInput {
+    pubkey: Ed25519Affine,
+    msg_hash: Scalar,
+    sig_R: Ed25519Affine,
+    sig_s: Scalar,
+}
+
+// `msg_hash` stands in for the SHA-512 challenge `H(R || A || M) mod l`, which is computed outside the circuit
+fn random_eddsa_input(rng: &mut StdRng) -> EdDSAInput {
+    let sk = Scalar::random(&mut *rng);
+    let pubkey = (Ed25519::generator() * sk).to_affine();
+    let msg_hash = Scalar::random(&mut *rng);
+    let k = Scalar::random(&mut *rng);
+    let sig_R = (Ed25519::generator() * k).to_affine();
+    let sig_s = k + msg_hash * sk;
+    EdDSAInput { pubkey, msg_hash, sig_R, sig_s }
+}
+
+fn eddsa_test(ctx: &mut Context<Fr>, range: &RangeChip<Fr>, input: EdDSAInput) -> Fr {
+    let fq_chip = FqChip::<Fr>::new(range, LIMB_BITS, NUM_LIMBS);
+    let fr_chip = FrChip::<Fr>::new(range, LIMB_BITS, NUM_LIMBS);
+    let chip = Ed25519Chip::<Fr>::new(&fq_chip);
+
+    let [msg_hash, sig_s] = [input.msg_hash, input.sig_s].map(|x| fr_chip.load_private(ctx, x));
+    let [pubkey, sig_R] =
+        [input.pubkey, input.sig_R].map(|pt| chip.load_private::<Ed25519Affine>(ctx, (pt.x, pt.y)));
+    let res = eddsa::verify::<Fr, _, Scalar, Ed25519Affine>(
+        &chip, ctx, pubkey, msg_hash, sig_R, sig_s, 4,
+    );
+    *res.value()
+}
+
+#[test]
+fn test_ed25519_generator() {
+    let (x, y) = <Ed25519Affine as TwistedEdwardsCurve>::generator();
+    let g = Ed25519::generator().to_affine();
+    assert_eq!((x, y), (g.x, g.y));
+}
+
+#[test]
+fn test_ed25519_add() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let P = Ed25519::random(&mut rng);
+    let Q = Ed25519::random(&mut rng);
+    let cases = [(P, Q), (P, P), (P, -P), (P, Ed25519::identity())];
+    base_test().k(16).lookup_bits(15).run(|ctx, range| {
+        let fq_chip = FqChip::<Fr>::new(range, LIMB_BITS, NUM_LIMBS);
+        let chip = Ed25519Chip::<Fr>::new(&fq_chip);
+        for (P, Q) in cases {
+            let [P_aff, Q_aff] = [P, Q].map(|pt| pt.to_affine());
+            let [P_assigned, Q_assigned] =
+                [P_aff, Q_aff].map(|pt| chip.load_private::<Ed25519Affine>(ctx, (pt.x, pt.y)));
+            let sum = chip.add::<Ed25519Affine>(ctx, &P_assigned, &Q_assigned);
+            let expected = (P + Q).to_affine();
+            assert_eq!(fq_chip.get_assigned_value(&sum.x.into()), expected.x);
+            assert_eq!(fq_chip.get_assigned_value(&sum.y.into()), expected.y);
+        }
+    });
+}
+
+#[test]
+fn test_ed25519_scalar_mult() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let P = Ed25519::random(&mut rng).to_affine();
+    let scalars = [Scalar::random(&mut rng), Scalar::ZERO, Scalar::ONE];
+    base_test().k(18).lookup_bits(17).run(|ctx, range| {
+        let fq_chip = FqChip::<Fr>::new(range, LIMB_BITS, NUM_LIMBS);
+        let fr_chip = FrChip::<Fr>::new(range, LIMB_BITS, NUM_LIMBS);
+        let chip = Ed25519Chip::<Fr>::new(&fq_chip);
+        for scalar in scalars {
+            let P_assigned = chip.load_private::<Ed25519Affine>(ctx, (P.x, P.y));
+            let s = fr_chip.load_private(ctx, scalar);
+            let sm = chip.scalar_mult::<Ed25519Affine>(
+                ctx,
+                P_assigned,
+                s.limbs().to_vec(),
+                LIMB_BITS,
+                4,
+            );
+            let expected = (Ed25519::from(P) * scalar).to_affine();
+            assert_eq!(fq_chip.get_assigned_value(&sm.x.into()), expected.x);
+            assert_eq!(fq_chip.get_assigned_value(&sm.y.into()), expected.y);
+        }
+    });
+}
+
+#[test]
+fn test_ed25519_eddsa() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let input = random_eddsa_input(&mut rng);
+    let res = base_test().k(18).lookup_bits(17).run(|ctx, range| eddsa_test(ctx, range, input));
+    assert_eq!(res, Fr::ONE);
+}
+
+#[test]
+fn test_ed25519_eddsa_wrong_s() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut input = random_eddsa_input(&mut rng);
+    input.sig_s += Scalar::ONE;
+    let res = base_test().k(18).lookup_bits(17).run(|ctx, range| eddsa_test(ctx, range, input));
+    assert_eq!(res, Fr::ZERO);
+}
@@ -0,0 +1,60 @@
+use crate::bigint::ProperCrtUint;
+use crate::ecc::EcPoint;
+use crate::fields::{fp::FpChip, FieldChip};
+use halo2_base::{gates::GateInstructions, utils::BigPrimeField, AssignedValue, Context};
+
+use super::{te_add, te_scalar_multiply, EdwardsChip, TwistedEdwardsCurve};
+
+// CF is the base field of C
+// SF is the scalar field of C, of prime order l
+/// Verifies an EdDSA signature `(sig_R, sig_s)` by checking `[sig_s] B == sig_R + [msg_hash] pubkey`, where `B` is
+/// the generator of `C`. Returns 1 iff the check passes and `sig_s < l`.
+///
+/// This is the cofactorless verification equation. The challenge `msg_hash = H(sig_R || pubkey || M) mod l` (SHA-512
+/// for Ed25519) is computed outside of this function.
+///
+/// # Assumptions
+/// * `pubkey` and `sig_R` are constrained to be on the curve, e.g. loaded with [`EdwardsChip::load_private`]
+/// * `sig_s, msg_hash` are proper CRT integers
+pub fn verify<F: BigPrimeField, CF: BigPrimeField, SF: BigPrimeField, C>(
+    chip: &EdwardsChip<F, FpChip<F, CF>>,
+    ctx: &mut Context<F>,
+    pubkey: EcPoint<F, <FpChip<F, CF> as FieldChip<F>>::FieldPoint>,
+    msg_hash: ProperCrtUint<F>,
+    sig_R: EcPoint<F, <FpChip<F, CF> as FieldChip<F>>::FieldPoint>,
+    sig_s: ProperCrtUint<F>,
+    window_bits: usize,
+) -> AssignedValue<F>
+where
+    C: TwistedEdwardsCurve<Base = CF, Scalar = SF>,
+{
+    let base_chip = chip.field_chip;
+    let scalar_chip =
+        FpChip::<F, SF>::new(base_chip.range, base_chip.limb_bits, base_chip.num_limbs);
+
+    // check s < l
+    let s_valid = scalar_chip.is_less_than_p(ctx, &sig_s);
+
+    // compute s * B and R + h * A
+    let B = chip.assign_constant_point(ctx, C::generator());
+    let s_B = te_scalar_multiply::<F, _, C>(
+        base_chip,
+        ctx,
+        B,
+        sig_s.limbs().to_vec(),
+        base_chip.limb_bits,
+        window_bits,
+    );
+    let h_A = te_scalar_multiply::<F, _, C>(
+        base_chip,
+        ctx,
+        pubkey,
+        msg_hash.limbs().to_vec(),
+        base_chip.limb_bits,
+        window_bits,
+    );
+    let rhs = te_add::<F, _, C>(base_chip, ctx, &sig_R, &h_A);
+
+    let equal_check = chip.is_equal(ctx, s_B, rhs);
+    base_chip.gate().and(ctx, s_valid, equal_check)
+}
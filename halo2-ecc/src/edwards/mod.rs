@@ -0,0 +1,325 @@
+#![allow(non_snake_case)]
+use crate::ecc::{ec_select, ec_select_from_bits, EcPoint};
+use crate::ff::Field;
+use crate::fields::{FieldChip, Selectable};
+use halo2_base::gates::{GateInstructions, RangeInstructions};
+use halo2_base::utils::{modulus, BigPrimeField};
+use halo2_base::{AssignedValue, Context};
+use std::marker::PhantomData;
+
+pub mod eddsa;
+
+/// A twisted Edwards curve `a x^2 + y^2 = 1 + d x^2 y^2` over the field `Self::Base`.
+///
+/// Throughout this module `a` is assumed to be a square and `d` a non-square in `Self::Base`. The addition law
+/// is then complete: the same formula adds any two points on the curve, including equal points and the
+/// identity `(0, 1)`, so none of the exceptional cases of short Weierstrass addition arise.
+pub trait TwistedEdwardsCurve {
+    type Base: BigPrimeField;
+    type Scalar: BigPrimeField;
+
+    fn a() -> Self::Base;
+
+    fn d() -> Self::Base;
+
+    /// The base point of the prime order subgroup, in affine coordinates.
+    fn generator() -> (Self::Base, Self::Base);
+}
+
+/// Computes `P + Q` on the twisted Edwards curve `C` with the complete addition law
+/// ```text
+/// x3 = (x1 y2 + y1 x2) / (1 + d x1 x2 y1 y2)
+/// y3 = (y1 y2 - a x1 x2) / (1 - d x1 x2 y1 y2)
+/// ```
+///
+/// # Assumptions
+/// * `P` and `Q` are on the curve, so both denominators are nonzero
+pub fn te_add<F: BigPrimeField, FC, C>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    P: &EcPoint<F, FC::FieldPoint>,
+    Q: &EcPoint<F, FC::FieldPoint>,
+) -> EcPoint<F, FC::FieldPoint>
+where
+    FC: FieldChip<F>,
+    C: TwistedEdwardsCurve<Base = FC::FieldType>,
+{
+    let x1x2 = chip.mul(ctx, &P.x, &Q.x);
+    let y1y2 = chip.mul(ctx, &P.y, &Q.y);
+    let x1y2 = chip.mul_no_carry(ctx, &P.x, &Q.y);
+    let y1x2 = chip.mul_no_carry(ctx, &P.y, &Q.x);
+
+    // dt = d x1 x2 y1 y2
+    let d = chip.load_constant(ctx, C::d());
+    let t = chip.mul(ctx, &x1x2, &y1y2);
+    let dt = chip.mul(ctx, t, d);
+
+    let x_num = chip.add_no_carry(ctx, x1y2, y1x2);
+    let x_den = chip.add_constant_no_carry(ctx, &dt, FC::FieldType::ONE);
+    let x3 = chip.divide_unsafe(ctx, x_num, x_den);
+
+    let a = chip.load_constant(ctx, C::a());
+    let ax1x2 = chip.mul_no_carry(ctx, x1x2, a);
+    let y_num = chip.sub_no_carry(ctx, y1y2, ax1x2);
+    let one = chip.load_constant(ctx, FC::FieldType::ONE);
+    let y_den = chip.sub_no_carry(ctx, one, dt);
+    let y3 = chip.divide_unsafe(ctx, y_num, y_den);
+
+    EcPoint::new(x3, y3)
+}
+
+/// Constrains `a x^2 + y^2 = 1 + d x^2 y^2` for `P = (x, y)`.
+pub fn te_assert_is_on_curve<F: BigPrimeField, FC, C>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    P: &EcPoint<F, FC::FieldPoint>,
+) where
+    FC: FieldChip<F>,
+    C: TwistedEdwardsCurve<Base = FC::FieldType>,
+{
+    let x_sq = chip.mul(ctx, &P.x, &P.x);
+    let y_sq = chip.mul(ctx, &P.y, &P.y);
+
+    let a = chip.load_constant(ctx, C::a());
+    let ax_sq = chip.mul_no_carry(ctx, &x_sq, a);
+    let lhs = chip.add_no_carry(ctx, ax_sq, &y_sq);
+
+    let d = chip.load_constant(ctx, C::d());
+    let dx_sq = chip.mul(ctx, x_sq, d);
+    let dx_sq_y_sq = chip.mul_no_carry(ctx, dx_sq, y_sq);
+    let rhs = chip.add_constant_no_carry(ctx, dx_sq_y_sq, FC::FieldType::ONE);
+
+    let diff = chip.sub_no_carry(ctx, lhs, rhs);
+    chip.check_carry_mod_to_zero(ctx, diff)
+}
+
+/// Computes `[scalar] * P` on the twisted Edwards curve `C` by windowed double-and-add.
+/// - `scalar` is represented as a reference array of `AssignedValue`s
+/// - `scalar = sum_i scalar_i * 2^{max_bits * i}`
+/// - an array of length > 1 is needed when `scalar` exceeds the modulus of scalar field `F`
+///
+/// Because the addition law is complete, the table of multiples starts from the identity `(0, 1)` and there is
+/// no need for a random starting point or for tracking leading zero windows: `scalar = 0` returns the identity.
+///
+/// # Assumptions
+/// - `window_bits != 0`
+/// - `P` is on the curve
+/// - `scalar_i < 2^{max_bits} for all i`
+/// - `max_bits <= modulus::<F>.bits()`
+pub fn te_scalar_multiply<F: BigPrimeField, FC, C>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+    P: EcPoint<F, FC::FieldPoint>,
+    scalar: Vec<AssignedValue<F>>,
+    max_bits: usize,
+    window_bits: usize,
+) -> EcPoint<F, FC::FieldPoint>
+where
+    FC: FieldChip<F> + Selectable<F, FC::FieldPoint>,
+    C: TwistedEdwardsCurve<Base = FC::FieldType>,
+{
+    assert!(!scalar.is_empty());
+    assert!((max_bits as u64) <= modulus::<F>().bits());
+    assert!(window_bits != 0);
+
+    let total_bits = max_bits * scalar.len();
+    let num_windows = (total_bits + window_bits - 1) / window_bits;
+    let rounded_bitlen = num_windows * window_bits;
+
+    let mut bits = Vec::with_capacity(rounded_bitlen);
+    for x in scalar {
+        let mut new_bits = chip.gate().num_to_bits(ctx, x, max_bits);
+        bits.append(&mut new_bits);
+    }
+    let zero_cell = ctx.load_zero();
+    bits.resize(rounded_bitlen, zero_cell);
+
+    // cached_points[idx] stores idx * P, with cached_points[0] = identity
+    let cache_size = 1usize << window_bits;
+    let mut cached_points = Vec::with_capacity(cache_size);
+    cached_points.push(te_identity(chip, ctx));
+    cached_points.push(P.clone());
+    for idx in 2..cache_size {
+        let new_point = te_add::<F, FC, C>(chip, ctx, &cached_points[idx - 1], &P);
+        cached_points.push(new_point);
+    }
+
+    let mut curr_point = ec_select_from_bits(
+        chip,
+        ctx,
+        &cached_points,
+        &bits[rounded_bitlen - window_bits..rounded_bitlen],
+    );
+    for idx in 1..num_windows {
+        for _ in 0..window_bits {
+            curr_point = te_add::<F, FC, C>(chip, ctx, &curr_point, &curr_point);
+        }
+        let add_point = ec_select_from_bits(
+            chip,
+            ctx,
+            &cached_points,
+            &bits[rounded_bitlen - window_bits * (idx + 1)..rounded_bitlen - window_bits * idx],
+        );
+        curr_point = te_add::<F, FC, C>(chip, ctx, &curr_point, &add_point);
+    }
+    curr_point
+}
+
+/// Returns the identity `(0, 1)` as constants.
+pub fn te_identity<F: BigPrimeField, FC: FieldChip<F>>(
+    chip: &FC,
+    ctx: &mut Context<F>,
+) -> EcPoint<F, FC::FieldPoint> {
+    let zero = chip.load_constant(ctx, FC::FieldType::ZERO);
+    let one = chip.load_constant(ctx, FC::FieldType::ONE);
+    EcPoint::new(zero, one)
+}
+
+/// Chip for twisted Edwards curves, the analogue of [`crate::ecc::EccChip`] for short Weierstrass curves.
+/// Points are represented by their affine coordinates in an [`EcPoint`], with identity `(0, 1)`.
+pub struct EdwardsChip<'chip, F: BigPrimeField, FC: FieldChip<F>> {
+    pub field_chip: &'chip FC,
+    _marker: PhantomData<F>,
+}
+
+impl<'chip, F: BigPrimeField, FC: FieldChip<F>> EdwardsChip<'chip, F, FC> {
+    pub fn new(field_chip: &'chip FC) -> Self {
+        Self { field_chip, _marker: PhantomData }
+    }
+
+    pub fn field_chip(&self) -> &FC {
+        self.field_chip
+    }
+
+    /// Load affine point as private witness. Constrains witness to lie on curve.
+    pub fn load_private<C>(
+        &self,
+        ctx: &mut Context<F>,
+        (x, y): (FC::FieldType, FC::FieldType),
+    ) -> EcPoint<F, FC::FieldPoint>
+    where
+        C: TwistedEdwardsCurve<Base = FC::FieldType>,
+    {
+        let pt = self.load_private_unchecked(ctx, (x, y));
+        self.assert_is_on_curve::<C>(ctx, &pt);
+        pt
+    }
+
+    /// Does not constrain witness to lie on curve
+    pub fn load_private_unchecked(
+        &self,
+        ctx: &mut Context<F>,
+        (x, y): (FC::FieldType, FC::FieldType),
+    ) -> EcPoint<F, FC::FieldPoint> {
+        let x_assigned = self.field_chip.load_private(ctx, x);
+        let y_assigned = self.field_chip.load_private(ctx, y);
+        EcPoint::new(x_assigned, y_assigned)
+    }
+
+    pub fn assign_constant_point(
+        &self,
+        ctx: &mut Context<F>,
+        (x, y): (FC::FieldType, FC::FieldType),
+    ) -> EcPoint<F, FC::FieldPoint> {
+        let x = self.field_chip.load_constant(ctx, x);
+        let y = self.field_chip.load_constant(ctx, y);
+        EcPoint::new(x, y)
+    }
+
+    pub fn load_identity(&self, ctx: &mut Context<F>) -> EcPoint<F, FC::FieldPoint> {
+        te_identity(self.field_chip, ctx)
+    }
+
+    pub fn assert_is_on_curve<C>(&self, ctx: &mut Context<F>, P: &EcPoint<F, FC::FieldPoint>)
+    where
+        C: TwistedEdwardsCurve<Base = FC::FieldType>,
+    {
+        te_assert_is_on_curve::<F, FC, C>(self.field_chip, ctx, P)
+    }
+
+    /// See [`te_add`] for more details.
+    pub fn add<C>(
+        &self,
+        ctx: &mut Context<F>,
+        P: &EcPoint<F, FC::FieldPoint>,
+        Q: &EcPoint<F, FC::FieldPoint>,
+    ) -> EcPoint<F, FC::FieldPoint>
+    where
+        C: TwistedEdwardsCurve<Base = FC::FieldType>,
+    {
+        te_add::<F, FC, C>(self.field_chip, ctx, P, Q)
+    }
+
+    /// Doubling uses the same complete addition law as [`Self::add`].
+    pub fn double<C>(
+        &self,
+        ctx: &mut Context<F>,
+        P: &EcPoint<F, FC::FieldPoint>,
+    ) -> EcPoint<F, FC::FieldPoint>
+    where
+        C: TwistedEdwardsCurve<Base = FC::FieldType>,
+    {
+        te_add::<F, FC, C>(self.field_chip, ctx, P, P)
+    }
+
+    /// The negation of `(x, y)` is `(-x, y)`.
+    pub fn negate(
+        &self,
+        ctx: &mut Context<F>,
+        P: impl Into<EcPoint<F, FC::FieldPoint>>,
+    ) -> EcPoint<F, FC::FieldPoint> {
+        let P = P.into();
+        EcPoint::new(self.field_chip.negate(ctx, P.x), P.y)
+    }
+
+    pub fn is_equal(
+        &self,
+        ctx: &mut Context<F>,
+        P: EcPoint<F, FC::FieldPoint>,
+        Q: EcPoint<F, FC::FieldPoint>,
+    ) -> AssignedValue<F> {
+        let x_is_equal = self.field_chip.is_equal(ctx, P.x, Q.x);
+        let y_is_equal = self.field_chip.is_equal(ctx, P.y, Q.y);
+        self.field_chip.range().gate().and(ctx, x_is_equal, y_is_equal)
+    }
+
+    pub fn assert_equal(
+        &self,
+        ctx: &mut Context<F>,
+        P: EcPoint<F, FC::FieldPoint>,
+        Q: EcPoint<F, FC::FieldPoint>,
+    ) {
+        self.field_chip.assert_equal(ctx, P.x, Q.x);
+        self.field_chip.assert_equal(ctx, P.y, Q.y);
+    }
+}
+
+impl<'chip, F: BigPrimeField, FC: FieldChip<F>> EdwardsChip<'chip, F, FC>
+where
+    FC: Selectable<F, FC::FieldPoint>,
+{
+    pub fn select(
+        &self,
+        ctx: &mut Context<F>,
+        P: EcPoint<F, FC::FieldPoint>,
+        Q: EcPoint<F, FC::FieldPoint>,
+        condition: AssignedValue<F>,
+    ) -> EcPoint<F, FC::FieldPoint> {
+        ec_select(self.field_chip, ctx, P, Q, condition)
+    }
+
+    /// See [`te_scalar_multiply`] for more details.
+    pub fn scalar_mult<C>(
+        &self,
+        ctx: &mut Context<F>,
+        P: EcPoint<F, FC::FieldPoint>,
+        scalar: Vec<AssignedValue<F>>,
+        max_bits: usize,
+        window_bits: usize,
+    ) -> EcPoint<F, FC::FieldPoint>
+    where
+        C: TwistedEdwardsCurve<Base = FC::FieldType>,
+    {
+        te_scalar_multiply::<F, FC, C>(self.field_chip, ctx, P, scalar, max_bits, window_bits)
+    }
+}
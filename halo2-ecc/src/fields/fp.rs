@@ -152,6 +152,27 @@ impl<'range, F: BigPrimeField, Fp: BigPrimeField> FpChip<'range, F, Fp> {
         )
     }
 
+    /// Returns `a * c` without carrying, where `c` is a constant.
+    ///
+    /// Cheaper than [`FieldChip::mul_no_carry`] with a loaded constant because the limbs of `c`
+    /// are folded into the inner products instead of being assigned as cells.
+    pub fn mul_constant_no_carry(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<CRTInteger<F>>,
+        c: Fp,
+    ) -> CRTInteger<F> {
+        let c = FixedCRTInteger::from_native(fe_to_biguint(&c), self.num_limbs, self.limb_bits);
+        mul_no_carry::crt_constant(
+            self.gate(),
+            ctx,
+            a.into(),
+            c,
+            self.limb_bits,
+            self.num_limbs_log2_ceil,
+        )
+    }
+
     // assuming `a` has been range checked to be a proper BigInt
     // constrain the witness `a` to be `< p`
     // then check if `a[0]` is even
@@ -164,6 +185,184 @@ impl<'range, F: BigPrimeField, Fp: BigPrimeField> FpChip<'range, F, Fp> {
         self.enforce_less_than_p(ctx, a.clone());
         big_is_even::positive(self.range(), ctx, a.0.truncation, self.limb_bits)
     }
+
+    /// Returns `(is_square, root)` where `is_square` is 1 iff `a` is a square in `Fp` (`0` counts as a square).
+    /// If `is_square` is 1 then `root^2 == a`, otherwise `root` is 0.
+    ///
+    /// The square root is computed out of circuit. When `a` is not a square, this is proven by
+    /// witnessing a square root of `a * g`, where `g` is the multiplicative generator of `Fp` and
+    /// hence a quadratic non-residue.
+    pub fn sqrt(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<ProperCrtUint<F>>,
+    ) -> (AssignedValue<F>, ProperCrtUint<F>) {
+        let a = a.into();
+        let g = Fp::MULTIPLICATIVE_GENERATOR;
+        let a_val = self.get_assigned_value(&a.clone().into());
+        let (is_square_val, root_val) = match Option::<Fp>::from(a_val.sqrt()) {
+            Some(root) => (true, root),
+            None => (false, Option::from((a_val * g).sqrt()).expect("a * g should be a square")),
+        };
+        let is_square = ctx.load_witness(F::from(is_square_val as u64));
+        self.gate().assert_bit(ctx, is_square);
+        let root = self.load_private(ctx, root_val);
+
+        // constrain root^2 == (is_square ? a : a * g)
+        let root_sq = self.mul(ctx, root.clone(), root.clone());
+        let a_g = self.mul_constant_no_carry(ctx, a.clone(), g);
+        let a_g = self.carry_mod(ctx, a_g);
+        let expected = self.select(ctx, a.clone(), a_g, is_square);
+        self.assert_equal(ctx, root_sq, expected);
+
+        // 0 = 0^2 * g, so we must separately force `is_square = 1` when `a = 0`
+        let a_is_zero = self.is_zero(ctx, a);
+        let not_square = self.gate().not(ctx, is_square);
+        let zero_not_square = self.gate().and(ctx, a_is_zero, not_square);
+        self.gate().assert_is_const(ctx, &zero_not_square, &F::ZERO);
+
+        let zero = self.load_constant(ctx, Fp::ZERO);
+        let root = self.select(ctx, root, zero, is_square);
+        (is_square, root)
+    }
+
+    /// Returns 1 iff `a` is a square in `Fp`, where `0` counts as a square. See [`Self::sqrt`].
+    pub fn is_square(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<ProperCrtUint<F>>,
+    ) -> AssignedValue<F> {
+        self.sqrt(ctx, a).0
+    }
+
+    /// Returns `[x_0^{-1}, ..., x_{n-1}^{-1}]` using Montgomery's trick: one witnessed inverse of the
+    /// product of all `xs` and `3(n - 1)` multiplications, instead of `n` divisions.
+    ///
+    /// Constrains every element of `xs` to be nonzero.
+    pub fn batch_invert(
+        &self,
+        ctx: &mut Context<F>,
+        xs: &[ProperCrtUint<F>],
+    ) -> Vec<ProperCrtUint<F>> {
+        if xs.is_empty() {
+            return vec![];
+        }
+        // prefix[i] = x_0 * ... * x_i
+        let mut prefix = vec![xs[0].clone()];
+        for x in &xs[1..] {
+            let product = self.mul(ctx, prefix.last().unwrap(), x);
+            prefix.push(product);
+        }
+        // constrains `acc * prefix[n - 1] == 1`, so the product, and hence every `x_i`, is nonzero
+        let one = self.load_constant(ctx, Fp::ONE);
+        let mut acc = self.divide_unsafe(ctx, one, prefix.last().unwrap());
+
+        // invariant: acc = (x_0 * ... * x_i)^{-1}
+        let mut inverses = Vec::with_capacity(xs.len());
+        for i in (1..xs.len()).rev() {
+            inverses.push(self.mul(ctx, &acc, &prefix[i - 1]));
+            acc = self.mul(ctx, acc, &xs[i]);
+        }
+        inverses.push(acc);
+        inverses.reverse();
+        inverses
+    }
+
+    /// Number of bytes in the encoding of [`Self::to_bytes_le`], `ceil(Fp::NUM_BITS / 8)`.
+    pub fn num_bytes(&self) -> usize {
+        (Fp::NUM_BITS as usize + 7) / 8
+    }
+
+    /// Returns the little-endian bytes of `a`, each as an `AssignedValue` in `[0, 256)`.
+    ///
+    /// Constrains `a < p`, so the encoding is canonical.
+    pub fn to_bytes_le(&self, ctx: &mut Context<F>, a: &ProperCrtUint<F>) -> Vec<AssignedValue<F>> {
+        self.enforce_less_than_p(ctx, a.clone());
+        let gate = self.gate();
+        let zero = ctx.load_zero();
+        // since `a < p < 2^{8 * num_bytes}`, any bits past `8 * num_bytes` are zero
+        let bits = a
+            .limbs()
+            .iter()
+            .flat_map(|limb| gate.num_to_bits(ctx, *limb, self.limb_bits))
+            .chain(std::iter::repeat(zero))
+            .take(8 * self.num_bytes())
+            .collect::<Vec<_>>();
+        bits.chunks(8)
+            .map(|byte_bits| {
+                let pow_of_two = gate.pow_of_two()[..8].iter().map(|c| Constant(*c));
+                gate.inner_product(ctx, byte_bits.iter().copied(), pow_of_two)
+            })
+            .collect()
+    }
+
+    /// Inverse of [`Self::to_bytes_le`]: constrains each of `bytes` to be in `[0, 256)` and reassembles the limbs.
+    ///
+    /// Constrains the encoded integer to be `< p`.
+    pub fn from_bytes_le(
+        &self,
+        ctx: &mut Context<F>,
+        bytes: &[AssignedValue<F>],
+    ) -> ProperCrtUint<F> {
+        assert_eq!(bytes.len(), self.num_bytes());
+        let gate = self.gate();
+        // num_to_bits constrains each byte to 8 bits
+        let mut bits =
+            bytes.iter().flat_map(|byte| gate.num_to_bits(ctx, *byte, 8)).collect::<Vec<_>>();
+        let max_bits = self.num_limbs * self.limb_bits;
+        for bit in bits.iter().skip(max_bits) {
+            gate.assert_is_const(ctx, bit, &F::ZERO);
+        }
+        bits.truncate(max_bits);
+        let zero = ctx.load_zero();
+        bits.resize(max_bits, zero);
+        let limbs = bits
+            .chunks(self.limb_bits)
+            .map(|limb_bits| {
+                let pow_of_two = gate.pow_of_two()[..self.limb_bits].iter().map(|c| Constant(*c));
+                gate.inner_product(ctx, limb_bits.iter().copied(), pow_of_two)
+            })
+            .collect::<Vec<_>>();
+        let value = BigUint::from_bytes_le(
+            &bytes.iter().map(|byte| byte.value().get_lower_32() as u8).collect::<Vec<_>>(),
+        );
+        let a = ProperUint(limbs).into_crt(ctx, gate, value, &self.limb_bases, self.limb_bits);
+        self.enforce_less_than_p(ctx, a.clone());
+        a
+    }
+
+    /// Returns the `num_limbs` limbs of `a` in little-endian order, e.g. to expose `a` as public cells.
+    ///
+    /// Constrains `a < p`, so every field element has a unique encoding.
+    pub fn to_public(&self, ctx: &mut Context<F>, a: &ProperCrtUint<F>) -> Vec<AssignedValue<F>> {
+        self.enforce_less_than_p(ctx, a.clone());
+        a.limbs().to_vec()
+    }
+
+    /// Inverse of [`Self::to_public`]: range checks each of `limbs` to `limb_bits` bits (fewer for the last limb)
+    /// and reassembles them.
+    ///
+    /// Constrains the encoded integer to be `< p`.
+    pub fn from_public(
+        &self,
+        ctx: &mut Context<F>,
+        limbs: &[AssignedValue<F>],
+    ) -> ProperCrtUint<F> {
+        assert_eq!(limbs.len(), self.num_limbs);
+        let value = limbs.iter().rev().fold(BigUint::from(0u64), |acc, limb| {
+            (acc << self.limb_bits) + fe_to_biguint(limb.value())
+        });
+        let a = ProperUint(limbs.to_vec()).into_crt(
+            ctx,
+            self.gate(),
+            value,
+            &self.limb_bases,
+            self.limb_bits,
+        );
+        self.range_check(ctx, a.clone(), Fp::NUM_BITS as usize);
+        self.enforce_less_than_p(ctx, a.clone());
+        a
+    }
 }
 
 impl<'range, F: BigPrimeField, Fp: BigPrimeField> PrimeFieldChip<F> for FpChip<'range, F, Fp> {
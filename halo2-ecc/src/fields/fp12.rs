@@ -1,13 +1,17 @@
 use std::marker::PhantomData;
 
+use itertools::Itertools;
+
 use crate::ff::PrimeField as _;
-use crate::impl_field_ext_chip_common;
+use crate::{impl_field_ext_chip_common, impl_field_ext_chip_selectable};
 
 use super::{
+    fp6::{mul_by_nonresidue_no_carry, mul_no_carry_fp6},
     vector::{FieldVector, FieldVectorChip},
-    FieldChip, FieldExtConstructor, PrimeFieldChip,
+    FieldChip, FieldExtConstructor, PrimeFieldChip, Selectable,
 };
 
+use crate::bigint::ProperCrtUint;
 use halo2_base::{
     utils::{modulus, BigPrimeField},
     AssignedValue, Context,
@@ -81,15 +85,8 @@ where
         ctx: &mut Context<F>,
         a: FieldVector<FpChip::FieldPoint>,
     ) -> FieldVector<FpChip::FieldPoint> {
-        let a = a.0;
-        assert_eq!(a.len(), 12);
-
-        let coeffs = a
-            .into_iter()
-            .enumerate()
-            .map(|(i, c)| if i % 2 == 0 { c } else { self.fp_chip().negate(ctx, c) })
-            .collect();
-        FieldVector(coeffs)
+        assert_eq!(a.0.len(), 12);
+        self.0.conjugate(ctx, a, 1)
     }
 }
 
@@ -111,6 +108,55 @@ pub fn mul_no_carry_w6<F: BigPrimeField, FC: FieldChip<F>, const XI_0: i64>(
     FieldVector(vec![out0_0_nocarry, out0_1_nocarry])
 }
 
+/// Returns the `Fp2` coefficient `a_{i0} + a_{i1} * u` of `w^i` in `a`, in the layout of [`Fp12Chip`].
+pub fn fp12_coeff<T: Clone>(a: &FieldVector<T>, i: usize) -> FieldVector<T> {
+    assert_eq!(a.0.len(), 12);
+    assert!(i < 6);
+    FieldVector(vec![a[i].clone(), a[i + 6].clone()])
+}
+
+/// Returns the `Fp2` coefficients of `w^0, ..., w^5` in `a`. See [`fp12_coeff`].
+pub fn fp12_coeffs<T: Clone>(a: &FieldVector<T>) -> [FieldVector<T>; 6] {
+    std::array::from_fn(|i| fp12_coeff(a, i))
+}
+
+/// Inverse of [`fp12_coeffs`]: returns `\sum_{i = 0}^5 coeffs[i] * w^i` in the layout of [`Fp12Chip`].
+pub fn fp12_from_coeffs<T>(coeffs: [FieldVector<T>; 6]) -> FieldVector<T> {
+    let (c0, c1): (Vec<_>, Vec<_>) = coeffs
+        .into_iter()
+        .map(|c| {
+            assert_eq!(c.0.len(), 2);
+            let mut c = c.0.into_iter();
+            (c.next().unwrap(), c.next().unwrap())
+        })
+        .unzip();
+    FieldVector([c0, c1].concat())
+}
+
+/// Returns `(a_0, a_1)` in `Fp6` for `a = a_0 + a_1 w`, where `Fp12 = Fp6[w] / (w^2 - v)`.
+///
+/// `w^{2i}` is `v^i`, so `a_0` has the coefficients of `w^0, w^2, w^4` and `a_1` those of `w^1, w^3, w^5`,
+/// both in the layout of [`super::fp6::Fp6Chip`].
+pub fn split_fp6<T>(a: FieldVector<T>) -> (FieldVector<T>, FieldVector<T>) {
+    assert_eq!(a.0.len(), 12);
+    let (mut a0, mut a1) = (Vec::with_capacity(6), Vec::with_capacity(6));
+    for (i, a_i) in a.0.into_iter().enumerate() {
+        if i % 2 == 0 {
+            a0.push(a_i);
+        } else {
+            a1.push(a_i);
+        }
+    }
+    (FieldVector(a0), FieldVector(a1))
+}
+
+/// Inverse of [`split_fp6`]: returns `a_0 + a_1 w` in the layout of [`Fp12Chip`].
+pub fn merge_fp6<T>(a0: FieldVector<T>, a1: FieldVector<T>) -> FieldVector<T> {
+    assert_eq!(a0.0.len(), 6);
+    assert_eq!(a1.0.len(), 6);
+    FieldVector(a0.0.into_iter().interleave(a1.0).collect())
+}
+
 // a lot of this is common to any field extension (lots of for loops), but due to the way rust traits work, it is hard to create a common generic trait that does this. The main problem is that if you had a `FieldExtCommon` trait and wanted to implement `FieldChip` for anything with `FieldExtCommon`, rust will stop you because someone could implement `FieldExtCommon` and `FieldChip` for the same type, causing a conflict.
 // partially solved using macro
 
@@ -137,88 +183,160 @@ where
     }
 
     // w^6 = u + xi for xi = 9
+    // Over `Fp12 = Fp6[w] / (w^2 - v)`:
+    // (a_0 + a_1 w) * (b_0 + b_1 w) = (a_0 b_0 + a_1 b_1 v) + ((a_0 + a_1)(b_0 + b_1) - a_0 b_0 - a_1 b_1) w
+    // Karatsuba saves one of four `Fp6` products. For proper inputs with `k` limbs of `n` bits, the limbs of the
+    // `w^0` coefficient are at most `102 k 2^{2n}` and those of the `w` coefficient at most `252 k 2^{2n}`, since
+    // `a_0 + a_1` and `b_0 + b_1` have `n + 1` bit limbs. That is about `2n + 10` bits, far below `F::NUM_BITS`.
+    // As integers, the `w` coefficient equals `a_0 b_1 + a_1 b_0` exactly, so it is below `84 p^2`. The binding
+    // value is the `w^0` coefficient, at most `102 p^2`: for BN254 with `n = 88, k = 3` that is about `2^{513.9}`,
+    // below the `2^{nk - 1 + F::NUM_BITS - 2} = 2^515` that `carry_mod` accepts.
     fn mul_no_carry(
         &self,
         ctx: &mut Context<F>,
         a: impl Into<Self::UnsafeFieldPoint>,
         b: impl Into<Self::UnsafeFieldPoint>,
     ) -> Self::UnsafeFieldPoint {
-        let a = a.into().0;
-        let b = b.into().0;
-        assert_eq!(a.len(), 12);
-        assert_eq!(b.len(), 12);
+        let a = a.into();
+        let b = b.into();
+        assert_eq!(a.0.len(), 12);
+        assert_eq!(b.0.len(), 12);
 
         let fp_chip = self.fp_chip();
-        // a = \sum_{i = 0}^5 (a_i * w^i + a_{i + 6} * w^i * u)
-        // b = \sum_{i = 0}^5 (b_i * w^i + b_{i + 6} * w^i * u)
-        let mut a0b0_coeffs: Vec<FpChip::UnsafeFieldPoint> = Vec::with_capacity(11);
-        let mut a0b1_coeffs: Vec<FpChip::UnsafeFieldPoint> = Vec::with_capacity(11);
-        let mut a1b0_coeffs: Vec<FpChip::UnsafeFieldPoint> = Vec::with_capacity(11);
-        let mut a1b1_coeffs: Vec<FpChip::UnsafeFieldPoint> = Vec::with_capacity(11);
-        for i in 0..6 {
-            for j in 0..6 {
-                let coeff00 = fp_chip.mul_no_carry(ctx, &a[i], &b[j]);
-                let coeff01 = fp_chip.mul_no_carry(ctx, &a[i], &b[j + 6]);
-                let coeff10 = fp_chip.mul_no_carry(ctx, &a[i + 6], &b[j]);
-                let coeff11 = fp_chip.mul_no_carry(ctx, &a[i + 6], &b[j + 6]);
-                if i + j < a0b0_coeffs.len() {
-                    a0b0_coeffs[i + j] = fp_chip.add_no_carry(ctx, &a0b0_coeffs[i + j], coeff00);
-                    a0b1_coeffs[i + j] = fp_chip.add_no_carry(ctx, &a0b1_coeffs[i + j], coeff01);
-                    a1b0_coeffs[i + j] = fp_chip.add_no_carry(ctx, &a1b0_coeffs[i + j], coeff10);
-                    a1b1_coeffs[i + j] = fp_chip.add_no_carry(ctx, &a1b1_coeffs[i + j], coeff11);
-                } else {
-                    a0b0_coeffs.push(coeff00);
-                    a0b1_coeffs.push(coeff01);
-                    a1b0_coeffs.push(coeff10);
-                    a1b1_coeffs.push(coeff11);
-                }
-            }
-        }
+        let (a0, a1) = split_fp6(a);
+        let (b0, b1) = split_fp6(b);
 
-        let mut a0b0_minus_a1b1 = Vec::with_capacity(11);
-        let mut a0b1_plus_a1b0 = Vec::with_capacity(11);
-        for i in 0..11 {
-            let a0b0_minus_a1b1_entry = fp_chip.sub_no_carry(ctx, &a0b0_coeffs[i], &a1b1_coeffs[i]);
-            let a0b1_plus_a1b0_entry = fp_chip.add_no_carry(ctx, &a0b1_coeffs[i], &a1b0_coeffs[i]);
-
-            a0b0_minus_a1b1.push(a0b0_minus_a1b1_entry);
-            a0b1_plus_a1b0.push(a0b1_plus_a1b0_entry);
-        }
+        let a0b0 = mul_no_carry_fp6::<F, FpChip, XI_0>(fp_chip, ctx, a0.clone(), b0.clone());
+        let a1b1 = mul_no_carry_fp6::<F, FpChip, XI_0>(fp_chip, ctx, a1.clone(), b1.clone());
+        let a0_plus_a1 = self.0.add_no_carry(ctx, a0, a1);
+        let b0_plus_b1 = self.0.add_no_carry(ctx, b0, b1);
+        let cross = mul_no_carry_fp6::<F, FpChip, XI_0>(fp_chip, ctx, a0_plus_a1, b0_plus_b1);
+        let cross = self.0.sub_no_carry(ctx, cross, &a0b0);
+        let c1 = self.0.sub_no_carry(ctx, cross, &a1b1);
 
-        // out_i       = a0b0_minus_a1b1_i + XI_0 * a0b0_minus_a1b1_{i + 6} - a0b1_plus_a1b0_{i + 6}
-        // out_{i + 6} = a0b1_plus_a1b0_{i} + a0b0_minus_a1b1_{i + 6} + XI_0 * a0b1_plus_a1b0_{i + 6}
-        let mut out_coeffs = Vec::with_capacity(12);
-        for i in 0..6 {
-            if i < 5 {
-                let mut coeff = fp_chip.scalar_mul_and_add_no_carry(
-                    ctx,
-                    &a0b0_minus_a1b1[i + 6],
-                    &a0b0_minus_a1b1[i],
-                    XI_0,
-                );
-                coeff = fp_chip.sub_no_carry(ctx, coeff, &a0b1_plus_a1b0[i + 6]);
-                out_coeffs.push(coeff);
-            } else {
-                out_coeffs.push(a0b0_minus_a1b1[i].clone());
-            }
-        }
-        for i in 0..6 {
-            if i < 5 {
-                let mut coeff =
-                    fp_chip.add_no_carry(ctx, &a0b1_plus_a1b0[i], &a0b0_minus_a1b1[i + 6]);
-                coeff =
-                    fp_chip.scalar_mul_and_add_no_carry(ctx, &a0b1_plus_a1b0[i + 6], coeff, XI_0);
-                out_coeffs.push(coeff);
-            } else {
-                out_coeffs.push(a0b1_plus_a1b0[i].clone());
-            }
-        }
-        FieldVector(out_coeffs)
+        let a1b1_v = mul_by_nonresidue_no_carry::<F, FpChip, XI_0>(fp_chip, ctx, a1b1);
+        let c0 = self.0.add_no_carry(ctx, a0b0, a1b1_v);
+        merge_fp6(c0, c1)
     }
 
     impl_field_ext_chip_common!();
 }
 
+impl<'a, F, FpChip, Fp12, const XI_0: i64> Fp12Chip<'a, F, FpChip, Fp12, XI_0>
+where
+    F: BigPrimeField,
+    FpChip: PrimeFieldChip<F>,
+    FpChip::FieldType: BigPrimeField,
+    Fp12: crate::ff::Field + FieldExtConstructor<FpChip::FieldType, 12>,
+    FieldVector<FpChip::UnsafeFieldPoint>: From<FieldVector<FpChip::FieldPoint>>,
+    FieldVector<FpChip::FieldPoint>: From<FieldVector<FpChip::ReducedFieldPoint>>,
+{
+    /// Constrains `a * a_inv == 1` with a single `mul_no_carry` and `check_carry_mod_to_zero`.
+    ///
+    /// `a, a_inv` must be such that `a * a_inv - 1` without carry does not overflow.
+    pub fn assert_is_inverse(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<FieldVector<FpChip::UnsafeFieldPoint>>,
+        a_inv: impl Into<FieldVector<FpChip::UnsafeFieldPoint>>,
+    ) {
+        let prod = self.mul_no_carry(ctx, a, a_inv);
+        let prod_minus_one = self.add_constant_no_carry(ctx, prod, -Fp12::ONE);
+        self.check_carry_mod_to_zero(ctx, prod_minus_one);
+    }
+
+    /// Returns `a^{-1}` for `a` possibly in unreduced form, by witnessing the inverse and
+    /// constraining `a * a^{-1} == 1`. This is cheaper than `divide_unsafe(1, a)`.
+    ///
+    /// If `a` is zero, the witness is zero and the circuit is unsatisfiable.
+    ///
+    /// `a` must be such that `a * a^{-1} - 1` without carry does not overflow.
+    pub fn invert_unsafe(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<FieldVector<FpChip::UnsafeFieldPoint>>,
+    ) -> FieldVector<FpChip::FieldPoint> {
+        let a = a.into();
+        let a_val = self.get_assigned_value(&a);
+        let a_inv_val: Fp12 = Option::from(a_val.invert()).unwrap_or_default();
+        let a_inv = self.load_private(ctx, a_inv_val);
+        self.assert_is_inverse(ctx, a, a_inv.clone());
+        a_inv
+    }
+
+    /// Returns `a^{-1}`. The constraint `a * a^{-1} == 1` also constrains `a` to be nonzero,
+    /// so unlike [`FieldChip::divide`] no separate `is_zero` check is needed.
+    pub fn invert(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<FieldVector<FpChip::FieldPoint>>,
+    ) -> FieldVector<FpChip::FieldPoint> {
+        let a: FieldVector<FpChip::UnsafeFieldPoint> = a.into().into();
+        self.invert_unsafe(ctx, a)
+    }
+}
+
+impl<'a, 'range, F, Fp, Fp12, const XI_0: i64>
+    Fp12Chip<'a, F, super::fp::FpChip<'range, F, Fp>, Fp12, XI_0>
+where
+    F: BigPrimeField,
+    Fp: BigPrimeField,
+    Fp12: crate::ff::Field,
+{
+    /// Returns the concatenated [`super::fp::FpChip::to_bytes_le`] encodings of the 12 coefficients of `a`,
+    /// in the order of [`FieldExtConstructor::coeffs`].
+    pub fn to_bytes_le(
+        &self,
+        ctx: &mut Context<F>,
+        a: &FieldVector<ProperCrtUint<F>>,
+    ) -> Vec<AssignedValue<F>> {
+        assert_eq!(a.0.len(), 12);
+        a.0.iter().flat_map(|coeff| self.fp_chip().to_bytes_le(ctx, coeff)).collect()
+    }
+
+    /// Inverse of [`Self::to_bytes_le`], see [`super::fp::FpChip::from_bytes_le`].
+    pub fn from_bytes_le(
+        &self,
+        ctx: &mut Context<F>,
+        bytes: &[AssignedValue<F>],
+    ) -> FieldVector<ProperCrtUint<F>> {
+        let num_bytes = self.fp_chip().num_bytes();
+        assert_eq!(bytes.len(), 12 * num_bytes);
+        FieldVector(
+            bytes.chunks(num_bytes).map(|coeff| self.fp_chip().from_bytes_le(ctx, coeff)).collect(),
+        )
+    }
+
+    /// Flattens `a` into `12 * num_limbs` cells, e.g. to expose a pairing output as public instances.
+    ///
+    /// The output is the concatenation of [`super::fp::FpChip::to_public`] of the 12 coefficients of `a`, in the
+    /// order of [`FieldExtConstructor::coeffs`]: coefficient `i` occupies cells `i * num_limbs..(i + 1) * num_limbs`,
+    /// with its limbs in little-endian order. Each coefficient is constrained to be `< p`, so the encoding is unique.
+    pub fn to_public(
+        &self,
+        ctx: &mut Context<F>,
+        a: &FieldVector<ProperCrtUint<F>>,
+    ) -> Vec<AssignedValue<F>> {
+        assert_eq!(a.0.len(), 12);
+        a.0.iter().flat_map(|coeff| self.fp_chip().to_public(ctx, coeff)).collect()
+    }
+
+    /// Inverse of [`Self::to_public`], see [`super::fp::FpChip::from_public`].
+    pub fn from_public(
+        &self,
+        ctx: &mut Context<F>,
+        cells: &[AssignedValue<F>],
+    ) -> FieldVector<ProperCrtUint<F>> {
+        let num_limbs = self.fp_chip().num_limbs;
+        assert_eq!(cells.len(), 12 * num_limbs);
+        FieldVector(
+            cells.chunks(num_limbs).map(|coeff| self.fp_chip().from_public(ctx, coeff)).collect(),
+        )
+    }
+}
+
+impl_field_ext_chip_selectable!(Fp12Chip<Fp12, const XI_0: i64>);
+
 mod bn254 {
     use crate::fields::FieldExtConstructor;
     use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq12, Fq2, Fq6};
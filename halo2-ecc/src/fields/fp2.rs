@@ -2,12 +2,14 @@ use std::fmt::Debug;
 use std::marker::PhantomData;
 
 use crate::ff::PrimeField as _;
-use crate::impl_field_ext_chip_common;
+use crate::{impl_field_ext_chip_common, impl_field_ext_chip_selectable};
 
 use super::{
+    fp12::mul_no_carry_w6,
     vector::{FieldVector, FieldVectorChip},
-    BigPrimeField, FieldChip, FieldExtConstructor, PrimeFieldChip,
+    BigPrimeField, FieldChip, FieldExtConstructor, PrimeFieldChip, Selectable,
 };
+use crate::bigint::{CRTInteger, ProperCrtUint};
 use halo2_base::{utils::modulus, AssignedValue, Context};
 use num_bigint::BigUint;
 
@@ -45,11 +47,36 @@ where
         ctx: &mut Context<F>,
         a: FieldVector<FpChip::FieldPoint>,
     ) -> FieldVector<FpChip::FieldPoint> {
-        let mut a = a.0;
-        assert_eq!(a.len(), 2);
+        assert_eq!(a.0.len(), 2);
+        self.0.conjugate(ctx, a, 1)
+    }
+
+    /// Returns `a^{p^power}`, which is [`Self::conjugate`] for odd `power` and `a` itself for even `power`.
+    pub fn frobenius_map(
+        &self,
+        ctx: &mut Context<F>,
+        a: FieldVector<FpChip::FieldPoint>,
+        power: usize,
+    ) -> FieldVector<FpChip::FieldPoint> {
+        if power % 2 == 1 {
+            self.conjugate(ctx, a)
+        } else {
+            a
+        }
+    }
 
-        let neg_a1 = self.fp_chip().negate(ctx, a.pop().unwrap());
-        FieldVector(vec![a.pop().unwrap(), neg_a1])
+    /// Returns `a * (XI_0 + u)`, the multiplication by the nonresidue defining `Fp6 = Fp2[v] / (v^3 - XI_0 - u)`.
+    /// For BN254, `XI_0 = 9` and this agrees with `Fq2::mul_by_nonresidue`.
+    pub fn mul_by_nonresidue<const XI_0: i64>(
+        &self,
+        ctx: &mut Context<F>,
+        a: FieldVector<FpChip::FieldPoint>,
+    ) -> FieldVector<FpChip::FieldPoint> {
+        assert_eq!(a.0.len(), 2);
+        let fp_chip = self.fp_chip();
+        let a = FieldVector(a.0.into_iter().map(Into::into).collect());
+        let out = mul_no_carry_w6::<F, FpChip, XI_0>(fp_chip, ctx, a);
+        FieldVector(out.0.into_iter().map(|x| fp_chip.carry_mod(ctx, x)).collect())
     }
 
     pub fn neg_conjugate(
@@ -65,6 +92,137 @@ where
     }
 }
 
+impl<'a, 'range, F, Fp, Fp2> Fp2Chip<'a, F, super::fp::FpChip<'range, F, Fp>, Fp2>
+where
+    F: BigPrimeField,
+    Fp: BigPrimeField,
+    Fp2: crate::ff::Field + FieldExtConstructor<Fp, 2>,
+{
+    /// Returns `a * c` without carrying, where `c` is a constant in `Fp`.
+    pub fn fp_mul_constant_no_carry(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<FieldVector<CRTInteger<F>>>,
+        c: Fp,
+    ) -> FieldVector<CRTInteger<F>> {
+        let fp_chip = self.fp_chip();
+        FieldVector(
+            a.into().0.into_iter().map(|a| fp_chip.mul_constant_no_carry(ctx, a, c)).collect(),
+        )
+    }
+
+    /// Returns `a * c` without carrying, where `c` is a constant in `Fp2`.
+    /// The coefficients of `c` are never loaded into the circuit.
+    pub fn mul_constant_no_carry(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<FieldVector<CRTInteger<F>>>,
+        c: Fp2,
+    ) -> FieldVector<CRTInteger<F>> {
+        let a = a.into().0;
+        assert_eq!(a.len(), 2);
+        let c = c.coeffs();
+        let fp_chip = self.fp_chip();
+        // (a_0 + a_1 * u) * (c_0 + c_1 * u) = (a_0 c_0 - a_1 c_1) + (a_0 c_1 + a_1 c_0) * u
+        let a0c0 = fp_chip.mul_constant_no_carry(ctx, a[0].clone(), c[0]);
+        let a1c1 = fp_chip.mul_constant_no_carry(ctx, a[1].clone(), c[1]);
+        let a0c1 = fp_chip.mul_constant_no_carry(ctx, a[0].clone(), c[1]);
+        let a1c0 = fp_chip.mul_constant_no_carry(ctx, a[1].clone(), c[0]);
+        let a0c0_minus_a1c1 = fp_chip.sub_no_carry(ctx, a0c0, a1c1);
+        let a0c1_plus_a1c0 = fp_chip.add_no_carry(ctx, a0c1, a1c0);
+
+        FieldVector(vec![a0c0_minus_a1c1, a0c1_plus_a1c0])
+    }
+
+    /// Returns `conj(a) * c` without carrying, where `c` is a constant in `Fp2`. Same as [`Self::conjugate`]
+    /// followed by [`Self::mul_constant_no_carry`], but the negation of `a_1` is folded into the constants, so
+    /// no cells are spent on it.
+    pub fn conjugate_mul_constant_no_carry(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<FieldVector<CRTInteger<F>>>,
+        c: Fp2,
+    ) -> FieldVector<CRTInteger<F>> {
+        let a = a.into().0;
+        assert_eq!(a.len(), 2);
+        let c = c.coeffs();
+        let fp_chip = self.fp_chip();
+        // (a_0 - a_1 * u) * (c_0 + c_1 * u) = (a_0 c_0 + a_1 c_1) + (a_0 c_1 + a_1 (-c_0)) * u
+        let a0c0 = fp_chip.mul_constant_no_carry(ctx, a[0].clone(), c[0]);
+        let a1_neg_c0 = fp_chip.mul_constant_no_carry(ctx, a[1].clone(), -c[0]);
+        if bool::from(c[1].is_zero()) {
+            return FieldVector(vec![a0c0, a1_neg_c0]);
+        }
+        let a1c1 = fp_chip.mul_constant_no_carry(ctx, a[1].clone(), c[1]);
+        let a0c1 = fp_chip.mul_constant_no_carry(ctx, a[0].clone(), c[1]);
+        let a0c0_plus_a1c1 = fp_chip.add_no_carry(ctx, a0c0, a1c1);
+        let a0c1_minus_a1c0 = fp_chip.add_no_carry(ctx, a0c1, a1_neg_c0);
+
+        FieldVector(vec![a0c0_plus_a1c1, a0c1_minus_a1c0])
+    }
+
+    /// Returns the norm `a_0^2 + a_1^2` of `a = a_0 + a_1 * u` down to `Fp`.
+    pub fn norm(
+        &self,
+        ctx: &mut Context<F>,
+        a: &FieldVector<ProperCrtUint<F>>,
+    ) -> ProperCrtUint<F> {
+        assert_eq!(a.0.len(), 2);
+        let fp_chip = self.fp_chip();
+        let a0_sq = fp_chip.mul_no_carry(ctx, &a[0], &a[0]);
+        let a1_sq = fp_chip.mul_no_carry(ctx, &a[1], &a[1]);
+        let norm = fp_chip.add_no_carry(ctx, a0_sq, a1_sq);
+        fp_chip.carry_mod(ctx, norm)
+    }
+
+    /// Returns `a * conj(a)`, which is [`Self::norm`] embedded in `Fp2`.
+    ///
+    /// Costs the two `Fp` squarings of the norm instead of a full `Fp2` multiplication.
+    pub fn mul_by_conjugate(
+        &self,
+        ctx: &mut Context<F>,
+        a: &FieldVector<ProperCrtUint<F>>,
+    ) -> FieldVector<ProperCrtUint<F>> {
+        let norm = self.norm(ctx, a);
+        let zero = self.fp_chip().load_constant(ctx, Fp::ZERO);
+        FieldVector(vec![norm, zero])
+    }
+
+    /// Returns 1 iff `a` is a square in `Fp2`, where `0` counts as a square.
+    ///
+    /// Since `Fp2 / Fp` is a quadratic extension, `a` is a square in `Fp2` iff its norm is a square in `Fp`.
+    pub fn is_square(
+        &self,
+        ctx: &mut Context<F>,
+        a: &FieldVector<ProperCrtUint<F>>,
+    ) -> AssignedValue<F> {
+        let norm = self.norm(ctx, a);
+        self.fp_chip().is_square(ctx, norm)
+    }
+
+    /// Returns `(is_square, root)` where `is_square` is 1 iff `a` is a square in `Fp2` (`0` counts as a square).
+    /// If `is_square` is 1 then `root^2 == a`, otherwise `root` is 0.
+    ///
+    /// `is_square` is constrained by [`Self::is_square`], i.e. by [`super::fp::FpChip::sqrt`] on the norm of `a`.
+    /// The root is computed out of circuit and constrained by `root^2 == (is_square ? a : 0)`.
+    pub fn sqrt(
+        &self,
+        ctx: &mut Context<F>,
+        a: &FieldVector<ProperCrtUint<F>>,
+    ) -> (AssignedValue<F>, FieldVector<ProperCrtUint<F>>) {
+        let is_square = self.is_square(ctx, a);
+        let a_val = self.get_assigned_value(&a.into());
+        let root_val = Option::<Fp2>::from(a_val.sqrt()).unwrap_or(Fp2::ZERO);
+        let root = self.load_private(ctx, root_val);
+
+        let root_sq = self.mul(ctx, &root, &root);
+        let zero = self.load_constant(ctx, Fp2::ZERO);
+        let expected = self.select(ctx, a.clone(), zero, is_square);
+        self.assert_equal(ctx, root_sq, expected);
+        (is_square, root)
+    }
+}
+
 impl<'a, F, FpChip, Fp2> FieldChip<F> for Fp2Chip<'a, F, FpChip, Fp2>
 where
     F: BigPrimeField,
@@ -117,6 +275,8 @@ where
     impl_field_ext_chip_common!();
 }
 
+impl_field_ext_chip_selectable!(Fp2Chip<Fp2>);
+
 mod bn254 {
     use crate::fields::FieldExtConstructor;
     use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq2};
@@ -0,0 +1,236 @@
+use std::marker::PhantomData;
+
+use crate::ff::PrimeField as _;
+use crate::{impl_field_ext_chip_common, impl_field_ext_chip_selectable};
+
+use super::{
+    fp12::mul_no_carry_w6,
+    vector::{FieldVector, FieldVectorChip},
+    FieldChip, FieldExtConstructor, PrimeFieldChip, Selectable,
+};
+
+use halo2_base::{
+    utils::{modulus, BigPrimeField},
+    AssignedValue, Context,
+};
+use num_bigint::BigUint;
+
+/// Represent Fp6 point as FqPoint with degree = 6
+/// `Fp6 = Fp2[v] / (v^3 - u - xi)`
+/// This implementation assumes p = 3 (mod 4) in order for the polynomial u^2 + 1 to
+/// be irreducible over Fp; i.e., in order for -1 to not be a square (quadratic residue) in Fp
+/// This means we store an Fp6 point as `\sum_{i = 0}^2 (a_{i0} + a_{i1} * u) * v^i`
+/// This is encoded in an FqPoint of degree 6 as `(a_{00}, a_{10}, a_{20}, a_{01}, a_{11}, a_{21})`
+///
+/// With `w^2 = v`, `Fp12 = Fp6[w] / (w^2 - v)` and this is the same convention as [`super::fp12::Fp12Chip`].
+#[derive(Clone, Copy, Debug)]
+pub struct Fp6Chip<'a, F: BigPrimeField, FpChip: FieldChip<F>, Fp6, const XI_0: i64>(
+    pub FieldVectorChip<'a, F, FpChip>,
+    PhantomData<Fp6>,
+);
+
+impl<'a, F, FpChip, Fp6, const XI_0: i64> Fp6Chip<'a, F, FpChip, Fp6, XI_0>
+where
+    F: BigPrimeField,
+    FpChip: PrimeFieldChip<F>,
+    FpChip::FieldType: BigPrimeField,
+    Fp6: crate::ff::Field,
+{
+    /// User must construct an `FpChip` first using a config. This is intended so everything shares a single `FlexGateChip`, which is needed for the column allocation to work.
+    pub fn new(fp_chip: &'a FpChip) -> Self {
+        assert_eq!(
+            modulus::<FpChip::FieldType>() % 4usize,
+            BigUint::from(3u64),
+            "p must be 3 (mod 4) for the polynomial u^2 + 1 to be irreducible"
+        );
+        Self(FieldVectorChip::new(fp_chip), PhantomData)
+    }
+
+    pub fn fp_chip(&self) -> &FpChip {
+        self.0.fp_chip
+    }
+
+    /// Returns `a * v` where `v^3 = u + XI_0`.
+    pub fn mul_by_nonresidue(
+        &self,
+        ctx: &mut Context<F>,
+        a: FieldVector<FpChip::FieldPoint>,
+    ) -> FieldVector<FpChip::FieldPoint> {
+        assert_eq!(a.0.len(), 6);
+        let fp_chip = self.fp_chip();
+        // (a_0 + a_1 v + a_2 v^2) * v = (XI_0 + u) a_2 + a_0 v + a_1 v^2
+        let a2 = FieldVector(vec![a[2].clone().into(), a[5].clone().into()]);
+        let xi_a2 = mul_no_carry_w6::<F, FpChip, XI_0>(fp_chip, ctx, a2);
+        let [xi_a2_0, xi_a2_1]: [_; 2] = xi_a2
+            .0
+            .into_iter()
+            .map(|x| fp_chip.carry_mod(ctx, x))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        let a = a.0;
+        FieldVector(vec![xi_a2_0, a[0].clone(), a[1].clone(), xi_a2_1, a[3].clone(), a[4].clone()])
+    }
+}
+
+/// Returns `a * v` without carry, for `a` in `Fp6 = Fp2[v] / (v^3 - u - XI_0)`.
+///
+/// # Assumptions
+/// * `a` is `Fp6` point represented as `FieldVector` with degree = 6, in the layout of [`Fp6Chip`]
+pub fn mul_by_nonresidue_no_carry<F: BigPrimeField, FC: FieldChip<F>, const XI_0: i64>(
+    fp_chip: &FC,
+    ctx: &mut Context<F>,
+    a: FieldVector<FC::UnsafeFieldPoint>,
+) -> FieldVector<FC::UnsafeFieldPoint> {
+    let [a0, a1, a2, a3, a4, a5]: [_; 6] = a.0.try_into().unwrap();
+    // (a_0 + a_1 v + a_2 v^2) * v = (XI_0 + u) a_2 + a_0 v + a_1 v^2
+    let xi_a2 = mul_no_carry_w6::<F, FC, XI_0>(fp_chip, ctx, FieldVector(vec![a2, a5]));
+    let [xi_a2_0, xi_a2_1]: [_; 2] = xi_a2.0.try_into().unwrap();
+    FieldVector(vec![xi_a2_0, a0, a1, xi_a2_1, a3, a4])
+}
+
+/// Returns `a * b` without carry, for `a, b` in `Fp6 = Fp2[v] / (v^3 - u - XI_0)`.
+///
+/// # Assumptions
+/// * `a, b` are `Fp6` points represented as `FieldVector` with degree = 6, in the layout of [`Fp6Chip`]
+pub fn mul_no_carry_fp6<F: BigPrimeField, FC: FieldChip<F>, const XI_0: i64>(
+    fp_chip: &FC,
+    ctx: &mut Context<F>,
+    a: FieldVector<FC::UnsafeFieldPoint>,
+    b: FieldVector<FC::UnsafeFieldPoint>,
+) -> FieldVector<FC::UnsafeFieldPoint> {
+    let a = a.0;
+    let b = b.0;
+    assert_eq!(a.len(), 6);
+    assert_eq!(b.len(), 6);
+
+    // a = \sum_{i = 0}^2 (a_i * v^i + a_{i + 3} * v^i * u)
+    // b = \sum_{i = 0}^2 (b_i * v^i + b_{i + 3} * v^i * u)
+    // a * b as element of Fp2[v] without evaluating v^3 = (XI_0 + u); coefficient `k` of `v^k` is
+    // `(a0b0_minus_a1b1[k], a0b1_plus_a1b0[k])`
+    let mut a0b0_minus_a1b1: Vec<FC::UnsafeFieldPoint> = Vec::with_capacity(5);
+    let mut a0b1_plus_a1b0: Vec<FC::UnsafeFieldPoint> = Vec::with_capacity(5);
+    for i in 0..3 {
+        for j in 0..3 {
+            let coeff00 = fp_chip.mul_no_carry(ctx, &a[i], &b[j]);
+            let coeff11 = fp_chip.mul_no_carry(ctx, &a[i + 3], &b[j + 3]);
+            let coeff01 = fp_chip.mul_no_carry(ctx, &a[i], &b[j + 3]);
+            let coeff10 = fp_chip.mul_no_carry(ctx, &a[i + 3], &b[j]);
+            let re = fp_chip.sub_no_carry(ctx, coeff00, coeff11);
+            let im = fp_chip.add_no_carry(ctx, coeff01, coeff10);
+            if i + j < a0b0_minus_a1b1.len() {
+                a0b0_minus_a1b1[i + j] = fp_chip.add_no_carry(ctx, &a0b0_minus_a1b1[i + j], re);
+                a0b1_plus_a1b0[i + j] = fp_chip.add_no_carry(ctx, &a0b1_plus_a1b0[i + j], im);
+            } else {
+                a0b0_minus_a1b1.push(re);
+                a0b1_plus_a1b0.push(im);
+            }
+        }
+    }
+
+    // out_i       = a0b0_minus_a1b1_i + XI_0 * a0b0_minus_a1b1_{i + 3} - a0b1_plus_a1b0_{i + 3}
+    // out_{i + 3} = a0b1_plus_a1b0_{i} + a0b0_minus_a1b1_{i + 3} + XI_0 * a0b1_plus_a1b0_{i + 3}
+    let mut out_coeffs = Vec::with_capacity(6);
+    for i in 0..3 {
+        if i < 2 {
+            let mut coeff = fp_chip.scalar_mul_and_add_no_carry(
+                ctx,
+                &a0b0_minus_a1b1[i + 3],
+                &a0b0_minus_a1b1[i],
+                XI_0,
+            );
+            coeff = fp_chip.sub_no_carry(ctx, coeff, &a0b1_plus_a1b0[i + 3]);
+            out_coeffs.push(coeff);
+        } else {
+            out_coeffs.push(a0b0_minus_a1b1[i].clone());
+        }
+    }
+    for i in 0..3 {
+        if i < 2 {
+            let mut coeff = fp_chip.add_no_carry(ctx, &a0b1_plus_a1b0[i], &a0b0_minus_a1b1[i + 3]);
+            coeff = fp_chip.scalar_mul_and_add_no_carry(ctx, &a0b1_plus_a1b0[i + 3], coeff, XI_0);
+            out_coeffs.push(coeff);
+        } else {
+            out_coeffs.push(a0b1_plus_a1b0[i].clone());
+        }
+    }
+    FieldVector(out_coeffs)
+}
+
+impl<'a, F, FpChip, Fp6, const XI_0: i64> FieldChip<F> for Fp6Chip<'a, F, FpChip, Fp6, XI_0>
+where
+    F: BigPrimeField,
+    FpChip: PrimeFieldChip<F>,
+    FpChip::FieldType: BigPrimeField,
+    Fp6: crate::ff::Field + FieldExtConstructor<FpChip::FieldType, 6>,
+    FieldVector<FpChip::UnsafeFieldPoint>: From<FieldVector<FpChip::FieldPoint>>,
+    FieldVector<FpChip::FieldPoint>: From<FieldVector<FpChip::ReducedFieldPoint>>,
+{
+    const PRIME_FIELD_NUM_BITS: u32 = FpChip::FieldType::NUM_BITS;
+    type UnsafeFieldPoint = FieldVector<FpChip::UnsafeFieldPoint>;
+    type FieldPoint = FieldVector<FpChip::FieldPoint>;
+    type ReducedFieldPoint = FieldVector<FpChip::ReducedFieldPoint>;
+    type FieldType = Fp6;
+    type RangeChip = FpChip::RangeChip;
+
+    fn get_assigned_value(&self, x: &Self::UnsafeFieldPoint) -> Fp6 {
+        assert_eq!(x.0.len(), 6);
+        let values = x.0.iter().map(|v| self.fp_chip().get_assigned_value(v)).collect::<Vec<_>>();
+        Fp6::new(values.try_into().unwrap())
+    }
+
+    // v^3 = u + xi for xi = 9
+    fn mul_no_carry(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<Self::UnsafeFieldPoint>,
+        b: impl Into<Self::UnsafeFieldPoint>,
+    ) -> Self::UnsafeFieldPoint {
+        mul_no_carry_fp6::<F, FpChip, XI_0>(self.fp_chip(), ctx, a.into(), b.into())
+    }
+
+    impl_field_ext_chip_common!();
+}
+
+impl<'a, F, FpChip, Fp6, const XI_0: i64> Fp6Chip<'a, F, FpChip, Fp6, XI_0>
+where
+    F: BigPrimeField,
+    FpChip: PrimeFieldChip<F>,
+    FpChip::FieldType: BigPrimeField,
+    Fp6: crate::ff::Field + FieldExtConstructor<FpChip::FieldType, 6>,
+    FieldVector<FpChip::UnsafeFieldPoint>: From<FieldVector<FpChip::FieldPoint>>,
+    FieldVector<FpChip::FieldPoint>: From<FieldVector<FpChip::ReducedFieldPoint>>,
+{
+    /// Returns `a^2`.
+    pub fn square(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<FieldVector<FpChip::UnsafeFieldPoint>>,
+    ) -> FieldVector<FpChip::FieldPoint> {
+        let a = a.into();
+        self.mul(ctx, a.clone(), a)
+    }
+}
+
+impl_field_ext_chip_selectable!(Fp6Chip<Fp6, const XI_0: i64>);
+
+mod bn254 {
+    use crate::fields::FieldExtConstructor;
+    use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq2, Fq6};
+    // This means we store an Fp6 point as `\sum_{i = 0}^2 (a_{i0} + a_{i1} * u) * v^i`
+    // This is encoded in an FqPoint of degree 6 as `(a_{00}, a_{10}, a_{20}, a_{01}, a_{11}, a_{21})`
+    impl FieldExtConstructor<Fq, 6> for Fq6 {
+        fn new(c: [Fq; 6]) -> Self {
+            Fq6 {
+                c0: Fq2 { c0: c[0], c1: c[3] },
+                c1: Fq2 { c0: c[1], c1: c[4] },
+                c2: Fq2 { c0: c[2], c1: c[5] },
+            }
+        }
+
+        fn coeffs(&self) -> Vec<Fq> {
+            let x = self;
+            vec![x.c0.c0, x.c1.c0, x.c2.c0, x.c0.c1, x.c1.c1, x.c2.c1]
+        }
+    }
+}
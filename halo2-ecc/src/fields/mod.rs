@@ -11,6 +11,7 @@ use std::fmt::Debug;
 pub mod fp;
 pub mod fp12;
 pub mod fp2;
+pub mod fp6;
 pub mod native_fp;
 pub mod vector;
 
@@ -276,6 +277,102 @@ pub trait FieldChip<F: BigPrimeField>: Clone + Send + Sync {
 
         quot
     }
+
+    /// Returns `(inv, is_zero)` where `is_zero` is 1 iff `a` is zero as a field element, and `inv` is `a^{-1}` if `a != 0`
+    /// and `0` otherwise.
+    ///
+    /// Constrains `a * inv == 1 - is_zero`, so unlike [`Self::divide_unsafe`] this is sound for adversarial `a`.
+    fn invert_or_zero(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl Into<Self::FieldPoint>,
+    ) -> (Self::FieldPoint, AssignedValue<F>)
+    where
+        Self: Selectable<F, Self::FieldPoint>,
+    {
+        let a = a.into();
+        let is_zero = self.is_zero(ctx, a.clone());
+
+        let a_val = self.get_assigned_value(&(&a).into());
+        let inv_val: Self::FieldType = Option::from(a_val.invert()).unwrap_or_default();
+        let inv = self.load_private(ctx, inv_val);
+
+        // constrain a * inv == 1 - is_zero
+        let zero = self.load_constant(ctx, Self::FieldType::ZERO);
+        let one = self.load_constant(ctx, Self::FieldType::ONE);
+        let expected = self.select(ctx, zero.clone(), one, is_zero);
+        let a_inv = self.mul(ctx, &a, &inv);
+        self.assert_equal(ctx, a_inv, expected);
+
+        let inv = self.select(ctx, zero, inv, is_zero);
+        (inv, is_zero)
+    }
+
+    /// Returns `a^e` where `e` is given by its little-endian bits `exp_bits`, using fixed windows of `window_bits` bits.
+    ///
+    /// A table `a^0, ..., a^{2^window_bits - 1}` is computed once, and each window of the exponent selects one entry by indicator.
+    /// Each window therefore costs `window_bits` squarings and one multiplication.
+    ///
+    /// Assumes that every element of `exp_bits` is constrained to be a bit (e.g., it comes from `num_to_bits`).
+    /// If `exp_bits` is empty, returns 1.
+    fn pow_var(
+        &self,
+        ctx: &mut Context<F>,
+        a: &Self::FieldPoint,
+        exp_bits: &[AssignedValue<F>],
+        window_bits: usize,
+    ) -> Self::FieldPoint
+    where
+        Self: Selectable<F, Self::FieldPoint>,
+    {
+        self.pow_var_with_square(ctx, a, exp_bits, window_bits, |ctx, x| self.mul(ctx, x, x))
+    }
+
+    /// Same as [`Self::pow_var`], but squares with `square` instead of [`Self::mul`].
+    ///
+    /// This lets subgroups with a cheaper squaring (e.g., the cyclotomic subgroup of `Fp12`) reuse the windowed loop.
+    /// `square(ctx, x)` must return `x^2` for every `x` it is called on.
+    fn pow_var_with_square(
+        &self,
+        ctx: &mut Context<F>,
+        a: &Self::FieldPoint,
+        exp_bits: &[AssignedValue<F>],
+        window_bits: usize,
+        square: impl Fn(&mut Context<F>, &Self::FieldPoint) -> Self::FieldPoint,
+    ) -> Self::FieldPoint
+    where
+        Self: Selectable<F, Self::FieldPoint>,
+    {
+        assert!(window_bits != 0);
+        let one = self.load_constant(ctx, Self::FieldType::ONE);
+        if exp_bits.is_empty() {
+            return one;
+        }
+        let table_len = 1 << window_bits.min(exp_bits.len());
+        let mut table = Vec::with_capacity(table_len);
+        table.push(one);
+        table.push(a.clone());
+        for i in 2..table_len {
+            let next = self.mul(ctx, &table[i - 1], a);
+            table.push(next);
+        }
+
+        let mut acc: Option<Self::FieldPoint> = None;
+        for window in exp_bits.chunks(window_bits).rev() {
+            let indicator = self.gate().bits_to_indicator(ctx, window);
+            let entry = self.select_by_indicator(ctx, &&table[..1 << window.len()], &indicator);
+            acc = Some(match acc {
+                None => entry,
+                Some(mut acc) => {
+                    for _ in 0..window.len() {
+                        acc = square(ctx, &acc);
+                    }
+                    self.mul(ctx, acc, entry)
+                }
+            });
+        }
+        acc.unwrap()
+    }
 }
 
 pub trait Selectable<F: ScalarField, Pt> {
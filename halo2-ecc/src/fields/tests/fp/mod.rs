@@ -1,12 +1,16 @@
+use crate::bigint::big_less_than;
 use crate::ff::{Field as _, PrimeField as _};
 use crate::fields::fp::FpChip;
 use crate::fields::FieldChip;
 use crate::halo2_proofs::halo2curves::bn256::{Fq, Fr};
 
+use halo2_base::gates::GateInstructions;
 use halo2_base::utils::biguint_to_fe;
 use halo2_base::utils::testing::base_test;
 use halo2_base::utils::{fe_to_biguint, modulus};
 use halo2_base::Context;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
 use rand::rngs::OsRng;
 
 pub mod assert_eq;
@@ -59,6 +63,189 @@ fn test_range_check() {
     });
 }
 
+#[test]
+fn test_sqrt() {
+    fp_chip_test(K, K - 1, 88, 3, |ctx, chip| {
+        let mut sqrt_test = |a: Fq| {
+            let expected: Option<Fq> = a.sqrt().into();
+            let a = chip.load_private(ctx, a);
+            let (is_square, root) = chip.sqrt(ctx, a);
+            assert_eq!(is_square.value(), &Fr::from(expected.is_some() as u64));
+            let root = chip.get_assigned_value(&root.into());
+            match expected {
+                Some(expected) => assert!(root == expected || root == -expected),
+                None => assert_eq!(root, Fq::zero()),
+            }
+        };
+        sqrt_test(Fq::zero());
+        sqrt_test(Fq::one());
+        sqrt_test(-Fq::one());
+        sqrt_test(Fq::MULTIPLICATIVE_GENERATOR);
+        for _ in 0..4 {
+            let a = Fq::random(OsRng);
+            sqrt_test(a);
+            sqrt_test(a.square());
+        }
+    });
+}
+
+#[test]
+fn test_is_square() {
+    fp_chip_test(K, K - 1, 88, 3, |ctx, chip| {
+        let mut is_square_test = |a: Fq| {
+            let expected = bool::from(a.sqrt().is_some());
+            let a = chip.load_private(ctx, a);
+            let is_square = chip.is_square(ctx, a);
+            assert_eq!(is_square.value(), &Fr::from(expected as u64));
+        };
+        is_square_test(Fq::zero());
+        for _ in 0..4 {
+            let a = Fq::random(OsRng);
+            is_square_test(a);
+            is_square_test(a.square());
+        }
+    });
+}
+
+#[test]
+fn test_is_square_matches_legendre() {
+    fp_chip_test(K + 4, K + 3, 88, 3, |ctx, chip| {
+        // little-endian bits of (p - 1) / 2
+        let exp = (modulus::<Fq>() - 1u64) >> 1;
+        let exp_bits = (0..exp.bits())
+            .map(|i| ctx.load_constant(Fr::from(exp.bit(i) as u64)))
+            .collect::<Vec<_>>();
+        let mut legendre_test = |a: Fq| {
+            let expected = bool::from(a.sqrt().is_some());
+            let a = chip.load_private(ctx, a);
+            let is_square = chip.is_square(ctx, a.clone());
+            assert_eq!(is_square.value(), &Fr::from(expected as u64));
+            let legendre = chip.pow_var(ctx, &a, &exp_bits, 4);
+            let legendre = chip.get_assigned_value(&legendre.into());
+            assert!(legendre == Fq::zero() || legendre == Fq::one() || legendre == -Fq::one());
+            assert_eq!(legendre != -Fq::one(), expected);
+        };
+        legendre_test(Fq::zero());
+        for _ in 0..16 {
+            legendre_test(Fq::random(OsRng));
+        }
+    });
+}
+
+#[test]
+fn test_invert_or_zero() {
+    fp_chip_test(K, K - 1, 88, 3, |ctx, chip| {
+        let mut invert_test = |a: Fq| {
+            let a_assigned = chip.load_private(ctx, a);
+            let (inv, is_zero) = chip.invert_or_zero(ctx, a_assigned);
+            assert_eq!(is_zero.value(), &Fr::from(bool::from(a.is_zero()) as u64));
+            let expected = Option::<Fq>::from(a.invert()).unwrap_or(Fq::zero());
+            assert_eq!(chip.get_assigned_value(&inv.into()), expected);
+        };
+        invert_test(Fq::zero());
+        invert_test(Fq::one());
+        invert_test(-Fq::one());
+        for _ in 0..4 {
+            invert_test(Fq::random(OsRng));
+        }
+    });
+}
+
+#[test]
+fn test_batch_invert() {
+    for len in [1, 2, 16] {
+        fp_chip_test(K, K - 1, 88, 3, |ctx, chip| {
+            let xs = (0..len).map(|_| Fq::random(OsRng)).collect::<Vec<_>>();
+            let assigned = xs.iter().map(|x| chip.load_private(ctx, *x)).collect::<Vec<_>>();
+            let inverses = chip.batch_invert(ctx, &assigned);
+            assert_eq!(inverses.len(), len);
+            for (x, inv) in xs.iter().zip(inverses) {
+                assert_eq!(chip.get_assigned_value(&inv.into()), x.invert().unwrap());
+            }
+        });
+    }
+}
+
+#[test]
+fn test_batch_invert_zero() {
+    base_test().k(K as u32).lookup_bits(K - 1).expect_satisfied(false).run(|ctx, range| {
+        let chip = FpChip::<Fr, Fq>::new(range, 88, 3);
+        let xs = [Fq::random(OsRng), Fq::zero(), Fq::random(OsRng)];
+        let xs = xs.map(|x| chip.load_private(ctx, x));
+        chip.batch_invert(ctx, &xs);
+    });
+}
+
+#[test]
+fn test_pow_var() {
+    fp_chip_test(K + 2, K + 1, 88, 3, |ctx, chip| {
+        let a = Fq::random(OsRng);
+        let exp = rand::random::<u64>();
+        let a_assigned = chip.load_private(ctx, a);
+        let exp_assigned = ctx.load_witness(Fr::from(exp));
+        let exp_bits = chip.gate().num_to_bits(ctx, exp_assigned, 64);
+        for window_bits in [1, 3, 4] {
+            let res = chip.pow_var(ctx, &a_assigned, &exp_bits, window_bits);
+            assert_eq!(chip.get_assigned_value(&res.into()), a.pow_vartime([exp]));
+        }
+        let res = chip.pow_var(ctx, &a_assigned, &exp_bits[..2], 4);
+        assert_eq!(chip.get_assigned_value(&res.into()), a.pow_vartime([exp & 3]));
+        let res = chip.pow_var(ctx, &a_assigned, &[], 4);
+        assert_eq!(chip.get_assigned_value(&res.into()), Fq::one());
+    });
+}
+
+#[test]
+fn test_crt_less_than() {
+    fp_chip_test(K + 2, K + 1, 88, 3, |ctx, chip| {
+        let p = modulus::<Fq>();
+        let limb = BigUint::one() << 88;
+        let vals = [
+            BigUint::zero(),
+            BigUint::one(),
+            &limb - 1u32,
+            limb.clone(),
+            &limb + 1u32,
+            &p - 2u32,
+            &p - 1u32,
+            p.clone(),
+            fe_to_biguint(&Fq::random(OsRng)),
+        ];
+        let assigned = vals.clone().map(|v| chip.load_constant_uint(ctx, v));
+        for (a, a_assigned) in vals.iter().zip(&assigned) {
+            for (b, b_assigned) in vals.iter().zip(&assigned) {
+                let lt = big_less_than::crt(
+                    chip.range(),
+                    ctx,
+                    a_assigned,
+                    b_assigned,
+                    chip.limb_bits,
+                    chip.limb_bases[1],
+                );
+                assert_eq!(lt.value(), &Fr::from(a < b), "{a} < {b}");
+            }
+        }
+        // witnessed operands
+        let [x, y] = [Fq::zero(), -Fq::one()].map(|x| chip.load_private(ctx, x));
+        let lt = big_less_than::crt(chip.range(), ctx, &x, &y, chip.limb_bits, chip.limb_bases[1]);
+        assert_eq!(lt.value(), &Fr::one());
+        let lt = big_less_than::crt(chip.range(), ctx, &y, &x, chip.limb_bits, chip.limb_bases[1]);
+        assert_eq!(lt.value(), &Fr::zero());
+    });
+}
+
+#[test]
+fn test_from_bytes_le_not_canonical() {
+    base_test().k(K as u32).lookup_bits(K - 1).expect_satisfied(false).run(|ctx, range| {
+        let chip = FpChip::<Fr, Fq>::new(range, 88, 3);
+        // little-endian bytes of p
+        let mut p_bytes = modulus::<Fq>().to_bytes_le();
+        p_bytes.resize(chip.num_bytes(), 0);
+        let bytes = ctx.assign_witnesses(p_bytes.into_iter().map(|b| Fr::from(b as u64)));
+        chip.from_bytes_le(ctx, &bytes);
+    });
+}
+
 #[cfg(feature = "dev-graph")]
 #[test]
 fn plot_fp() {
@@ -1,9 +1,19 @@
-use crate::ff::Field as _;
+use crate::bigint::{CRTInteger, ProperCrtUint};
+use crate::ff::{Field as _, PrimeField as _};
 use crate::fields::fp::FpChip;
-use crate::fields::fp12::Fp12Chip;
-use crate::fields::FieldChip;
-use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq12};
+use crate::fields::fp12::{
+    fp12_coeff, fp12_coeffs, fp12_from_coeffs, merge_fp6, split_fp6, Fp12Chip,
+};
+use crate::fields::fp2::Fp2Chip;
+use crate::fields::fp6::{mul_by_nonresidue_no_carry, mul_no_carry_fp6};
+use crate::fields::vector::FieldVector;
+use crate::fields::{FieldChip, FieldExtConstructor};
+use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq12, Fq2, Fr};
+use halo2_base::gates::GateInstructions;
 use halo2_base::utils::testing::base_test;
+use halo2_base::utils::ScalarField as _;
+use halo2_base::utils::{decompose_biguint, fe_to_biguint, modulus};
+use num_bigint::BigUint;
 use rand_core::OsRng;
 
 const XI_0: i64 = 9;
@@ -38,3 +48,261 @@ fn test_fp12() {
 
     fp12_mul_test(k, k as usize - 1, 88, 3, a, b);
 }
+
+#[test]
+fn test_fp12_mul_matches_four_products() {
+    let a = Fq12::random(OsRng);
+    let b = Fq12::random(OsRng);
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+        let [a_assigned, b_assigned] = [a, b].map(|x| chip.load_private(ctx, x));
+
+        let start = ctx.advice.len();
+        let karatsuba = chip.mul(ctx, &a_assigned, &b_assigned);
+        let karatsuba_cells = ctx.advice.len() - start;
+
+        // the four `Fp6` product form that `Fp12Chip::mul_no_carry` used before Karatsuba
+        let start = ctx.advice.len();
+        let [a_assigned, b_assigned]: [FieldVector<CRTInteger<Fr>>; 2] =
+            [a_assigned, b_assigned].map(FieldVector::from);
+        let (a0, a1) = split_fp6(a_assigned);
+        let (b0, b1) = split_fp6(b_assigned);
+        let a0b0 = mul_no_carry_fp6::<_, _, XI_0>(&fp_chip, ctx, a0.clone(), b0.clone());
+        let a1b1 = mul_no_carry_fp6::<_, _, XI_0>(&fp_chip, ctx, a1.clone(), b1.clone());
+        let a0b1 = mul_no_carry_fp6::<_, _, XI_0>(&fp_chip, ctx, a0, b1);
+        let a1b0 = mul_no_carry_fp6::<_, _, XI_0>(&fp_chip, ctx, a1, b0);
+        let a1b1_v = mul_by_nonresidue_no_carry::<_, _, XI_0>(&fp_chip, ctx, a1b1);
+        let c0 = chip.0.add_no_carry(ctx, a0b0, a1b1_v);
+        let c1 = chip.0.add_no_carry(ctx, a0b1, a1b0);
+        let four_products = chip.carry_mod(ctx, merge_fp6(c0, c1));
+        let four_products_cells = ctx.advice.len() - start;
+
+        assert_eq!(chip.get_assigned_value(&karatsuba.clone().into()), a * b);
+        assert_eq!(chip.get_assigned_value(&four_products.clone().into()), a * b);
+        chip.assert_equal(ctx, karatsuba, four_products);
+        assert!(
+            karatsuba_cells < four_products_cells,
+            "Karatsuba: {karatsuba_cells} cells, four products: {four_products_cells} cells"
+        );
+    });
+}
+
+#[test]
+fn test_fp12_invert() {
+    for _ in 0..4 {
+        let a = Fq12::random(OsRng);
+        base_test().k(12).lookup_bits(11).run(|ctx, range| {
+            let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+            let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+            let a_assigned = chip.load_private(ctx, a);
+            let a_inv = chip.invert(ctx, a_assigned);
+            assert_eq!(chip.get_assigned_value(&a_inv.into()), a.invert().unwrap());
+        });
+    }
+}
+
+#[test]
+fn test_fp12_invert_bad_witness() {
+    let a = Fq12::random(OsRng);
+    let bad_inv = a.invert().unwrap() + Fq12::ONE;
+    base_test().k(12).lookup_bits(11).expect_satisfied(false).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+        let [a, bad_inv] = [a, bad_inv].map(|x| chip.load_private(ctx, x));
+        chip.assert_is_inverse(ctx, a, bad_inv);
+    });
+    // zero has no inverse, so the zero witness fails `a * a^{-1} == 1`
+    base_test().k(12).lookup_bits(11).expect_satisfied(false).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+        let zero = chip.load_private(ctx, Fq12::ZERO);
+        chip.invert(ctx, zero);
+    });
+}
+
+#[test]
+fn test_fp12_bytes_round_trip() {
+    let a = Fq12::random(OsRng);
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+
+        let a_assigned = chip.load_private(ctx, a);
+        let bytes = chip.to_bytes_le(ctx, &a_assigned);
+        let expected = a.coeffs().into_iter().flat_map(|c| c.to_repr()).collect::<Vec<_>>();
+        assert_eq!(
+            bytes.iter().map(|b| b.value().get_lower_32() as u8).collect::<Vec<_>>(),
+            expected
+        );
+
+        let b = chip.from_bytes_le(ctx, &bytes);
+        for (a_coeff, b_coeff) in a_assigned.into_iter().zip(b) {
+            for (a_limb, b_limb) in a_coeff.limbs().iter().zip(b_coeff.limbs()) {
+                assert_eq!(a_limb.value(), b_limb.value());
+                ctx.constrain_equal(a_limb, b_limb);
+            }
+        }
+    });
+}
+
+#[test]
+fn test_fp12_public_round_trip() {
+    let a = Fq12::random(OsRng);
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+
+        let a_assigned = chip.load_private(ctx, a);
+        let cells = chip.to_public(ctx, &a_assigned);
+        assert_eq!(cells.len(), 12 * 3);
+        // coefficient `i` is in cells `3i..3i + 3`, limbs in little-endian order
+        for (coeff, limbs) in a.coeffs().into_iter().zip(cells.chunks(3)) {
+            let limbs = limbs.iter().map(|l| fe_to_biguint(l.value())).collect::<Vec<_>>();
+            let value = limbs.iter().rev().fold(BigUint::from(0u64), |acc, l| (acc << 88) + l);
+            assert_eq!(value, fe_to_biguint(&coeff));
+        }
+
+        let b = chip.from_public(ctx, &cells);
+        assert_eq!(chip.get_assigned_value(&b.clone().into()), a);
+        chip.assert_equal(ctx, a_assigned, b);
+    });
+}
+
+#[test]
+fn test_fp12_from_public_not_reduced() {
+    base_test().k(14).lookup_bits(13).expect_satisfied(false).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+
+        // the limbs of `p` encode zero, but are not the canonical encoding
+        let p_limbs = decompose_biguint::<Fr>(&modulus::<Fq>(), 3, 88);
+        let mut cells = ctx.assign_witnesses(p_limbs);
+        cells.extend((0..33).map(|_| ctx.load_zero()));
+        chip.from_public(ctx, &cells);
+    });
+}
+
+#[test]
+fn test_fp12_pow_var() {
+    let a = Fq12::random(OsRng);
+    let exp = rand::random::<u16>() as u64;
+    base_test().k(16).lookup_bits(15).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+
+        let a_assigned = chip.load_private(ctx, a);
+        let exp_assigned = ctx.load_witness(Fr::from(exp));
+        let exp_bits = chip.gate().num_to_bits(ctx, exp_assigned, 16);
+        let res = chip.pow_var(ctx, &a_assigned, &exp_bits, 3);
+        assert_eq!(chip.get_assigned_value(&res.into()), a.pow_vartime([exp]));
+    });
+}
+
+#[test]
+fn test_fp12_conjugate_coeffs() {
+    let a = Fq12::random(OsRng);
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+
+        let a_assigned = chip.load_private(ctx, a);
+        let conj = chip.conjugate(ctx, a_assigned.clone());
+        // conjugation is the Frobenius map `a -> a^{p^6}`, i.e. `w -> -w`
+        let mut expected = a;
+        expected.frobenius_map(6);
+        assert_eq!(chip.get_assigned_value(&conj.clone().into()), expected);
+
+        // coefficient `k` is the `u^{k / 6}` part of the `w^{k % 6}` coefficient: it is negated iff `k` is odd
+        let cells = |x: &ProperCrtUint<Fr>| x.limbs().iter().map(|l| l.cell).collect::<Vec<_>>();
+        let coeffs = a.coeffs();
+        for (k, (c, a_c)) in conj.into_iter().zip(a_assigned).enumerate() {
+            if k % 2 == 0 {
+                assert_eq!(cells(&c), cells(&a_c));
+            } else {
+                assert_eq!(fp_chip.get_assigned_value(&c.into()), -coeffs[k]);
+            }
+        }
+    });
+}
+
+#[test]
+fn test_fp12_mul_max_coeffs() {
+    // all coordinates `p - 1`, the largest reduced values, to exercise the overflow bounds of the `Fp6`-based product
+    let a = Fq12::new([-Fq::one(); 12]);
+    let b = Fq12::random(OsRng);
+    fp12_mul_test(12, 11, 88, 3, a, a);
+    fp12_mul_test(12, 11, 88, 3, a, b);
+}
+
+#[test]
+fn test_fp12_coeffs() {
+    let a = Fq12::random(OsRng);
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+        let fp2_chip = Fp2Chip::<_, _, Fq2>::new(&fp_chip);
+
+        let a_assigned = chip.load_private(ctx, a);
+        // `Fp12 = Fp6[w] / (w^2 - v)`, so `w^i` is `v^{i / 2} w^{i % 2}`
+        let expected = [a.c0.c0, a.c1.c0, a.c0.c1, a.c1.c1, a.c0.c2, a.c1.c2];
+        let coeffs = fp12_coeffs(&a_assigned);
+        for (i, (coeff, expected)) in coeffs.iter().zip(expected).enumerate() {
+            assert_eq!(fp2_chip.get_assigned_value(&coeff.clone().into()), expected);
+            let coeff_i = fp12_coeff(&a_assigned, i);
+            assert_eq!(fp2_chip.get_assigned_value(&coeff_i.into()), expected);
+        }
+
+        // round trip reuses the same cells
+        let cells = |x: &ProperCrtUint<Fr>| x.limbs().iter().map(|l| l.cell).collect::<Vec<_>>();
+        let b = fp12_from_coeffs(coeffs);
+        for (a_c, b_c) in a_assigned.into_iter().zip(b) {
+            assert_eq!(cells(&a_c), cells(&b_c));
+        }
+    });
+}
+
+// returns `a` with 1 added to the coefficient at `idx`, which only changes the lowest limb of that coefficient
+// unless it carries
+fn fp12_bump_coeff(a: Fq12, idx: usize) -> Fq12 {
+    let mut coeffs: [Fq; 12] = a.coeffs().try_into().unwrap();
+    coeffs[idx] += Fq::one();
+    Fq12::new(coeffs)
+}
+
+#[test]
+fn test_fp12_is_equal() {
+    let a = Fq12::random(OsRng);
+    base_test().k(14).lookup_bits(13).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+
+        let a_assigned = chip.load_private(ctx, a);
+        let a_again = chip.load_private(ctx, a);
+        let is_equal = chip.is_equal(ctx, a_assigned.clone(), a_again.clone());
+        assert_eq!(is_equal.value(), &Fr::one());
+        chip.assert_equal(ctx, a_assigned.clone(), a_again);
+
+        for idx in [0, 5, 6, 11] {
+            let b = chip.load_private(ctx, fp12_bump_coeff(a, idx));
+            let is_equal = chip.is_equal(ctx, a_assigned.clone(), b);
+            assert_eq!(is_equal.value(), &Fr::zero());
+        }
+        let b = chip.load_private(ctx, Fq12::random(OsRng));
+        let is_equal = chip.is_equal(ctx, a_assigned, b);
+        assert_eq!(is_equal.value(), &Fr::zero());
+    });
+}
+
+#[test]
+fn test_fp12_assert_equal_fails() {
+    let a = Fq12::random(OsRng);
+    base_test().k(12).lookup_bits(11).expect_satisfied(false).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp12Chip::<_, _, Fq12, XI_0>::new(&fp_chip);
+
+        let a_assigned = chip.load_private(ctx, a);
+        let b = chip.load_private(ctx, fp12_bump_coeff(a, 7));
+        chip.assert_equal(ctx, a_assigned, b);
+    });
+}
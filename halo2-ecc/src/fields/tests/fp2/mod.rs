@@ -0,0 +1,159 @@
+use crate::bigint::ProperCrtUint;
+use crate::ff::Field as _;
+use crate::fields::fp::FpChip;
+use crate::fields::fp2::Fp2Chip;
+use crate::fields::FieldChip;
+use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq2, Fr};
+use halo2_base::utils::testing::base_test;
+use rand_core::OsRng;
+
+#[test]
+fn test_fp2_is_square() {
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp2Chip::<_, _, Fq2>::new(&fp_chip);
+
+        let mut is_square_test = |a: Fq2| {
+            let expected = bool::from(a.sqrt().is_some());
+            let a = chip.load_private(ctx, a);
+            let is_square = chip.is_square(ctx, &a);
+            assert_eq!(is_square.value(), &Fr::from(expected as u64));
+        };
+        is_square_test(Fq2::zero());
+        is_square_test(Fq2::one());
+        for _ in 0..4 {
+            let a = Fq2::random(OsRng);
+            is_square_test(a);
+            is_square_test(a.square());
+        }
+    });
+}
+
+#[test]
+fn test_fp2_sqrt() {
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp2Chip::<_, _, Fq2>::new(&fp_chip);
+
+        let mut sqrt_test = |a: Fq2| {
+            let expected: Option<Fq2> = a.sqrt().into();
+            let a = chip.load_private(ctx, a);
+            let (is_square, root) = chip.sqrt(ctx, &a);
+            assert_eq!(is_square.value(), &Fr::from(expected.is_some() as u64));
+            let root = chip.get_assigned_value(&root.into());
+            match expected {
+                Some(expected) => assert!(root == expected || root == -expected),
+                None => assert_eq!(root, Fq2::zero()),
+            }
+        };
+        sqrt_test(Fq2::zero());
+        sqrt_test(Fq2::one());
+        // purely imaginary
+        sqrt_test(Fq2 { c0: Fq::zero(), c1: Fq::one() });
+        for _ in 0..4 {
+            let a = Fq2::random(OsRng);
+            sqrt_test(a);
+            sqrt_test(a.square());
+        }
+    });
+}
+
+#[test]
+fn test_fp2_frobenius_map() {
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp2Chip::<_, _, Fq2>::new(&fp_chip);
+
+        let a = Fq2::random(OsRng);
+        let a_assigned = chip.load_private(ctx, a);
+        let conjugate = chip.conjugate(ctx, a_assigned.clone());
+        for power in 0..4 {
+            let frob = chip.frobenius_map(ctx, a_assigned.clone(), power);
+            let mut expected = a;
+            expected.frobenius_map(power);
+            assert_eq!(chip.get_assigned_value(&frob.clone().into()), expected);
+            match power % 2 {
+                1 => chip.assert_equal(ctx, frob, conjugate.clone()),
+                _ => chip.assert_equal(ctx, frob, a_assigned.clone()),
+            }
+        }
+    });
+}
+
+#[test]
+fn test_fp2_conjugate_coeffs() {
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp2Chip::<_, _, Fq2>::new(&fp_chip);
+
+        let a = Fq2::random(OsRng);
+        let a_assigned = chip.load_private(ctx, a);
+        let conj = chip.conjugate(ctx, a_assigned.clone());
+        assert_eq!(chip.get_assigned_value(&conj.clone().into()), Fq2 { c0: a.c0, c1: -a.c1 });
+        // `a_0` is passed through untouched and `a_1` is negated
+        let cells = |x: &ProperCrtUint<Fr>| x.limbs().iter().map(|l| l.cell).collect::<Vec<_>>();
+        assert_eq!(cells(&conj[0]), cells(&a_assigned[0]));
+        assert_eq!(fp_chip.get_assigned_value(&conj[1].clone().into()), -a.c1);
+    });
+}
+
+#[test]
+fn test_fp2_invert_or_zero() {
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp2Chip::<_, _, Fq2>::new(&fp_chip);
+
+        // `u = (0, 1)` is nonzero even though one coordinate is zero
+        for a in [Fq2::zero(), Fq2 { c0: Fq::zero(), c1: Fq::one() }, Fq2::random(OsRng)] {
+            let a_assigned = chip.load_private(ctx, a);
+            let (inv, is_zero) = chip.invert_or_zero(ctx, a_assigned);
+            assert_eq!(is_zero.value(), &Fr::from(bool::from(a.is_zero()) as u64));
+            let expected = Option::<Fq2>::from(a.invert()).unwrap_or(Fq2::zero());
+            assert_eq!(chip.get_assigned_value(&inv.into()), expected);
+        }
+    });
+}
+
+#[test]
+fn test_fp2_norm() {
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp2Chip::<_, _, Fq2>::new(&fp_chip);
+
+        for a in [Fq2::zero(), Fq2 { c0: Fq::zero(), c1: Fq::one() }, Fq2::random(OsRng)] {
+            let conj = Fq2 { c0: a.c0, c1: -a.c1 };
+            let expected = a * conj;
+            assert_eq!(expected.c1, Fq::zero());
+
+            let a_assigned = chip.load_private(ctx, a);
+            let norm = chip.norm(ctx, &a_assigned);
+            assert_eq!(fp_chip.get_assigned_value(&norm.into()), expected.c0);
+            let prod = chip.mul_by_conjugate(ctx, &a_assigned);
+            assert_eq!(chip.get_assigned_value(&prod.clone().into()), expected);
+
+            // agrees with the generic product
+            let a_conj = chip.conjugate(ctx, a_assigned.clone());
+            let generic = chip.mul(ctx, &a_assigned, &a_conj);
+            chip.assert_equal(ctx, prod, generic);
+        }
+    });
+}
+
+#[test]
+fn test_fp2_mul_by_nonresidue() {
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp2Chip::<_, _, Fq2>::new(&fp_chip);
+
+        let xi = Fq2 { c0: Fq::from(9), c1: Fq::one() };
+        for a in [Fq2::zero(), Fq2::one(), Fq2::random(OsRng), Fq2::random(OsRng)] {
+            let a_assigned = chip.load_private(ctx, a);
+            let out = chip.mul_by_nonresidue::<9>(ctx, a_assigned);
+            let out = chip.get_assigned_value(&out.into());
+            let mut expected = a;
+            expected.mul_by_nonresidue();
+            assert_eq!(out, expected);
+            assert_eq!(out, a * xi);
+        }
+    });
+}
@@ -0,0 +1,49 @@
+use crate::ff::Field as _;
+use crate::fields::fp::FpChip;
+use crate::fields::fp6::Fp6Chip;
+use crate::fields::{FieldChip, FieldExtConstructor};
+use crate::halo2_proofs::halo2curves::bn256::{Fq, Fq2, Fq6};
+use halo2_base::utils::testing::base_test;
+use rand_core::OsRng;
+
+const XI_0: i64 = 9;
+
+#[test]
+fn test_fp6() {
+    let a = Fq6::random(OsRng);
+    let b = Fq6::random(OsRng);
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp6Chip::<_, _, Fq6, XI_0>::new(&fp_chip);
+
+        let [a_assigned, b_assigned] = [a, b].map(|x| chip.load_private(ctx, x));
+        let c = chip.mul(ctx, a_assigned.clone(), b_assigned.clone());
+        assert_eq!(chip.get_assigned_value(&c.into()), a * b);
+        let a_sq = chip.square(ctx, a_assigned.clone());
+        assert_eq!(chip.get_assigned_value(&a_sq.into()), a.square());
+
+        let sum = chip.add_no_carry(ctx, a_assigned.clone(), b_assigned.clone());
+        assert_eq!(chip.get_assigned_value(&sum), a + b);
+        let diff = chip.sub_no_carry(ctx, a_assigned.clone(), b_assigned);
+        assert_eq!(chip.get_assigned_value(&diff), a - b);
+
+        // v = 0 + 1 * v + 0 * v^2
+        let v = Fq6 { c0: Fq2::zero(), c1: Fq2::one(), c2: Fq2::zero() };
+        let a_v = chip.mul_by_nonresidue(ctx, a_assigned);
+        assert_eq!(chip.get_assigned_value(&a_v.into()), a * v);
+    });
+}
+
+#[test]
+fn test_fp6_max_coeffs() {
+    // all coordinates `p - 1`, the largest reduced values
+    let a = Fq6::new([-Fq::one(); 6]);
+    base_test().k(12).lookup_bits(11).run(|ctx, range| {
+        let fp_chip = FpChip::<_, Fq>::new(range, 88, 3);
+        let chip = Fp6Chip::<_, _, Fq6, XI_0>::new(&fp_chip);
+
+        let a_assigned = chip.load_private(ctx, a);
+        let a_sq = chip.square(ctx, a_assigned);
+        assert_eq!(chip.get_assigned_value(&a_sq.into()), a.square());
+    });
+}
@@ -1,2 +1,5 @@
 pub mod fp;
 pub mod fp12;
+pub mod fp2;
+pub mod fp6;
+pub mod vector;
@@ -65,6 +65,40 @@ impl<T> IntoIterator for FieldVector<T> {
     }
 }
 
+impl<'a, T> IntoIterator for &'a FieldVector<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T> FromIterator<T> for FieldVector<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        FieldVector(iter.into_iter().collect())
+    }
+}
+
+impl<T> FieldVector<T> {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.0.iter()
+    }
+
+    /// Applies `f` to every coordinate, keeping the `FieldVector` wrapper.
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> FieldVector<U> {
+        self.0.into_iter().map(f).collect()
+    }
+}
+
 /// Contains common functionality for vector operations that can be derived from those of the underlying `FpChip`
 #[derive(Clone, Copy, Debug)]
 pub struct FieldVectorChip<'fp, F: BigPrimeField, FpChip: FieldChip<F>> {
@@ -116,6 +150,29 @@ where
         )
     }
 
+    /// Coordinate-wise `select_by_indicator` of the vectors in `v`, which must all have the same length.
+    pub fn select_by_indicator<FP>(
+        &self,
+        ctx: &mut Context<F>,
+        v: &impl AsRef<[FieldVector<FP>]>,
+        coeffs: &[AssignedValue<F>],
+    ) -> FieldVector<FP>
+    where
+        FP: Clone,
+        FpChip: Selectable<F, FP>,
+    {
+        let v = v.as_ref();
+        let degree = v[0].0.len();
+        FieldVector(
+            (0..degree)
+                .map(|i| {
+                    let coords = v.iter().map(|a| a[i].clone()).collect::<Vec<_>>();
+                    self.fp_chip.select_by_indicator(ctx, &coords, coeffs)
+                })
+                .collect(),
+        )
+    }
+
     pub fn load_private<FieldExt, const DEGREE: usize>(
         &self,
         ctx: &mut Context<F>,
@@ -196,6 +253,28 @@ where
         FieldVector(a.into_iter().map(|a| self.fp_chip.negate(ctx, a)).collect())
     }
 
+    /// Negates the coordinates of `a` at indices `k` with `(k / stride) % 2 == 1`, leaving the rest unchanged.
+    ///
+    /// This is the conjugation of a quadratic extension `K[t] / (t^2 - c)` when the coordinates are laid out so that
+    /// the coefficients of `t` form the odd blocks of `stride` coordinates:
+    /// * `Fp2 = Fp[u] / (u^2 + 1)` stored as `(a_0, a_1)` uses `stride = 1` (`u -> -u`);
+    /// * `Fp12 = Fp2[w] / (w^6 - u - xi)` stored as `(a_{00}, ..., a_{50}, a_{01}, ..., a_{51})` uses `stride = 1` (`w -> -w`),
+    ///   since `w^i` has an odd index in both halves exactly when `i` is odd.
+    pub fn conjugate(
+        &self,
+        ctx: &mut Context<F>,
+        a: impl IntoIterator<Item = FpChip::FieldPoint>,
+        stride: usize,
+    ) -> FieldVector<FpChip::FieldPoint> {
+        assert!(stride != 0);
+        FieldVector(
+            a.into_iter()
+                .enumerate()
+                .map(|(k, a)| if (k / stride) % 2 == 1 { self.fp_chip.negate(ctx, a) } else { a })
+                .collect(),
+        )
+    }
+
     pub fn scalar_mul_no_carry<A>(
         &self,
         ctx: &mut Context<F>,
@@ -347,12 +426,40 @@ where
         a: impl IntoIterator<Item = FpChip::FieldPoint>,
         b: impl IntoIterator<Item = FpChip::FieldPoint>,
     ) {
-        for (a_coeff, b_coeff) in a.into_iter().zip(b) {
+        for (a_coeff, b_coeff) in a.into_iter().zip_eq(b) {
             self.fp_chip.assert_equal(ctx, a_coeff, b_coeff)
         }
     }
 }
 
+/// Coordinate-wise selection, shared by every extension field chip built on a [`FieldVectorChip`].
+impl<'fp, F, FpChip, Pt> Selectable<F, FieldVector<Pt>> for FieldVectorChip<'fp, F, FpChip>
+where
+    F: BigPrimeField,
+    FpChip: PrimeFieldChip<F> + Selectable<F, Pt>,
+    FpChip::FieldType: BigPrimeField,
+    Pt: Clone,
+{
+    fn select(
+        &self,
+        ctx: &mut Context<F>,
+        a: FieldVector<Pt>,
+        b: FieldVector<Pt>,
+        sel: AssignedValue<F>,
+    ) -> FieldVector<Pt> {
+        FieldVectorChip::select(self, ctx, a, b, sel)
+    }
+
+    fn select_by_indicator(
+        &self,
+        ctx: &mut Context<F>,
+        a: &impl AsRef<[FieldVector<Pt>]>,
+        coeffs: &[AssignedValue<F>],
+    ) -> FieldVector<Pt> {
+        FieldVectorChip::select_by_indicator(self, ctx, a, coeffs)
+    }
+}
+
 #[macro_export]
 macro_rules! impl_field_ext_chip_common {
     // Implementation of the functions in `FieldChip` trait for field extensions that can be derived from `FieldVectorChip`
@@ -503,3 +610,38 @@ macro_rules! impl_field_ext_chip_common {
         }
     };
 }
+
+#[macro_export]
+macro_rules! impl_field_ext_chip_selectable {
+    // Implementation of `Selectable` for field extension chips wrapping a `FieldVectorChip`, by delegating to it.
+    // The optional `const` parameter is for chips that are generic over the non-residue, e.g. `XI_0`.
+    ($chip:ident<$fp_ext:ident $(, const $xi:ident: $xi_ty:ty)?>) => {
+        impl<'a, F, FpChip, $fp_ext, Pt $(, const $xi: $xi_ty)?> Selectable<F, FieldVector<Pt>>
+            for $chip<'a, F, FpChip, $fp_ext $(, $xi)?>
+        where
+            F: BigPrimeField,
+            FpChip: PrimeFieldChip<F> + Selectable<F, Pt>,
+            FpChip::FieldType: BigPrimeField,
+            Pt: Clone,
+        {
+            fn select(
+                &self,
+                ctx: &mut Context<F>,
+                a: FieldVector<Pt>,
+                b: FieldVector<Pt>,
+                sel: AssignedValue<F>,
+            ) -> FieldVector<Pt> {
+                self.0.select(ctx, a, b, sel)
+            }
+
+            fn select_by_indicator(
+                &self,
+                ctx: &mut Context<F>,
+                a: &impl AsRef<[FieldVector<Pt>]>,
+                coeffs: &[AssignedValue<F>],
+            ) -> FieldVector<Pt> {
+                self.0.select_by_indicator(ctx, a, coeffs)
+            }
+        }
+    };
+}
@@ -5,9 +5,12 @@
 
 pub mod bigint;
 pub mod ecc;
+pub mod edwards;
 pub mod fields;
 
 pub mod bn254;
+#[cfg(feature = "ed25519")]
+pub mod ed25519;
 pub mod grumpkin;
 pub mod secp256k1;
 
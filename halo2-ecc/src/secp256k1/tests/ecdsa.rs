@@ -13,12 +13,19 @@ use crate::halo2_proofs::{
 };
 use crate::secp256k1::{FpChip, FqChip};
 use crate::{
-    ecc::{ecdsa::ecdsa_verify_no_pubkey_check, EccChip},
+    ecc::{
+        ecdsa::{
+            ecdsa_verify_batch, ecdsa_verify_low_s_no_pubkey_check, ecdsa_verify_no_pubkey_check,
+            recover_pubkey, BatchEcdsaInput,
+        },
+        EccChip,
+    },
     fields::FieldChip,
 };
 use halo2_base::gates::RangeChip;
 use halo2_base::utils::{biguint_to_fe, fe_to_biguint, modulus, BigPrimeField};
 use halo2_base::Context;
+use rand_core::RngCore;
 use serde::{Deserialize, Serialize};
 use test_log::test;
 
@@ -144,3 +151,190 @@ fn bench_secp256k1_ecdsa() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+fn recover_pubkey_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    params: CircuitParams,
+    input: ECDSAInput,
+    recovery_id: u64,
+) -> Secp256k1Affine {
+    let fp_chip = FpChip::<F>::new(range, params.limb_bits, params.num_limbs);
+    let fq_chip = FqChip::<F>::new(range, params.limb_bits, params.num_limbs);
+
+    let [m, r, s] = [input.msghash, input.r, input.s].map(|x| fq_chip.load_private(ctx, x));
+    let recovery_id = ctx.load_witness(F::from(recovery_id));
+
+    let ecc_chip = EccChip::<F, FpChip<F>>::new(&fp_chip);
+    let pk =
+        recover_pubkey::<F, Fp, Fq, Secp256k1Affine>(&ecc_chip, ctx, r, s, m, recovery_id, 4, 4);
+    let [x, y] = [pk.x, pk.y].map(|c| fp_chip.get_assigned_value(&c.into()));
+    Secp256k1Affine::from_xy(x, y).unwrap()
+}
+
+#[test]
+fn test_secp256k1_ecdsa_recover_pubkey() {
+    let path = "configs/secp256k1/ecdsa_circuit.config";
+    let params: CircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..4 {
+        let sk = <Secp256k1Affine as CurveAffine>::ScalarExt::random(rng.clone());
+        let pk = Secp256k1Affine::from(Secp256k1Affine::generator() * sk);
+        let msghash = <Secp256k1Affine as CurveAffine>::ScalarExt::random(rng.clone());
+        let k = <Secp256k1Affine as CurveAffine>::ScalarExt::random(&mut rng);
+
+        let r_point =
+            Secp256k1Affine::from(Secp256k1Affine::generator() * k).coordinates().unwrap();
+        let x_bigint = fe_to_biguint(r_point.x());
+        let r = biguint_to_fe::<Fq>(&(&x_bigint % modulus::<Fq>()));
+        let s = k.invert().unwrap() * (msghash + (r * sk));
+        // bit 0: parity of R.y, bit 1: R.x >= n
+        let y_is_odd = fe_to_biguint(r_point.y()).bit(0) as u64;
+        let x_overflows = (x_bigint >= modulus::<Fq>()) as u64;
+        let recovery_id = y_is_odd | (x_overflows << 1);
+
+        let input = ECDSAInput { r, s, msghash, pk };
+        let recovered = base_test()
+            .k(params.degree)
+            .lookup_bits(params.lookup_bits)
+            .run(|ctx, range| recover_pubkey_test(ctx, range, params, input, recovery_id));
+        assert_eq!(recovered, pk);
+    }
+}
+
+#[test]
+fn test_secp256k1_ecdsa_recover_pubkey_wrong_parity() {
+    let path = "configs/secp256k1/ecdsa_circuit.config";
+    let params: CircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let input = random_ecdsa_input(&mut rng);
+    // flipping the parity recovers the key for `-R`, which is a different (valid) key
+    let recovered = [0, 1].map(|recovery_id| {
+        base_test()
+            .k(params.degree)
+            .lookup_bits(params.lookup_bits)
+            .run(|ctx, range| recover_pubkey_test(ctx, range, params, input, recovery_id))
+    });
+    assert_ne!(recovered[0], recovered[1]);
+    assert!(recovered.contains(&input.pk));
+}
+
+fn ecdsa_low_s_test<F: BigPrimeField>(
+    ctx: &mut Context<F>,
+    range: &RangeChip<F>,
+    params: CircuitParams,
+    input: ECDSAInput,
+) -> F {
+    let fp_chip = FpChip::<F>::new(range, params.limb_bits, params.num_limbs);
+    let fq_chip = FqChip::<F>::new(range, params.limb_bits, params.num_limbs);
+
+    let [m, r, s] = [input.msghash, input.r, input.s].map(|x| fq_chip.load_private(ctx, x));
+
+    let ecc_chip = EccChip::<F, FpChip<F>>::new(&fp_chip);
+    let pk = ecc_chip.load_private_unchecked(ctx, (input.pk.x, input.pk.y));
+    let res = ecdsa_verify_low_s_no_pubkey_check::<F, Fp, Fq, Secp256k1Affine>(
+        &ecc_chip, ctx, pk, r, s, m, 4, 4,
+    );
+    *res.value()
+}
+
+#[test]
+fn test_secp256k1_ecdsa_low_s() {
+    let path = "configs/secp256k1/ecdsa_circuit.config";
+    let params: CircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut input = random_ecdsa_input(&mut rng);
+    // `(r, s)` and `(r, n - s)` are both valid signatures; make `input.s` the low one
+    let half_n = modulus::<Fq>() >> 1;
+    if fe_to_biguint(&input.s) > half_n {
+        input.s = -input.s;
+    }
+    let high_input = ECDSAInput { s: -input.s, ..input };
+
+    let run = |input: ECDSAInput, low_s: bool| {
+        base_test().k(params.degree).lookup_bits(params.lookup_bits).run(|ctx, range| {
+            if low_s {
+                ecdsa_low_s_test(ctx, range, params, input)
+            } else {
+                ecdsa_test(ctx, range, params, input)
+            }
+        })
+    };
+    assert_eq!(run(input, true), Fr::ONE);
+    assert_eq!(run(high_input, false), Fr::ONE);
+    assert_eq!(run(high_input, true), Fr::ZERO);
+}
+
+/// Returns a random signature together with its nonce point `R`.
+fn random_ecdsa_input_with_nonce(rng: &mut StdRng) -> (ECDSAInput, Secp256k1Affine) {
+    let sk = <Secp256k1Affine as CurveAffine>::ScalarExt::random(&mut *rng);
+    let pk = Secp256k1Affine::from(Secp256k1Affine::generator() * sk);
+    let msghash = <Secp256k1Affine as CurveAffine>::ScalarExt::random(&mut *rng);
+    let k = <Secp256k1Affine as CurveAffine>::ScalarExt::random(&mut *rng);
+
+    let R = Secp256k1Affine::from(Secp256k1Affine::generator() * k);
+    let x_bigint = fe_to_biguint(R.coordinates().unwrap().x());
+    let r = biguint_to_fe::<Fq>(&(x_bigint % modulus::<Fq>()));
+    let s = k.invert().unwrap() * (msghash + (r * sk));
+    (ECDSAInput { r, s, msghash, pk }, R)
+}
+
+fn ecdsa_batch_test(
+    params: CircuitParams,
+    inputs: Vec<(ECDSAInput, Secp256k1Affine)>,
+    challenge: Fr,
+) -> Fr {
+    base_test().k(params.degree).lookup_bits(params.lookup_bits).run_builder(|pool, range| {
+        let fp_chip = FpChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let fq_chip = FqChip::<Fr>::new(range, params.limb_bits, params.num_limbs);
+        let ecc_chip = EccChip::<Fr, FpChip<Fr>>::new(&fp_chip);
+
+        let ctx = pool.main();
+        let sigs = inputs
+            .into_iter()
+            .map(|(input, R)| {
+                let [msghash, r, s] =
+                    [input.msghash, input.r, input.s].map(|x| fq_chip.load_private(ctx, x));
+                let pubkey =
+                    ecc_chip.load_private::<Secp256k1Affine>(ctx, (input.pk.x, input.pk.y));
+                let R = ecc_chip.load_private::<Secp256k1Affine>(ctx, (R.x, R.y));
+                BatchEcdsaInput { pubkey, r, s, msghash, R }
+            })
+            .collect();
+        let challenge = ctx.load_witness(challenge);
+        let res =
+            ecdsa_verify_batch::<Fr, Fp, Fq, Secp256k1Affine>(&ecc_chip, pool, sigs, challenge);
+        *res.value()
+    })
+}
+
+#[test]
+fn test_secp256k1_ecdsa_batch() {
+    let path = "configs/secp256k1/ecdsa_circuit.config";
+    let params: CircuitParams = serde_json::from_reader(
+        File::open(path).unwrap_or_else(|e| panic!("{path} does not exist: {e:?}")),
+    )
+    .unwrap();
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let inputs = (0..3).map(|_| random_ecdsa_input_with_nonce(&mut rng)).collect::<Vec<_>>();
+    let challenge = Fr::from(rng.next_u64());
+    assert_eq!(ecdsa_batch_test(params, inputs.clone(), challenge), Fr::ONE);
+
+    // one bad signature makes the whole batch fail
+    let mut bad_inputs = inputs;
+    bad_inputs[1].0.msghash += Fq::ONE;
+    assert_eq!(ecdsa_batch_test(params, bad_inputs, challenge), Fr::ZERO);
+}